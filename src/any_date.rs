@@ -0,0 +1,355 @@
+use std::convert::TryFrom;
+
+use crate::arithmetic::CalendarArithmetic;
+use crate::babylonian::BabylonianDate;
+use crate::cal::Calendar;
+use crate::format::DateFormat;
+use crate::hebrew::{HebrewDate, HebrewMonth};
+use crate::invalid_year_err::CalendarError;
+use crate::julian_day::JulianDayNumber;
+use crate::julian_gregorian::{Era, GregorianCalendar, JulianCalendar, RomanMonth};
+use crate::mars::DarianDate;
+use crate::sidereal_solar::{BengaliDate, NepaliDate, TamilDate};
+use crate::tibetan::TibetanDate;
+
+/*
+A date on any of the calendars in the crate.
+
+With the serde feature it is stored with the calendar named and the month
+written out, the way Calendar::new takes it:
+{"calendar":"hebrew","year":5785,"month":"Nisan","day":15}
+Julian, Gregorian and Babylonian dates also have an "era" ("AD" or "BC"),
+and the second of two Tibetan days with the same number has
+"leap_day":true. Reading one back goes through Calendar::new, so unknown
+calendars and month names are rejected
+ */
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(into = "TaggedDate", try_from = "TaggedDate")
+)]
+pub enum AnyDate {
+    Hebrew(HebrewDate),
+    Julian(JulianCalendar),
+    Gregorian(GregorianCalendar),
+    Tibetan(TibetanDate),
+    Babylonian(BabylonianDate),
+    Nepali(NepaliDate),
+    Bengali(BengaliDate),
+    Tamil(TamilDate),
+    Darian(DarianDate),
+}
+
+impl AnyDate {
+    // the calendar's id, as used by js_api and the registry
+    pub fn calendar(&self) -> &'static str {
+        match self {
+            AnyDate::Hebrew(_) => "Hebrew",
+            AnyDate::Julian(_) => "Julian",
+            AnyDate::Gregorian(_) => "Gregorian",
+            AnyDate::Tibetan(_) => "Tibetan",
+            AnyDate::Babylonian(_) => "Babylonian",
+            AnyDate::Nepali(_) => "Nepali",
+            AnyDate::Bengali(_) => "Bengali",
+            AnyDate::Tamil(_) => "Tamil",
+            AnyDate::Darian(_) => "Darian",
+        }
+    }
+
+    // the date with the given fields, as taken by Calendar::new, on the
+    // calendar with the given id (ignoring case)
+    pub fn new(calendar: &str, day: u32, month: String, year: i32, era: String) -> Result<AnyDate, CalendarError> {
+        match calendar.to_lowercase().as_str() {
+            "hebrew" => Ok(AnyDate::Hebrew(HebrewDate::new(day, month, year, era)?)),
+            "julian" => Ok(AnyDate::Julian(JulianCalendar::new(day, month, year, era)?)),
+            "gregorian" => Ok(AnyDate::Gregorian(GregorianCalendar::new(day, month, year, era)?)),
+            "tibetan" => Ok(AnyDate::Tibetan(TibetanDate::new(day, month, year, era)?)),
+            "babylonian" => Ok(AnyDate::Babylonian(BabylonianDate::new(day, month, year, era)?)),
+            "nepali" => Ok(AnyDate::Nepali(NepaliDate::new(day, month, year, era)?)),
+            "bengali" => Ok(AnyDate::Bengali(BengaliDate::new(day, month, year, era)?)),
+            "tamil" => Ok(AnyDate::Tamil(TamilDate::new(day, month, year, era)?)),
+            "darian" => Ok(AnyDate::Darian(DarianDate::new(day, month, year, era)?)),
+            _ => Err(CalendarError::UnknownCalendar(String::from(calendar))),
+        }
+    }
+
+    pub fn to_julian_day_number(&self) -> Result<JulianDayNumber, CalendarError> {
+        match self {
+            AnyDate::Hebrew(date) => date.to_julian_day_number(),
+            AnyDate::Julian(date) => date.to_julian_day_number(),
+            AnyDate::Gregorian(date) => date.to_julian_day_number(),
+            AnyDate::Tibetan(date) => date.to_julian_day_number(),
+            AnyDate::Babylonian(date) => date.to_julian_day_number(),
+            AnyDate::Nepali(date) => date.to_julian_day_number(),
+            AnyDate::Bengali(date) => date.to_julian_day_number(),
+            AnyDate::Tamil(date) => date.to_julian_day_number(),
+            AnyDate::Darian(date) => date.to_julian_day_number(),
+        }
+    }
+
+    // the date of `day` on the calendar with the given id (ignoring case)
+    pub fn from_julian_day_number(calendar: &str, day: JulianDayNumber) -> Result<AnyDate, CalendarError> {
+        match calendar.to_lowercase().as_str() {
+            "hebrew" => Ok(AnyDate::Hebrew(HebrewDate::from_julian_day_number(day)?)),
+            "julian" => Ok(AnyDate::Julian(JulianCalendar::from_julian_day_number(day)?)),
+            "gregorian" => Ok(AnyDate::Gregorian(GregorianCalendar::from_julian_day_number(day)?)),
+            "tibetan" => Ok(AnyDate::Tibetan(TibetanDate::from_julian_day_number(day)?)),
+            "babylonian" => Ok(AnyDate::Babylonian(BabylonianDate::from_julian_day_number(day)?)),
+            "nepali" => Ok(AnyDate::Nepali(NepaliDate::from_julian_day_number(day)?)),
+            "bengali" => Ok(AnyDate::Bengali(BengaliDate::from_julian_day_number(day)?)),
+            "tamil" => Ok(AnyDate::Tamil(TamilDate::from_julian_day_number(day)?)),
+            "darian" => Ok(AnyDate::Darian(DarianDate::from_julian_day_number(day)?)),
+            _ => Err(CalendarError::UnknownCalendar(String::from(calendar))),
+        }
+    }
+
+    // see DateFormat::format
+    pub fn format(&self, pattern: &str) -> Result<String, CalendarError> {
+        self.format_in(pattern, "en")
+    }
+
+    // see DateFormat::format_in
+    pub fn format_in(&self, pattern: &str, locale: &str) -> Result<String, CalendarError> {
+        match self {
+            AnyDate::Hebrew(date) => date.format_in(pattern, locale),
+            AnyDate::Julian(date) => date.format_in(pattern, locale),
+            AnyDate::Gregorian(date) => date.format_in(pattern, locale),
+            AnyDate::Tibetan(date) => date.format_in(pattern, locale),
+            AnyDate::Babylonian(date) => date.format_in(pattern, locale),
+            AnyDate::Nepali(date) => date.format_in(pattern, locale),
+            AnyDate::Bengali(date) => date.format_in(pattern, locale),
+            AnyDate::Tamil(date) => date.format_in(pattern, locale),
+            AnyDate::Darian(date) => date.format_in(pattern, locale),
+        }
+    }
+
+    // the same day on another calendar
+    pub fn convert(&self, calendar: &str) -> Result<AnyDate, CalendarError> {
+        AnyDate::from_julian_day_number(calendar, self.to_julian_day_number()?)
+    }
+}
+
+impl std::fmt::Display for AnyDate {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            AnyDate::Hebrew(date) => date.fmt(f),
+            AnyDate::Julian(date) => date.fmt(f),
+            AnyDate::Gregorian(date) => date.fmt(f),
+            AnyDate::Tibetan(date) => date.fmt(f),
+            AnyDate::Babylonian(date) => date.fmt(f),
+            AnyDate::Nepali(date) => date.fmt(f),
+            AnyDate::Bengali(date) => date.fmt(f),
+            AnyDate::Tamil(date) => date.fmt(f),
+            AnyDate::Darian(date) => date.fmt(f),
+        }
+    }
+}
+
+macro_rules! any_date_from {
+    ($($variant:ident($date:ty)),*) => {
+        $(
+            impl From<$date> for AnyDate {
+                fn from(date: $date) -> AnyDate {
+                    AnyDate::$variant(date)
+                }
+            }
+        )*
+    };
+}
+
+any_date_from!(
+    Hebrew(HebrewDate),
+    Julian(JulianCalendar),
+    Gregorian(GregorianCalendar),
+    Tibetan(TibetanDate),
+    Babylonian(BabylonianDate),
+    Nepali(NepaliDate),
+    Bengali(BengaliDate),
+    Tamil(TamilDate),
+    Darian(DarianDate)
+);
+
+// the stored form of an AnyDate
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[derive(Clone, PartialEq, Debug)]
+pub struct TaggedDate {
+    pub calendar: String,
+    pub year: i64,
+    pub month: String,
+    pub day: u32,
+    #[cfg_attr(feature = "serde", serde(default, skip_serializing_if = "Option::is_none"))]
+    pub era: Option<String>,
+    #[cfg_attr(feature = "serde", serde(default, skip_serializing_if = "is_false"))]
+    pub leap_day: bool,
+}
+
+#[cfg(feature = "serde")]
+fn is_false(value: &bool) -> bool {
+    !*value
+}
+
+// the era and year counted from 1 in that era, of an astronomical year
+fn era_year(year: i64) -> (Era, i64) {
+    match year > 0 {
+        true => (Era::AD, year),
+        false => (Era::BC, 1 - year),
+    }
+}
+
+fn roman_fields<T: CalendarArithmetic>(calendar: &str, date: &T) -> TaggedDate {
+    let (year, month, day) = date.fields();
+    let (era, year) = era_year(year);
+    TaggedDate {
+        calendar: String::from(calendar),
+        year,
+        month: month_or_number(RomanMonth::try_from(month).map(|m| m.name()), month),
+        day: day as u32,
+        era: Some(era.to_string()),
+        leap_day: false,
+    }
+}
+
+// the name, or the number if the month does not exist, which reading the
+// date back rejects
+fn month_or_number(name: Result<&str, CalendarError>, month: u8) -> String {
+    match name {
+        Ok(name) if !name.is_empty() => String::from(name),
+        _ => month.to_string(),
+    }
+}
+
+fn plain_fields(calendar: &str, year: u32, month: String, day: u8) -> TaggedDate {
+    TaggedDate {
+        calendar: String::from(calendar),
+        year: year as i64,
+        month,
+        day: day as u32,
+        era: None,
+        leap_day: false,
+    }
+}
+
+impl From<AnyDate> for TaggedDate {
+    fn from(date: AnyDate) -> TaggedDate {
+        // stored in lower case, but any case is read back
+        let calendar = date.calendar().to_lowercase();
+        let calendar = calendar.as_str();
+        match date {
+            AnyDate::Hebrew(d) => {
+                let month = HebrewMonth::from_number(d.month, d.year).map(|m| m.name());
+                plain_fields(calendar, d.year, month_or_number(month, d.month), d.day)
+            }
+            AnyDate::Julian(d) => roman_fields(calendar, &d),
+            AnyDate::Gregorian(d) => roman_fields(calendar, &d),
+            AnyDate::Tibetan(d) => {
+                let month = match d.is_leap_month {
+                    true => format!("Leap Month {}", d.month),
+                    false => format!("Month {}", d.month),
+                };
+                TaggedDate {
+                    leap_day: d.is_leap_day,
+                    ..plain_fields(calendar, d.year, month, d.day)
+                }
+            }
+            AnyDate::Babylonian(d) => TaggedDate {
+                era: Some(d.era.to_string()),
+                ..plain_fields(calendar, d.year, month_or_number(Ok(&d.month_name()), d.month), d.day)
+            },
+            AnyDate::Nepali(d) => plain_fields(calendar, d.year, month_or_number(Ok(d.month_name()), d.month), d.day),
+            AnyDate::Bengali(d) => plain_fields(calendar, d.year, month_or_number(Ok(d.month_name()), d.month), d.day),
+            AnyDate::Tamil(d) => plain_fields(calendar, d.year, month_or_number(Ok(d.month_name()), d.month), d.day),
+            AnyDate::Darian(d) => plain_fields(calendar, d.year, month_or_number(Ok(d.month_name()), d.month), d.sol),
+        }
+    }
+}
+
+impl TryFrom<TaggedDate> for AnyDate {
+    type Error = CalendarError;
+
+    fn try_from(tagged: TaggedDate) -> Result<Self, Self::Error> {
+        let year = match i32::try_from(tagged.year) {
+            Ok(year) => year,
+            Err(_) => return Err(CalendarError::Overflow),
+        };
+        let era = tagged.era.unwrap_or_default();
+        let date = AnyDate::new(&tagged.calendar, tagged.day, tagged.month, year, era)?;
+        match date {
+            AnyDate::Tibetan(mut d) => {
+                // only some days are repeated, so check the day again
+                d.is_leap_day = tagged.leap_day;
+                d.to_julian_day_number()?;
+                Ok(AnyDate::Tibetan(d))
+            }
+            _ => Ok(date),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn tibetan(year: i64, month: &str, day: u32, leap_day: bool) -> TaggedDate {
+        TaggedDate {
+            calendar: String::from("tibetan"),
+            year,
+            month: String::from(month),
+            day,
+            era: None,
+            leap_day,
+        }
+    }
+
+    #[test]
+    fn tibetan_leap_day() {
+        // 11 Month 1 2150 is repeated, the second one being 3 March 2023
+        let date = AnyDate::try_from(tibetan(2150, "Month 1", 11, true)).unwrap();
+        assert_eq!(date.to_julian_day_number(), Ok(JulianDayNumber::new(2460007)));
+        assert_eq!(TaggedDate::from(date), tibetan(2150, "Month 1", 11, true));
+        assert_eq!(AnyDate::try_from(tibetan(2151, "Month 1", 1, true)), Err(CalendarError::InvalidInput));
+        assert!(AnyDate::try_from(tibetan(2151, "Month 1", 1, false)).is_ok());
+    }
+}
+
+#[cfg(all(test, feature = "serde"))]
+mod serde_tests {
+    use super::*;
+
+    fn round_trip(date: AnyDate, json: &str) {
+        assert_eq!(serde_json::to_string(&date).unwrap(), json);
+        assert_eq!(serde_json::from_str::<AnyDate>(json).unwrap(), date);
+    }
+
+    #[test]
+    fn hebrew() {
+        let date = AnyDate::new("hebrew", 15, String::from("Nisan"), 5785, String::new()).unwrap();
+        round_trip(date, r#"{"calendar":"hebrew","year":5785,"month":"Nisan","day":15}"#);
+        let date = AnyDate::new("hebrew", 14, String::from("Adar II"), 5784, String::new()).unwrap();
+        round_trip(date, r#"{"calendar":"hebrew","year":5784,"month":"Adar II","day":14}"#);
+
+        // 5785 is a common year
+        let json = r#"{"calendar":"hebrew","year":5785,"month":"Adar I","day":14}"#;
+        assert!(serde_json::from_str::<AnyDate>(json).is_err());
+    }
+
+    #[test]
+    fn julian_bc() {
+        let date = AnyDate::new("julian", 15, String::from("March"), 44, String::from("BC")).unwrap();
+        round_trip(date, r#"{"calendar":"julian","year":44,"month":"March","day":15,"era":"BC"}"#);
+        assert_eq!(date.to_julian_day_number(), Ok(JulianDayNumber::new(1705426)));
+    }
+
+    #[test]
+    fn tibetan_leap_day() {
+        let day = |is_leap_day| TibetanDate { year: 2150, month: 1, is_leap_month: false, day: 11, is_leap_day };
+        let date = AnyDate::Tibetan(day(true));
+        round_trip(date, r#"{"calendar":"tibetan","year":2150,"month":"Month 1","day":11,"leap_day":true}"#);
+        let date = AnyDate::Tibetan(day(false));
+        round_trip(date, r#"{"calendar":"tibetan","year":2150,"month":"Month 1","day":11}"#);
+
+        // 1 Month 1 2151 is not repeated
+        let json = r#"{"calendar":"tibetan","year":2151,"month":"Month 1","day":1,"leap_day":true}"#;
+        assert!(serde_json::from_str::<AnyDate>(json).is_err());
+    }
+}
@@ -0,0 +1,564 @@
+use crate::invalid_year_err::CalendarError;
+use crate::cal::{Calendar, Weekday};
+use crate::range::DateRange;
+use crate::julian_day::JulianDayNumber;
+use std::convert::TryFrom;
+
+/*
+What to do when adding months or years reaches a day that its month does
+not have, such as 30 Cheshvan in a year where Cheshvan has 29 days or
+29 February in a common year
+ */
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum OverflowPolicy {
+    // use the last day of the month (28 February)
+    Clamp,
+    // return CalendarError::DayOutOfRange
+    Reject,
+    // carry the extra days into the next month (1 March)
+    RollOver,
+}
+
+/*
+Adding days, months and years to dates.
+
+Adding days goes through the julian day, so it works the same way for every
+calendar. Months and years are added on the calendar itself, which needs
+the following from each calendar:
+
+fields: the year, the position of the month within the year and the day.
+Years use astronomical numbering where the calendar has eras (1 BC is year
+0, 2 BC is year -1). Month positions start at 1 and count leap months, so
+Adar II is month 7 of a Hebrew leap year
+
+from_fields: builds a date from the same three values. It does not need to
+check that the day exists
+
+month_count: number of months in a year, counting leap months
+
+MONTH_CYCLE: the years and months of a cycle after which month_count
+repeats, such as (1, 12) for a calendar without leap months or (19, 235)
+for the Hebrew calendar. add_months uses it to step over whole cycles at
+once. Calendars without one have their year counted one at a time
+
+month_in_year: position in `to_year` of the month which is at `month` in
+`from_year`, used when adding years. Calendars with leap months override
+this so a date keeps its named month, and a leap month becomes its regular
+month in a year without it
+
+month_length: highest day number of a month. Defaults to the number of
+days between the start of the month and the start of the next one
+
+is_leap_year: whether the date's year has a leap day, leap month or other
+extra days, whichever the calendar uses
+
+month_days: every day of the month containing a date, in order, with the
+julian day of the first. This is what DateRange uses to step through days.
+Defaults to day numbers 1 to month_length. Calendars which skip or repeat
+day numbers override this with month_days_by_julian_day
+
+The remaining methods are queries and arithmetic built on these
+ */
+pub trait CalendarArithmetic: Calendar + Sized {
+    fn fields(&self) -> (i64, u8, u8);
+
+    fn from_fields(year: i64, month: u8, day: u8) -> Result<Self, CalendarError>;
+
+    fn month_count(year: i64) -> Result<u8, CalendarError>;
+
+    const MONTH_CYCLE: Option<(i64, i64)> = None;
+
+    fn month_in_year(_from_year: i64, month: u8, _to_year: i64) -> Result<u8, CalendarError> {
+        Ok(month)
+    }
+
+    fn month_length(year: i64, month: u8) -> Result<u8, CalendarError> {
+        let start = Self::from_fields(year, month, 1)?.to_julian_day_number()?;
+        let (next_year, next_month) = match month >= Self::month_count(year)? {
+            true => (year + 1, 1),
+            false => (year, month + 1),
+        };
+        let end = Self::from_fields(next_year, next_month, 1)?.to_julian_day_number()?;
+        Ok(end.days_since(start) as u8)
+    }
+
+    fn month_days(date: &Self, julian_day: JulianDayNumber) -> Result<(JulianDayNumber, Vec<Self>), CalendarError> {
+        let (year, month, day) = date.fields();
+        let days = (1..=Self::month_length(year, month)?)
+            .map(|d| Self::from_fields(year, month, d))
+            .collect::<Result<Vec<Self>, CalendarError>>()?;
+        Ok((julian_day.offset(1 - day as i64)?, days))
+    }
+
+    fn is_leap_year(&self) -> bool;
+
+    fn months_in_year(&self) -> Result<u8, CalendarError> {
+        Self::month_count(self.fields().0)
+    }
+
+    // number of days in the date's month, which can be fewer than the highest
+    // day number on calendars that skip day numbers
+    fn days_in_month(&self) -> Result<u8, CalendarError> {
+        let (year, month, _) = self.fields();
+        let (next_year, next_month) = match month >= Self::month_count(year)? {
+            true => (year + 1, 1),
+            false => (year, month + 1),
+        };
+        Ok(month_start::<Self>(next_year, next_month)?.days_since(month_start::<Self>(year, month)?) as u8)
+    }
+
+    fn days_in_year(&self) -> Result<u16, CalendarError> {
+        let year = self.fields().0;
+        Ok(month_start::<Self>(year + 1, 1)?.days_since(month_start::<Self>(year, 1)?) as u16)
+    }
+
+    // starts at 1 for the first day of the year
+    fn day_of_year(&self) -> Result<u16, CalendarError> {
+        let year = self.fields().0;
+        Ok((self.to_julian_day_number()?.days_since(month_start::<Self>(year, 1)?) + 1) as u16)
+    }
+
+    /*
+    Week of the year, starting at 1 for the week containing the first day of
+    the year. Weeks run from Sunday to Saturday, as Weekday numbers them, so
+    week 2 starts on the first Sunday after the year begins. This is not the
+    ISO 8601 week, which starts on Monday and can begin the year in the last
+    week of the year before
+     */
+    fn week_of_year(&self) -> Result<u8, CalendarError> {
+        let first = month_start::<Self>(self.fields().0, 1)?;
+        let days = self.to_julian_day_number()?.days_since(first) + Weekday::from_julian_day_number(first).number() as i128;
+        Ok((days / 7 + 1) as u8)
+    }
+
+    // every day of the date's month
+    fn month_iter(&self) -> Result<DateRange<Self>, CalendarError> where Self: Clone {
+        let (start, days) = Self::month_days(self, self.to_julian_day_number()?)?;
+        DateRange::from_month(start, days)
+    }
+
+    // every day of the date's year
+    fn year_iter(&self) -> Result<DateRange<Self>, CalendarError> where Self: Clone {
+        let year = self.fields().0;
+        Ok(DateRange::from_julian_days(month_start::<Self>(year, 1)?, month_start::<Self>(year + 1, 1)?))
+    }
+
+    fn add_days(&self, days: i128) -> Result<Self, CalendarError> {
+        let days = match i64::try_from(days) {
+            Ok(days) => days,
+            Err(_) => return Err(CalendarError::Overflow),
+        };
+        Self::from_julian_day_number(self.to_julian_day_number()?.offset(days)?)
+    }
+
+    fn add_months(&self, months: i64, policy: OverflowPolicy) -> Result<Self, CalendarError> {
+        self.to_julian_day_number()?;
+        let (mut year, month, day) = self.fields();
+
+        let mut month = match (month as i64).checked_add(months) {
+            Some(m) => m,
+            None => return Err(CalendarError::Overflow),
+        };
+        match Self::MONTH_CYCLE {
+            // leave month between 1 and one cycle, so the loops below only
+            // go round a few times
+            Some((cycle_years, cycle_months)) => {
+                let cycles = (month - 1).div_euclid(cycle_months);
+                month = (month - 1).rem_euclid(cycle_months) + 1;
+                year = match cycles.checked_mul(cycle_years).and_then(|y| year.checked_add(y)) {
+                    Some(y) => y,
+                    None => return Err(CalendarError::Overflow),
+                };
+            }
+            // no month is shorter than 27 days, so a longer span would leave
+            // the supported range
+            None => {
+                let range = Self::supported_range();
+                if months.unsigned_abs() as i128 > range.end().days_since(*range.start()) / 27 + 1 {
+                    return Err(CalendarError::Overflow);
+                }
+            }
+        }
+        while month > Self::month_count(year)? as i64 {
+            month -= Self::month_count(year)? as i64;
+            year += 1;
+        }
+        while month < 1 {
+            year -= 1;
+            month += Self::month_count(year)? as i64;
+        }
+
+        resolve(year, month as u8, day, policy)
+    }
+
+    fn add_years(&self, years: i64, policy: OverflowPolicy) -> Result<Self, CalendarError> {
+        self.to_julian_day_number()?;
+        let (year, month, day) = self.fields();
+
+        let to_year = match year.checked_add(years) {
+            Some(y) => y,
+            None => return Err(CalendarError::Overflow),
+        };
+        let month = Self::month_in_year(year, month, to_year)?;
+
+        resolve(to_year, month, day, policy)
+    }
+}
+
+// first day of a month
+fn month_start<T: CalendarArithmetic>(year: i64, month: u8) -> Result<JulianDayNumber, CalendarError> {
+    resolve::<T>(year, month, 1, OverflowPolicy::RollOver)?.to_julian_day_number()
+}
+
+// month_days found by converting every day of the month, for calendars whose
+// day numbers do not follow the julian day one to one. same_month tells
+// whether a date is in the month of the date on julian_day
+pub(crate) fn month_days_by_julian_day<T: Calendar>(
+    julian_day: JulianDayNumber,
+    same_month: impl Fn(&T) -> bool,
+) -> Result<(JulianDayNumber, Vec<T>), CalendarError> {
+    let mut start = julian_day;
+    while same_month(&T::from_julian_day_number(start.offset(-1)?)?) {
+        start = start.offset(-1)?;
+    }
+
+    let mut days = Vec::new();
+    loop {
+        let date = T::from_julian_day_number(start.offset(days.len() as i64)?)?;
+        if !same_month(&date) {
+            break;
+        }
+        days.push(date);
+    }
+    Ok((start, days))
+}
+
+// the date with the given fields, applying the policy if the day does not exist
+fn resolve<T: CalendarArithmetic>(year: i64, month: u8, day: u8, policy: OverflowPolicy) -> Result<T, CalendarError> {
+    let length = T::month_length(year, month)?;
+    if day > length {
+        return match policy {
+            OverflowPolicy::Reject => Err(CalendarError::DayOutOfRange { day: day as u32, max: length as u32 }),
+            OverflowPolicy::Clamp => resolve(year, month, length, policy),
+            OverflowPolicy::RollOver => {
+                let last = resolve::<T>(year, month, length, OverflowPolicy::Clamp)?;
+                last.add_days((day - length) as i128)
+            }
+        };
+    }
+
+    let date = T::from_fields(year, month, day)?;
+    match date.to_julian_day_number() {
+        Ok(_) => Ok(date),
+        // a day number the calendar skips within the month (the Tibetan
+        // calendar does this). Clamping uses the day before it and rolling
+        // over uses the day after it
+        Err(CalendarError::InvalidInput) if policy != OverflowPolicy::Reject => match day {
+            1 => resolve(year, month, 2, policy),
+            _ => {
+                let previous = resolve::<T>(year, month, day - 1, OverflowPolicy::Clamp)?;
+                match policy {
+                    OverflowPolicy::RollOver => previous.add_days(1),
+                    _ => Ok(previous),
+                }
+            }
+        },
+        Err(e) => Err(e),
+    }
+}
+
+/*
+Difference between two dates.
+
+total_days: exact number of days from the first date to the second, found
+through the julian day, so the two dates can be on any calendars
+
+years, months, days: the same difference broken down on a chosen calendar.
+This is measured from the earlier date: whole years are added with
+add_years, then whole months with add_months, and days is whatever is left.
+Both use OverflowPolicy::Clamp, so a day missing from the month reached
+counts as the last day of that month (from 30 Cheshvan to 29 Cheshvan of
+the next year is one year when that Cheshvan has 29 days, and from 29
+February to 28 February of the next year is one year). On the Hebrew
+calendar a year from Adar I or Adar II reaches Adar in a common year, and a
+year from Adar reaches Adar II in a leap year.
+
+All fields are negative when the second date is earlier than the first
+ */
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub struct DateDifference {
+    pub total_days: i128,
+    pub years: i64,
+    pub months: i64,
+    pub days: i64,
+}
+
+// exact number of days from `from` to `to`, negative if `to` is earlier
+pub fn days_between<A: Calendar, B: Calendar>(from: &A, to: &B) -> Result<i128, CalendarError> {
+    Ok(to.to_julian_day_number()?.days_since(from.to_julian_day_number()?))
+}
+
+// difference from `from` to `to`, broken down on the calendar C. For example
+// difference::<HebrewDate, _, _>(&birth, &today) for an age in Hebrew years
+pub fn difference<C, A, B>(from: &A, to: &B) -> Result<DateDifference, CalendarError>
+where
+    C: CalendarArithmetic,
+    A: Calendar,
+    B: Calendar,
+{
+    let from_day = from.to_julian_day_number()?;
+    let to_day = to.to_julian_day_number()?;
+    let (start, end, sign) = match from_day <= to_day {
+        true => (from_day, to_day, 1),
+        false => (to_day, from_day, -1),
+    };
+
+    let start_date = C::from_julian_day_number(start)?;
+    let end_date = C::from_julian_day_number(end)?;
+
+    // the difference between the year numbers is one too many when the end
+    // date is earlier in its year than the start date
+    let mut years = end_date.fields().0 - start_date.fields().0;
+    while (years > 0) && (start_date.add_years(years, OverflowPolicy::Clamp)?.to_julian_day_number()? > end) {
+        years -= 1;
+    }
+    let after_years = start_date.add_years(years, OverflowPolicy::Clamp)?;
+
+    let mut months = 0;
+    while after_years.add_months(months + 1, OverflowPolicy::Clamp)?.to_julian_day_number()? <= end {
+        months += 1;
+    }
+    let after_months = after_years.add_months(months, OverflowPolicy::Clamp)?;
+    let days = end.days_since(after_months.to_julian_day_number()?);
+
+    Ok(DateDifference {
+        total_days: sign as i128 * end.days_since(start),
+        years: sign * years,
+        months: sign * months,
+        days: sign * days as i64,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::hebrew::{HebrewDate, HebrewMonth};
+    use crate::julian_gregorian::GregorianCalendar;
+    use OverflowPolicy::{Clamp, Reject, RollOver};
+
+    fn gregorian(day: u32, month: &str, year: i32) -> GregorianCalendar {
+        GregorianCalendar::new(day, month.into(), year, "AD".into()).unwrap()
+    }
+
+    fn hebrew(day: u8, month: HebrewMonth, year: u32) -> HebrewDate {
+        HebrewDate::from_month(day, month, year).unwrap()
+    }
+
+    #[test]
+    fn leap_day_plus_a_year() {
+        let leap_day = gregorian(29, "February", 2024);
+        assert_eq!(leap_day.add_years(1, Clamp), Ok(gregorian(28, "February", 2025)));
+        assert_eq!(leap_day.add_years(1, Reject), Err(CalendarError::DayOutOfRange { day: 29, max: 28 }));
+        assert_eq!(leap_day.add_years(1, RollOver), Ok(gregorian(1, "March", 2025)));
+        assert_eq!(leap_day.add_years(4, Reject), Ok(gregorian(29, "February", 2028)));
+        assert_eq!(leap_day.add_years(-4, Reject), Ok(gregorian(29, "February", 2020)));
+    }
+
+    #[test]
+    fn gregorian_month_ends() {
+        let date = gregorian(31, "January", 2023);
+        assert_eq!(date.add_months(1, Clamp), Ok(gregorian(28, "February", 2023)));
+        assert_eq!(date.add_months(1, Reject), Err(CalendarError::DayOutOfRange { day: 31, max: 28 }));
+        assert_eq!(date.add_months(1, RollOver), Ok(gregorian(3, "March", 2023)));
+        assert_eq!(gregorian(31, "March", 2024).add_months(-1, Clamp), Ok(gregorian(29, "February", 2024)));
+        assert_eq!(gregorian(31, "December", 2023).add_months(2, Clamp), Ok(gregorian(29, "February", 2024)));
+        assert_eq!(gregorian(15, "January", 2024).add_months(-13, Reject), Ok(gregorian(15, "December", 2022)));
+    }
+
+    #[test]
+    fn adar_plus_a_year() {
+        // Adar of a common year goes to Adar II, and Adar I and II to Adar
+        assert_eq!(hebrew(15, HebrewMonth::Adar, 5783).add_years(1, Reject), Ok(hebrew(15, HebrewMonth::AdarII, 5784)));
+        assert_eq!(hebrew(15, HebrewMonth::AdarI, 5784).add_years(1, Reject), Ok(hebrew(15, HebrewMonth::Adar, 5785)));
+        assert_eq!(hebrew(15, HebrewMonth::AdarII, 5784).add_years(1, Reject), Ok(hebrew(15, HebrewMonth::Adar, 5785)));
+        assert_eq!(hebrew(15, HebrewMonth::AdarII, 5784).add_years(-1, Reject), Ok(hebrew(15, HebrewMonth::Adar, 5783)));
+
+        // Adar I has 30 days and Adar 29
+        let date = hebrew(30, HebrewMonth::AdarI, 5784);
+        assert_eq!(date.add_years(1, Clamp), Ok(hebrew(29, HebrewMonth::Adar, 5785)));
+        assert_eq!(date.add_years(1, Reject), Err(CalendarError::DayOutOfRange { day: 30, max: 29 }));
+        assert_eq!(date.add_years(1, RollOver), Ok(hebrew(1, HebrewMonth::Nisan, 5785)));
+    }
+
+    #[test]
+    fn adar_plus_months() {
+        // counting months passes through both Adars in a leap year
+        assert_eq!(hebrew(30, HebrewMonth::Shevat, 5784).add_months(1, Reject), Ok(hebrew(30, HebrewMonth::AdarI, 5784)));
+        let date = hebrew(30, HebrewMonth::AdarI, 5784);
+        assert_eq!(date.add_months(1, Clamp), Ok(hebrew(29, HebrewMonth::AdarII, 5784)));
+        assert_eq!(date.add_months(1, Reject), Err(CalendarError::DayOutOfRange { day: 30, max: 29 }));
+        assert_eq!(date.add_months(1, RollOver), Ok(hebrew(1, HebrewMonth::Nisan, 5784)));
+        assert_eq!(hebrew(1, HebrewMonth::Nisan, 5784).add_months(-2, Reject), Ok(hebrew(1, HebrewMonth::AdarI, 5784)));
+        assert_eq!(hebrew(1, HebrewMonth::Adar, 5783).add_months(12, Reject), Ok(hebrew(1, HebrewMonth::AdarI, 5784)));
+        assert_eq!(hebrew(1, HebrewMonth::Adar, 5783).add_months(13, Reject), Ok(hebrew(1, HebrewMonth::AdarII, 5784)));
+        assert_eq!(hebrew(1, HebrewMonth::Elul, 5784).add_months(1, Reject), Ok(hebrew(1, HebrewMonth::Tishrei, 5785)));
+    }
+
+    #[test]
+    fn long_month_spans() {
+        let date = gregorian(15, "January", 2024);
+        assert_eq!(date.add_months(12_000_000, Reject), Ok(gregorian(15, "January", 1_002_024)));
+        assert_eq!(date.add_months(i64::MAX, Reject), Err(CalendarError::Overflow));
+        assert!(date.add_months(i64::MAX / 2, Reject).is_err());
+        assert!(date.add_months(i64::MIN, Reject).is_err());
+
+        // a hundred 19-year cycles later Nisan is in the same place
+        let nisan = hebrew(1, HebrewMonth::Nisan, 5784);
+        assert_eq!(nisan.add_months(23_500, Reject), Ok(hebrew(1, HebrewMonth::Nisan, 7684)));
+        assert_eq!(nisan.add_months(-23_500, Reject), Ok(hebrew(1, HebrewMonth::Nisan, 3884)));
+        assert!(nisan.add_months(i64::MAX / 2, Reject).is_err());
+
+        // the Babylonian calendar has no cycle, but a span this long is
+        // rejected before counting the years
+        let nisannu = crate::babylonian::BabylonianDate::new(1, "Nisannu".into(), 311, "BC".into()).unwrap();
+        assert_eq!(nisannu.add_months(i64::MAX / 2, Reject), Err(CalendarError::Overflow));
+        assert_eq!(nisannu.add_months(-(i64::MAX / 2), Reject), Err(CalendarError::Overflow));
+    }
+
+    fn breakdown(years: i64, months: i64, days: i64, total_days: i128) -> DateDifference {
+        DateDifference { total_days, years, months, days }
+    }
+
+    #[test]
+    fn difference_across_adar_i_and_adar_ii() {
+        // Shevat and Adar I have 30 days each in 5784
+        let from = hebrew(1, HebrewMonth::Shevat, 5784);
+        let to = hebrew(15, HebrewMonth::AdarII, 5784);
+        assert_eq!(days_between(&from, &to), Ok(74));
+        assert_eq!(difference::<HebrewDate, _, _>(&from, &to), Ok(breakdown(0, 2, 14, 74)));
+        // 5785 has only Adar, reached a year after either Adar of 5784
+        let purim = hebrew(14, HebrewMonth::Adar, 5785);
+        assert_eq!(
+            difference::<HebrewDate, _, _>(&hebrew(14, HebrewMonth::AdarI, 5784), &purim).map(|d| (d.years, d.months, d.days)),
+            Ok((1, 0, 0))
+        );
+        assert_eq!(
+            difference::<HebrewDate, _, _>(&hebrew(14, HebrewMonth::AdarII, 5784), &purim).map(|d| (d.years, d.months, d.days)),
+            Ok((1, 0, 0))
+        );
+    }
+
+    #[test]
+    fn difference_from_the_end_of_january() {
+        let from = gregorian(31, "January", 2023);
+        let to = gregorian(28, "February", 2023);
+        assert_eq!(difference::<GregorianCalendar, _, _>(&from, &to), Ok(breakdown(0, 1, 0, 28)));
+
+        let from = gregorian(31, "January", 2024);
+        let to = gregorian(29, "February", 2024);
+        assert_eq!(difference::<GregorianCalendar, _, _>(&from, &to), Ok(breakdown(0, 1, 0, 29)));
+        let to = gregorian(28, "February", 2024);
+        assert_eq!(difference::<GregorianCalendar, _, _>(&from, &to), Ok(breakdown(0, 0, 28, 28)));
+    }
+
+    #[test]
+    fn negative_difference() {
+        let from = gregorian(15, "March", 2024);
+        let to = gregorian(10, "January", 2023);
+        assert_eq!(days_between(&from, &to), Ok(-430));
+        assert_eq!(difference::<GregorianCalendar, _, _>(&from, &to), Ok(breakdown(-1, -2, -5, -430)));
+        assert_eq!(difference::<GregorianCalendar, _, _>(&to, &from), Ok(breakdown(1, 2, 5, 430)));
+        assert_eq!(difference::<GregorianCalendar, _, _>(&from, &from), Ok(breakdown(0, 0, 0, 0)));
+    }
+
+    #[test]
+    fn difference_between_calendars() {
+        use crate::julian_gregorian::JulianCalendar;
+        let julian = |day, month: &str, year| JulianCalendar::new(day, month.into(), year, "AD".into()).unwrap();
+
+        // the day after 4 October 1582 (Julian) was 15 October (Gregorian)
+        assert_eq!(days_between(&julian(4, "October", 1582), &gregorian(15, "October", 1582)), Ok(1));
+
+        // 25 December 2023 (Julian) is 7 January 2024 (Gregorian), and
+        // 25 December 2024 (Gregorian) is 12 December 2024 (Julian)
+        let from = julian(25, "December", 2023);
+        let to = gregorian(25, "December", 2024);
+        assert_eq!(days_between(&from, &to), Ok(353));
+        assert_eq!(difference::<GregorianCalendar, _, _>(&from, &to), Ok(breakdown(0, 11, 18, 353)));
+        assert_eq!(difference::<JulianCalendar, _, _>(&from, &to), Ok(breakdown(0, 11, 17, 353)));
+    }
+
+    #[test]
+    fn thirty_cheshvan() {
+        // Cheshvan has 30 days in 5785 and 29 in 5786
+        let date = hebrew(30, HebrewMonth::Cheshvan, 5785);
+        assert_eq!(date.add_years(1, Clamp), Ok(hebrew(29, HebrewMonth::Cheshvan, 5786)));
+        assert_eq!(date.add_years(1, Reject), Err(CalendarError::DayOutOfRange { day: 30, max: 29 }));
+        assert_eq!(date.add_years(1, RollOver), Ok(hebrew(1, HebrewMonth::Kislev, 5786)));
+        assert_eq!(hebrew(30, HebrewMonth::Tishrei, 5785).add_months(1, Reject), Ok(date));
+        assert_eq!(hebrew(30, HebrewMonth::Tishrei, 5786).add_months(1, RollOver), Ok(hebrew(1, HebrewMonth::Kislev, 5786)));
+    }
+
+    #[test]
+    fn hebrew_year_lengths() {
+        // deficient, complete and regular common years and deficient and
+        // complete leap years
+        let years = [(5781, 353), (5783, 355), (5786, 354), (5784, 383), (5779, 385)];
+        for &(year, length) in years.iter() {
+            assert_eq!(hebrew(1, HebrewMonth::Tishrei, year).days_in_year(), Ok(length), "year {}", year);
+        }
+        // Cheshvan and Kislev both have 29 days in a deficient year and 30
+        // in a complete one
+        assert_eq!(hebrew(1, HebrewMonth::Cheshvan, 5781).days_in_month(), Ok(29));
+        assert_eq!(hebrew(1, HebrewMonth::Kislev, 5781).days_in_month(), Ok(29));
+        assert_eq!(hebrew(1, HebrewMonth::Cheshvan, 5783).days_in_month(), Ok(30));
+        assert_eq!(hebrew(1, HebrewMonth::Kislev, 5783).days_in_month(), Ok(30));
+        assert_eq!(hebrew(1, HebrewMonth::AdarI, 5784).days_in_month(), Ok(30));
+        assert_eq!(hebrew(1, HebrewMonth::AdarII, 5784).days_in_month(), Ok(29));
+        assert_eq!(hebrew(1, HebrewMonth::Adar, 5785).days_in_month(), Ok(29));
+    }
+
+    #[test]
+    fn day_of_year() {
+        assert_eq!(gregorian(1, "January", 2024).day_of_year(), Ok(1));
+        assert_eq!(gregorian(1, "March", 2023).day_of_year(), Ok(60));
+        assert_eq!(gregorian(1, "March", 2024).day_of_year(), Ok(61));
+        assert_eq!(gregorian(31, "December", 2024).day_of_year(), Ok(366));
+        assert_eq!(gregorian(1, "February", 2024).days_in_month(), Ok(29));
+        assert_eq!(gregorian(1, "February", 2100).days_in_month(), Ok(28));
+        assert_eq!(gregorian(1, "February", 2000).days_in_year(), Ok(366));
+
+        // Tishrei to Adar II of 5784 have 206 days
+        assert_eq!(hebrew(1, HebrewMonth::Tishrei, 5784).day_of_year(), Ok(1));
+        assert_eq!(hebrew(1, HebrewMonth::Nisan, 5784).day_of_year(), Ok(207));
+        assert_eq!(hebrew(29, HebrewMonth::Elul, 5784).day_of_year(), Ok(383));
+    }
+
+    #[test]
+    fn week_of_year_starts_on_sunday() {
+        // 1 January 2023 was a Sunday and 1 January 2022 a Saturday
+        assert_eq!(gregorian(1, "January", 2023).week_of_year(), Ok(1));
+        assert_eq!(gregorian(7, "January", 2023).week_of_year(), Ok(1));
+        assert_eq!(gregorian(8, "January", 2023).week_of_year(), Ok(2));
+        assert_eq!(gregorian(31, "December", 2023).week_of_year(), Ok(53));
+        assert_eq!(gregorian(1, "January", 2022).week_of_year(), Ok(1));
+        assert_eq!(gregorian(2, "January", 2022).week_of_year(), Ok(2));
+        assert_eq!(gregorian(31, "December", 2022).week_of_year(), Ok(53));
+
+        // 1 Tishrei 5785 was a Thursday
+        assert_eq!(hebrew(1, HebrewMonth::Tishrei, 5785).week_of_year(), Ok(1));
+        assert_eq!(hebrew(3, HebrewMonth::Tishrei, 5785).week_of_year(), Ok(1));
+        assert_eq!(hebrew(4, HebrewMonth::Tishrei, 5785).week_of_year(), Ok(2));
+        assert!(hebrew(31, HebrewMonth::Tishrei, 5785).week_of_year().is_err());
+    }
+
+    #[test]
+    fn darian_weeks_restart_each_month() {
+        use crate::mars::DarianDate;
+        assert_eq!(DarianDate { year: 214, month: 1, sol: 7 }.week_of_year(), Ok(1));
+        assert_eq!(DarianDate { year: 214, month: 1, sol: 8 }.week_of_year(), Ok(2));
+        assert_eq!(DarianDate { year: 214, month: 6, sol: 27 }.week_of_year(), Ok(24));
+        assert_eq!(DarianDate { year: 214, month: 7, sol: 1 }.week_of_year(), Ok(25));
+        assert!(DarianDate { year: 214, month: 7, sol: 0 }.week_of_year().is_err());
+    }
+}
@@ -0,0 +1,415 @@
+/*
+Low precision positions of the sun and moon, used by the calendars whose
+months or years begin with an astronomical event instead of an arithmetic
+rule.
+
+Times are astronomical julian dates as f64 (the day starts at noon). The
+julian day numbers used by Calendar count midnight-to-midnight days, and
+civil day `n` starts at julian date `n + 0.5` in universal time. Formulas
+are from Meeus, Astronomical Algorithms (2nd ed.), truncated to the larger
+terms, which is accurate to a few hundredths of a degree for the sun and a
+few tenths of a degree for the moon.
+ */
+
+const J2000: f64 = 2451545.0;
+const MEAN_SYNODIC_MONTH: f64 = 29.530588861;
+const MEAN_TROPICAL_YEAR: f64 = 365.242189;
+
+fn sin_deg(x: f64) -> f64 {
+    x.to_radians().sin()
+}
+
+fn cos_deg(x: f64) -> f64 {
+    x.to_radians().cos()
+}
+
+// angle in [0, 360)
+pub(crate) fn normalize_degrees(x: f64) -> f64 {
+    x.rem_euclid(360.0)
+}
+
+// angle in [-180, 180)
+fn signed_degrees(x: f64) -> f64 {
+    normalize_degrees(x + 180.0) - 180.0
+}
+
+fn julian_centuries(julian_date: f64) -> f64 {
+    (julian_date - J2000) / 36525.0
+}
+
+// start of civil day `julian_day` in universal time
+pub(crate) fn day_start(julian_day: i128) -> f64 {
+    julian_day as f64 + 0.5
+}
+
+// civil day containing the universal time `julian_date`
+pub(crate) fn day_of(julian_date: f64) -> i128 {
+    (julian_date - 0.5).floor() as i128
+}
+
+// c[0] + c[1] x + c[2] x^2 + ...
+fn polynomial(x: f64, coefficients: &[f64]) -> f64 {
+    coefficients.iter().rev().fold(0.0, |sum, c| sum * x + c)
+}
+
+/*
+TT - UT in days, from the polynomials of Espenak and Meeus (Five Millennium
+Canon of Solar Eclipses) between 500 BC and AD 2150. These follow the
+observed values to within a second through the 20th century; after 2005
+they are a prediction, which has run a couple of seconds high since the
+earth's rotation sped up around 2020. Outside those years it
+uses the Morrison-Stephenson parabola the polynomials are fitted to join,
+good to about an hour in antiquity.
+ */
+pub(crate) fn delta_t(julian_date: f64) -> f64 {
+    let year = 2000.0 + (julian_date - J2000) / MEAN_TROPICAL_YEAR;
+    let parabola = |year: f64| -20.0 + 32.0 * ((year - 1820.0) / 100.0).powi(2);
+    let seconds = match year {
+        y if y < -500.0 => parabola(y),
+        y if y < 500.0 => polynomial(
+            y / 100.0,
+            &[10583.6, -1014.41, 33.78311, -5.952053, -0.1798452, 0.022174192, 0.0090316521],
+        ),
+        y if y < 1600.0 => polynomial(
+            (y - 1000.0) / 100.0,
+            &[1574.2, -556.01, 71.23472, 0.319781, -0.8503463, -0.005050998, 0.0083572073],
+        ),
+        y if y < 1700.0 => polynomial(y - 1600.0, &[120.0, -0.9808, -0.01532, 1.0 / 7129.0]),
+        y if y < 1800.0 => polynomial(y - 1700.0, &[8.83, 0.1603, -0.0059285, 0.00013336, -1.0 / 1174000.0]),
+        y if y < 1860.0 => polynomial(
+            y - 1800.0,
+            &[13.72, -0.332447, 0.0068612, 0.0041116, -0.00037436, 0.0000121272, -0.0000001699, 0.000000000875],
+        ),
+        y if y < 1900.0 => polynomial(
+            y - 1860.0,
+            &[7.62, 0.5737, -0.251754, 0.01680668, -0.0004473624, 1.0 / 233174.0],
+        ),
+        y if y < 1920.0 => polynomial(y - 1900.0, &[-2.79, 1.494119, -0.0598939, 0.0061966, -0.000197]),
+        y if y < 1941.0 => polynomial(y - 1920.0, &[21.20, 0.84493, -0.076100, 0.0020936]),
+        y if y < 1961.0 => polynomial(y - 1950.0, &[29.07, 0.407, -1.0 / 233.0, 1.0 / 2547.0]),
+        y if y < 1986.0 => polynomial(y - 1975.0, &[45.45, 1.067, -1.0 / 260.0, -1.0 / 718.0]),
+        y if y < 2005.0 => polynomial(
+            y - 2000.0,
+            &[63.86, 0.3345, -0.060374, 0.0017275, 0.000651814, 0.00002373599],
+        ),
+        y if y < 2050.0 => polynomial(y - 2000.0, &[62.92, 0.32217, 0.005589]),
+        y if y < 2150.0 => parabola(y) - 0.5628 * (2150.0 - y),
+        y => parabola(y),
+    };
+    seconds / 86400.0
+}
+
+// nutation in longitude, only the largest term
+fn nutation(t: f64) -> f64 {
+    -0.00478 * sin_deg(125.04 - 1934.136 * t)
+}
+
+fn obliquity(t: f64) -> f64 {
+    23.439291 - 0.0130042 * t
+}
+
+// apparent geocentric longitude of the sun in degrees, `tt` in dynamical time
+pub(crate) fn solar_longitude(tt: f64) -> f64 {
+    let t = julian_centuries(tt);
+    let l0 = 280.46646 + 36000.76983 * t + 0.0003032 * t * t;
+    let m = 357.52911 + 35999.05029 * t - 0.0001537 * t * t;
+    let c = (1.914602 - 0.004817 * t - 0.000014 * t * t) * sin_deg(m)
+        + (0.019993 - 0.000101 * t) * sin_deg(2.0 * m)
+        + 0.000289 * sin_deg(3.0 * m);
+    normalize_degrees(l0 + c - 0.00569 + nutation(t))
+}
+
+// (coefficient in millionths of a degree, D, M, M', F) for the moon's longitude
+const LUNAR_LONGITUDE_TERMS: [(f64, f64, f64, f64, f64); 24] = [
+    (6288774.0, 0.0, 0.0, 1.0, 0.0),
+    (1274027.0, 2.0, 0.0, -1.0, 0.0),
+    (658314.0, 2.0, 0.0, 0.0, 0.0),
+    (213618.0, 0.0, 0.0, 2.0, 0.0),
+    (-185116.0, 0.0, 1.0, 0.0, 0.0),
+    (-114332.0, 0.0, 0.0, 0.0, 2.0),
+    (58793.0, 2.0, 0.0, -2.0, 0.0),
+    (57066.0, 2.0, -1.0, -1.0, 0.0),
+    (53322.0, 2.0, 0.0, 1.0, 0.0),
+    (45758.0, 2.0, -1.0, 0.0, 0.0),
+    (-40923.0, 0.0, 1.0, -1.0, 0.0),
+    (-34720.0, 1.0, 0.0, 0.0, 0.0),
+    (-30383.0, 0.0, 1.0, 1.0, 0.0),
+    (15327.0, 2.0, 0.0, 0.0, -2.0),
+    (-12528.0, 0.0, 0.0, 1.0, 2.0),
+    (10980.0, 0.0, 0.0, 1.0, -2.0),
+    (10675.0, 4.0, 0.0, -1.0, 0.0),
+    (10034.0, 0.0, 0.0, 3.0, 0.0),
+    (8548.0, 4.0, 0.0, -2.0, 0.0),
+    (-7888.0, 2.0, 1.0, -1.0, 0.0),
+    (-6766.0, 2.0, 1.0, 0.0, 0.0),
+    (-5163.0, 1.0, 0.0, -1.0, 0.0),
+    (4987.0, 1.0, 1.0, 0.0, 0.0),
+    (4036.0, 2.0, -1.0, 1.0, 0.0),
+];
+
+// (coefficient in millionths of a degree, D, M, M', F) for the moon's latitude
+const LUNAR_LATITUDE_TERMS: [(f64, f64, f64, f64, f64); 7] = [
+    (5128122.0, 0.0, 0.0, 0.0, 1.0),
+    (280602.0, 0.0, 0.0, 1.0, 1.0),
+    (277693.0, 0.0, 0.0, 1.0, -1.0),
+    (173237.0, 2.0, 0.0, 0.0, -1.0),
+    (55413.0, 2.0, 0.0, -1.0, 1.0),
+    (46271.0, 2.0, 0.0, -1.0, -1.0),
+    (32573.0, 2.0, 0.0, 0.0, 1.0),
+];
+
+struct LunarArguments {
+    mean_longitude: f64,
+    elongation: f64,
+    solar_anomaly: f64,
+    lunar_anomaly: f64,
+    node_distance: f64,
+    eccentricity: f64,
+}
+
+fn lunar_arguments(t: f64) -> LunarArguments {
+    LunarArguments {
+        mean_longitude: 218.3164477 + 481267.88123421 * t - 0.0015786 * t * t,
+        elongation: 297.8501921 + 445267.1114034 * t - 0.0018819 * t * t,
+        solar_anomaly: 357.5291092 + 35999.0502909 * t - 0.0001536 * t * t,
+        lunar_anomaly: 134.9633964 + 477198.8675055 * t + 0.0087414 * t * t,
+        node_distance: 93.2720950 + 483202.0175233 * t - 0.0036539 * t * t,
+        eccentricity: 1.0 - 0.002516 * t - 0.0000074 * t * t,
+    }
+}
+
+fn sum_lunar_terms(terms: &[(f64, f64, f64, f64, f64)], args: &LunarArguments) -> f64 {
+    let mut sum = 0.0;
+    for &(coefficient, d, m, m_prime, f) in terms {
+        let angle = d * args.elongation
+            + m * args.solar_anomaly
+            + m_prime * args.lunar_anomaly
+            + f * args.node_distance;
+        // terms involving the sun's anomaly shrink as the earth's orbit becomes rounder
+        let eccentricity = args.eccentricity.powf(m.abs());
+        sum += coefficient * eccentricity * sin_deg(angle);
+    }
+    sum / 1_000_000.0
+}
+
+// apparent geocentric longitude of the moon in degrees, `tt` in dynamical time
+pub(crate) fn lunar_longitude(tt: f64) -> f64 {
+    let t = julian_centuries(tt);
+    let args = lunar_arguments(t);
+    normalize_degrees(args.mean_longitude + sum_lunar_terms(&LUNAR_LONGITUDE_TERMS, &args) + nutation(t))
+}
+
+// geocentric latitude of the moon in degrees, `tt` in dynamical time
+pub(crate) fn lunar_latitude(tt: f64) -> f64 {
+    let t = julian_centuries(tt);
+    sum_lunar_terms(&LUNAR_LATITUDE_TERMS, &lunar_arguments(t))
+}
+
+// time of the mean new moon with number `k` (0 is 6 January 2000),
+// corrected to the true new moon. Dynamical time
+pub(crate) fn new_moon(k: i128) -> f64 {
+    let k = k as f64;
+    let t = k / 1236.85;
+    let mean = 2451550.09766 + MEAN_SYNODIC_MONTH * k + 0.00015437 * t * t;
+    let e = 1.0 - 0.002516 * t - 0.0000074 * t * t;
+    let m = 2.5534 + 29.10535670 * k - 0.0000014 * t * t;
+    let m_prime = 201.5643 + 385.81693528 * k + 0.0107582 * t * t;
+    let f = 160.7108 + 390.67050284 * k - 0.0016118 * t * t;
+    let omega = 124.7746 - 1.56375588 * k + 0.0020672 * t * t;
+
+    let correction = -0.40720 * sin_deg(m_prime)
+        + 0.17241 * e * sin_deg(m)
+        + 0.01608 * sin_deg(2.0 * m_prime)
+        + 0.01039 * sin_deg(2.0 * f)
+        + 0.00739 * e * sin_deg(m_prime - m)
+        - 0.00514 * e * sin_deg(m_prime + m)
+        + 0.00208 * e * e * sin_deg(2.0 * m)
+        - 0.00111 * sin_deg(m_prime - 2.0 * f)
+        - 0.00057 * sin_deg(m_prime + 2.0 * f)
+        + 0.00056 * e * sin_deg(2.0 * m_prime + m)
+        - 0.00042 * sin_deg(3.0 * m_prime)
+        + 0.00042 * e * sin_deg(m + 2.0 * f)
+        + 0.00038 * e * sin_deg(m - 2.0 * f)
+        - 0.00024 * e * sin_deg(2.0 * m_prime - m)
+        - 0.00017 * sin_deg(omega);
+
+    mean + correction
+}
+
+// number of the last new moon at or before the universal time `julian_date`
+pub(crate) fn new_moon_number_before(julian_date: f64) -> i128 {
+    let tt = julian_date + delta_t(julian_date);
+    let mut k = ((tt - 2451550.09766) / MEAN_SYNODIC_MONTH).floor() as i128;
+    while new_moon(k + 1) <= tt {
+        k += 1;
+    }
+    while new_moon(k) > tt {
+        k -= 1;
+    }
+    k
+}
+
+// Lahiri ayanamsa in degrees: the distance of the start of the sidereal
+// zodiac used by Indian calendars from the vernal equinox
+fn ayanamsa(tt: f64) -> f64 {
+    23.85306 + 50.2788 / 3600.0 * (tt - J2000) / 365.25
+}
+
+// longitude of the sun in degrees measured from the start of the sidereal
+// zodiac instead of the vernal equinox, `tt` in dynamical time
+pub(crate) fn sidereal_solar_longitude(tt: f64) -> f64 {
+    normalize_degrees(solar_longitude(tt) - ayanamsa(tt))
+}
+
+// first universal time after `julian_date` at which the sun's apparent
+// longitude reaches `longitude`
+pub(crate) fn solar_longitude_after(longitude: f64, julian_date: f64) -> f64 {
+    longitude_after(solar_longitude, longitude, julian_date)
+}
+
+// first universal time after `julian_date` at which the sun's sidereal
+// longitude reaches `longitude`
+pub(crate) fn sidereal_solar_longitude_after(longitude: f64, julian_date: f64) -> f64 {
+    longitude_after(sidereal_solar_longitude, longitude, julian_date)
+}
+
+fn longitude_after(position: fn(f64) -> f64, longitude: f64, julian_date: f64) -> f64 {
+    let mut jd = julian_date
+        + normalize_degrees(longitude - position(julian_date + delta_t(julian_date)))
+            * MEAN_TROPICAL_YEAR
+            / 360.0;
+    for _ in 0..4 {
+        let difference = signed_degrees(longitude - position(jd + delta_t(jd)));
+        jd += difference * MEAN_TROPICAL_YEAR / 360.0;
+    }
+    jd
+}
+
+// right ascension and declination in degrees of a body at ecliptic
+// longitude and latitude `longitude`, `latitude`
+fn equatorial(longitude: f64, latitude: f64, t: f64) -> (f64, f64) {
+    let e = obliquity(t);
+    let right_ascension = (sin_deg(longitude) * cos_deg(e) - latitude.to_radians().tan() * sin_deg(e))
+        .atan2(cos_deg(longitude))
+        .to_degrees();
+    let declination = (sin_deg(latitude) * cos_deg(e) + cos_deg(latitude) * sin_deg(e) * sin_deg(longitude))
+        .asin()
+        .to_degrees();
+    (normalize_degrees(right_ascension), declination)
+}
+
+// hour angle in degrees at which a body with declination `declination`
+// reaches altitude `altitude`, or None if it never does
+fn setting_hour_angle(declination: f64, latitude: f64, altitude: f64) -> Option<f64> {
+    let cos_h = (sin_deg(altitude) - sin_deg(latitude) * sin_deg(declination))
+        / (cos_deg(latitude) * cos_deg(declination));
+    if !(-1.0..=1.0).contains(&cos_h) {
+        return None;
+    }
+    Some(cos_h.acos().to_degrees())
+}
+
+// equation of time in degrees, the difference between apparent and mean solar time
+fn equation_of_time(t: f64) -> f64 {
+    let l0 = 280.46646 + 36000.76983 * t;
+    let m = 357.52911 + 35999.05029 * t;
+    let e = 0.016708634 - 0.000042037 * t;
+    let y = (obliquity(t) / 2.0).to_radians().tan().powi(2);
+    (y * sin_deg(2.0 * l0) - 2.0 * e * sin_deg(m) + 4.0 * e * y * sin_deg(m) * cos_deg(2.0 * l0)
+        - 0.5 * y * y * sin_deg(4.0 * l0)
+        - 1.25 * e * e * sin_deg(2.0 * m))
+        .to_degrees()
+}
+
+/*
+A place on earth. Longitude is positive east of Greenwich.
+ */
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub struct Location {
+    pub latitude: f64,
+    pub longitude: f64,
+}
+
+// universal time of local midnight at the start of civil day `julian_day`
+// at `location`, by local mean time
+pub(crate) fn local_midnight(julian_day: i128, location: Location) -> f64 {
+    day_start(julian_day) - location.longitude / 360.0
+}
+
+// universal time of sunrise on civil day `julian_day` at `location`
+pub(crate) fn sunrise(julian_day: i128, location: Location) -> Option<f64> {
+    solar_event(julian_day, location, -1.0)
+}
+
+// universal time of sunset on civil day `julian_day` at `location`
+pub(crate) fn sunset(julian_day: i128, location: Location) -> Option<f64> {
+    solar_event(julian_day, location, 1.0)
+}
+
+fn solar_event(julian_day: i128, location: Location, direction: f64) -> Option<f64> {
+    // start from local noon and refine once with the sun's position at the event
+    let noon = local_midnight(julian_day, location) + 0.5;
+    let mut event = noon;
+    for _ in 0..2 {
+        let tt = event + delta_t(event);
+        let t = julian_centuries(tt);
+        let (_, declination) = equatorial(solar_longitude(tt), 0.0, t);
+        let hour_angle = setting_hour_angle(declination, location.latitude, -0.833)?;
+        event = noon + (direction * hour_angle - equation_of_time(t)) / 360.0;
+    }
+    Some(event)
+}
+
+// minutes between sunset and moonset on the evening of civil day
+// `julian_day`, negative when the moon sets first
+pub(crate) fn moon_lag(julian_day: i128, location: Location) -> Option<f64> {
+    let set = sunset(julian_day, location)?;
+    let tt = set + delta_t(set);
+    let t = julian_centuries(tt);
+    let (sun_right_ascension, sun_declination) = equatorial(solar_longitude(tt), 0.0, t);
+    let (moon_right_ascension, moon_declination) = equatorial(lunar_longitude(tt), lunar_latitude(tt), t);
+
+    let sun_hour_angle = setting_hour_angle(sun_declination, location.latitude, -0.833)?;
+    let moon_set_hour_angle = setting_hour_angle(moon_declination, location.latitude, 0.125)?;
+    // hour angle of the moon when the sun is setting
+    let moon_hour_angle = sun_hour_angle + signed_degrees(sun_right_ascension - moon_right_ascension);
+
+    // the sky turns by about one degree every four minutes
+    Some((moon_set_hour_angle - moon_hour_angle) * 4.0)
+}
+
+// angle in degrees from the sun to the moon along the ecliptic, in [0, 360)
+pub(crate) fn lunar_phase(julian_date: f64) -> f64 {
+    let tt = julian_date + delta_t(julian_date);
+    normalize_degrees(lunar_longitude(tt) - solar_longitude(tt))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn delta_t_seconds(year: f64) -> f64 {
+        delta_t(J2000 + (year - 2000.0) * MEAN_TROPICAL_YEAR) * 86400.0
+    }
+
+    #[test]
+    fn delta_t_matches_observed_values() {
+        // observed TT - UT at the start of each year, in seconds
+        let observed = [(1900.0, -2.7), (1950.0, 29.1), (1980.0, 50.5), (2000.0, 63.8)];
+        for &(year, seconds) in observed.iter() {
+            let difference = delta_t_seconds(year) - seconds;
+            assert!(difference.abs() < 1.0, "{}: {}", year, delta_t_seconds(year));
+        }
+        // the prediction after 2005 is a little high
+        assert!((delta_t_seconds(2020.0) - 69.4).abs() < 3.0);
+    }
+
+    #[test]
+    fn delta_t_joins_up_between_polynomials() {
+        let joins = [-500.0, 500.0, 1600.0, 1700.0, 1800.0, 1860.0, 1900.0, 1920.0, 1941.0, 1961.0, 1986.0, 2005.0, 2050.0, 2150.0];
+        for &year in joins.iter() {
+            let jump = delta_t_seconds(year + 0.001) - delta_t_seconds(year - 0.001);
+            assert!(jump.abs() < 2.0, "{}: {}", year, jump);
+        }
+    }
+}
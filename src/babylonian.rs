@@ -0,0 +1,606 @@
+use crate::astronomy;
+use crate::astronomy::Location;
+use crate::cal::{supported, Calendar};
+use crate::arithmetic::CalendarArithmetic;
+use crate::format::DateFormat;
+use crate::invalid_year_err::CalendarError;
+use crate::julian_day::JulianDayNumber;
+use crate::julian_gregorian::Era;
+use crate::GenericDate;
+use std::cmp::Ordering;
+use std::convert::TryInto;
+use std::ops::RangeInclusive;
+
+/*
+Date on the Babylonian lunisolar calendar.
+
+year: Julian year (with era) in which Nisannu 1 of this year falls
+
+month: starts at month 1 (Nisannu). is_leap_month is true for the
+intercalary month that follows the regular month with the same number,
+which is either Ululu II (6) or Addaru II (12)
+
+day: day in month. Months begin on the evening the new crescent is first
+seen, so day 1 is the Julian date whose evening follows that sighting
+ */
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct BabylonianDate {
+    pub era: Era,
+    pub year: u32,
+    pub month: u8,
+    pub is_leap_month: bool,
+    pub day: u8,
+}
+
+pub const BABYLONIAN_MONTH_NAMES: [&str; 12] = [
+    "Nisannu", "Ayyaru", "Simanu", "Duzu", "Abu", "Ululu", "Tashritu", "Arahsamnu", "Kislimu",
+    "Tebetu", "Shabatu", "Addaru",
+];
+
+// ordered by time: BC years count down and Ululu II and Addaru II follow the
+// regular month with the same number
+impl Ord for BabylonianDate {
+    fn cmp(&self, other: &Self) -> Ordering {
+        let key = |d: &Self| (astronomical_year(&d.era, d.year), d.month, d.is_leap_month, d.day, d.era == Era::AD);
+        key(self).cmp(&key(other))
+    }
+}
+
+impl PartialOrd for BabylonianDate {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Calendar for BabylonianDate {
+    fn to_julian_day_number(&self) -> Result<JulianDayNumber, CalendarError> {
+        supported::<Self>(JulianDayNumber::from_legacy(from_babylonian(self)?)?)
+    }
+
+    fn from_julian_day_number(day: JulianDayNumber) -> Result<Self, CalendarError> where Self: Sized {
+        to_babylonian(supported::<Self>(day)?.to_legacy())
+    }
+
+    // 1 Nisannu 2000 BC to 31 December AD 2500 (Julian), the years the
+    // astronomy is trusted for
+    fn supported_range() -> RangeInclusive<JulianDayNumber> where Self: Sized {
+        JulianDayNumber::new(991029)..=JulianDayNumber::new(2634548)
+    }
+
+    fn new(day: u32, month: String, year: i32, era: String) -> Result<Self, CalendarError> where Self: Sized {
+        // months have at most 30 days
+        let day: u8 = match day.try_into() {
+            Ok(n) => n,
+            Err(_) => return Err(CalendarError::DayOutOfRange { day, max: 30 }),
+        };
+
+        let era: Era = era.parse()?;
+
+        let year: u32 = match year.try_into() {
+            Ok(n) if n > 0 => n,
+            _ => return Err(CalendarError::InvalidYear { year: year as i64, era: era.to_string() }),
+        };
+
+        let (month, is_leap_month) = match month.as_str() {
+            "Ululu II" => (6, true),
+            "Addaru II" => (12, true),
+            name => match BABYLONIAN_MONTH_NAMES.iter().position(|&n| n == name) {
+                Some(i) => (i as u8 + 1, false),
+                None => return Err(CalendarError::UnknownMonth(month)),
+            },
+        };
+
+        Ok(BabylonianDate {
+            era,
+            year,
+            month,
+            is_leap_month,
+            day,
+        })
+    }
+}
+
+impl BabylonianDate {
+    // e.g. "Addaru II"
+    pub fn month_name(&self) -> String {
+        let month = BABYLONIAN_MONTH_NAMES
+            .get((self.month as usize).wrapping_sub(1))
+            .unwrap_or(&"");
+        let leap = match self.is_leap_month {
+            true => " II",
+            false => "",
+        };
+        format!("{}{}", month, leap)
+    }
+}
+
+impl std::fmt::Display for BabylonianDate {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(
+            f,
+            "era: {}, year: {}, month: {}, day: {}",
+            self.era, self.year, self.month_name(), self.day
+        )
+    }
+}
+
+const BABYLON: Location = Location {
+    latitude: 32.5355,
+    longitude: 44.4275,
+};
+
+// the crescent is taken to be visible when the moon sets at least this many
+// minutes after the sun, the rule of thumb recorded in Babylonian texts
+const MIN_MOON_LAG_MINUTES: f64 = 48.0;
+
+#[derive(Clone, Copy, PartialEq)]
+enum Intercalation {
+    None,
+    UluluII,
+    AddaruII,
+}
+
+use Intercalation::{AddaruII as A, None as N, UluluII as U};
+
+// first and last astronomical years (Nisannu 1 in 626 BC and AD 75) covered
+// by the tables of Parker and Dubberstein, Babylonian Chronology 626 B.C. -
+// A.D. 75, in which years are intercalated by CYCLE
+const TABLE_FIRST_YEAR: i128 = -625;
+const TABLE_LAST_YEAR: i128 = 75;
+
+/*
+Intercalary month of each year of the 19-year cycle, starting with year 1 of
+the Seleucid era (311 BC): Addaru II in years 1, 4, 7, 9, 12 and 15 and
+Ululu II in year 18.
+
+This is the cycle Parker and Dubberstein find in use from 367 BC onward. It
+is the fallback for every year of the table not listed in
+IRREGULAR_INTERCALATIONS. The reigns recorded in the chronicles (see the
+tests) fall in the expected months.
+ */
+const CYCLE: [Intercalation; 19] = [
+    A, N, N, A, N, N, A, N, A, N, N, A, N, N, A, N, N, U, N,
+];
+
+// first astronomical year (367 BC) from which the tables follow CYCLE
+const CYCLE_REGULAR_FROM: i128 = -366;
+
+/*
+Years before CYCLE_REGULAR_FROM in which the tables of Parker and
+Dubberstein intercalate differently from CYCLE, as (astronomical year,
+intercalation), in order of year.
+
+The list is empty: the year-by-year intercalations from the tables were not
+at hand when it was added, and entries must come from the tables rather
+than be reconstructed. Until then those years follow CYCLE and dates in
+them can be a month out. Each entry should be balanced by a neighbouring
+one so that the chronicle dates in the tests stay in their months
+ */
+const IRREGULAR_INTERCALATIONS: [(i128, Intercalation); 0] = [];
+
+// lunation (new moon number, see astronomy::new_moon) whose crescent starts
+// Nisannu 1 of the first year of the Seleucid era, 3 April 311 BC
+const SELEUCID_EPOCH_LUNATION: i128 = -28568;
+const SELEUCID_EPOCH_YEAR: i128 = -310;
+
+// years outside the table are computed back to 2000 BC and up to AD 2500,
+// beyond which the approximations in astronomy are no longer trustworthy
+const COMPUTED_FIRST_YEAR: i128 = -1999;
+const COMPUTED_LAST_YEAR: i128 = 2500;
+
+fn is_crescent_visible(julian_day: i128) -> bool {
+    let set = match astronomy::sunset(julian_day, BABYLON) {
+        Some(t) => t,
+        None => return false,
+    };
+    let phase = astronomy::lunar_phase(set);
+    if !(0.0..90.0).contains(&phase) {
+        return false;
+    }
+    match astronomy::moon_lag(julian_day, BABYLON) {
+        Some(lag) => lag > MIN_MOON_LAG_MINUTES,
+        None => false,
+    }
+}
+
+// julian day of the first day of the month that starts after new moon `lunation`
+fn month_start(lunation: i128) -> i128 {
+    let conjunction = astronomy::new_moon(lunation);
+    let conjunction = conjunction - astronomy::delta_t(conjunction);
+    let mut evening = astronomy::day_of(conjunction + BABYLON.longitude / 360.0);
+    // the crescent is always seen within a few evenings of the conjunction;
+    // stop looking after that so bad input can not loop forever
+    while !is_crescent_visible(evening) & (evening < astronomy::day_of(conjunction) + 4) {
+        evening += 1;
+    }
+    evening + 1
+}
+
+fn table_intercalation(year: i128) -> Intercalation {
+    let irregular = match year < CYCLE_REGULAR_FROM {
+        true => IRREGULAR_INTERCALATIONS.iter().find(|&&(y, _)| y == year),
+        false => None,
+    };
+    match irregular {
+        Some(&(_, intercalation)) => intercalation,
+        None => CYCLE[(year - SELEUCID_EPOCH_YEAR).rem_euclid(19) as usize],
+    }
+}
+
+fn months_in_table_year(year: i128) -> i128 {
+    match table_intercalation(year) {
+        Intercalation::None => 12,
+        _ => 13,
+    }
+}
+
+// outside the table, Nisannu 1 is the first day of the first month to begin
+// on or after the day of the vernal equinox. This agrees with the table in the
+// years on either side of it, so the two join up without a gap
+fn computed_nisannu_earliest(year: i128) -> i128 {
+    // the vernal equinox is within a few weeks of 21 March; start looking at 1 March
+    let march_1 = 1721117 + (year * 1461).div_euclid(4);
+    let equinox = astronomy::solar_longitude_after(0.0, astronomy::day_start(march_1));
+    astronomy::day_of(equinox + BABYLON.longitude / 360.0)
+}
+
+// lunation that begins Nisannu in a year inside the table
+fn table_nisannu(year: i128) -> i128 {
+    let mut lunation = SELEUCID_EPOCH_LUNATION;
+    let mut y = SELEUCID_EPOCH_YEAR;
+    while y < year {
+        lunation += months_in_table_year(y);
+        y += 1;
+    }
+    while y > year {
+        y -= 1;
+        lunation -= months_in_table_year(y);
+    }
+    lunation
+}
+
+// lunation that begins Nisannu in a year outside the table
+fn computed_nisannu(year: i128) -> i128 {
+    let earliest = computed_nisannu_earliest(year);
+    let mut lunation = astronomy::new_moon_number_before(astronomy::day_start(earliest)) - 1;
+    while month_start(lunation) < earliest {
+        lunation += 1;
+    }
+    lunation
+}
+
+fn nisannu(year: i128) -> i128 {
+    match (TABLE_FIRST_YEAR..=TABLE_LAST_YEAR).contains(&year) {
+        true => table_nisannu(year),
+        false => computed_nisannu(year),
+    }
+}
+
+// lunation that begins Nisannu in the astronomical year `year`, and how the year is intercalated
+fn year_structure(year: i128) -> Result<(i128, Intercalation), CalendarError> {
+    if !(COMPUTED_FIRST_YEAR..=COMPUTED_LAST_YEAR).contains(&year) {
+        return Err(CalendarError::Overflow);
+    }
+
+    if (TABLE_FIRST_YEAR..=TABLE_LAST_YEAR).contains(&year) {
+        return Ok((table_nisannu(year), table_intercalation(year)));
+    }
+
+    let lunation = computed_nisannu(year);
+    let intercalation = match nisannu(year + 1) - lunation {
+        13 => Intercalation::AddaruII,
+        _ => Intercalation::None,
+    };
+    Ok((lunation, intercalation))
+}
+
+// lunation offsets within the year of each month, in order
+fn year_months(intercalation: Intercalation) -> Vec<(u8, bool)> {
+    let mut months = Vec::new();
+    for month in 1..=12 {
+        months.push((month, false));
+        if ((month == 6) & (intercalation == Intercalation::UluluII))
+            | ((month == 12) & (intercalation == Intercalation::AddaruII))
+        {
+            months.push((month, true));
+        }
+    }
+    months
+}
+
+fn astronomical_year(era: &Era, year: u32) -> i128 {
+    match era {
+        Era::AD => year as i128,
+        Era::BC => 1 - year as i128,
+    }
+}
+
+fn from_babylonian(date: &BabylonianDate) -> Result<i128, CalendarError> {
+    if date.year < 1 {
+        return Err(CalendarError::InvalidYear { year: 0, era: date.era.to_string() });
+    }
+
+    let year = astronomical_year(&date.era, date.year);
+    let (nisannu, intercalation) = year_structure(year)?;
+    let index = match year_months(intercalation)
+        .iter()
+        .position(|&m| m == (date.month, date.is_leap_month))
+    {
+        Some(i) => i as i128,
+        None if (1..=12).contains(&date.month) => return Err(CalendarError::MonthNotInYear {
+            month: date.month_name(),
+            year: date.year as i64,
+        }),
+        None => return Err(CalendarError::UnknownMonth(date.month.to_string())),
+    };
+
+    let start = month_start(nisannu + index);
+    let length = month_start(nisannu + index + 1) - start;
+    if (date.day < 1) | (date.day as i128 > length) {
+        return Err(CalendarError::DayOutOfRange { day: date.day as u32, max: length as u32 });
+    }
+
+    Ok(start + date.day as i128 - 1)
+}
+
+pub(crate) fn to_babylonian(julian_day: i128) -> Result<BabylonianDate, CalendarError> {
+    // Nisannu 1 is in the spring, so the year is either the Julian year the
+    // day falls in or the one before it
+    let mut year = (4 * (julian_day - 1721423) + 3).div_euclid(1461) + 1;
+    if !(COMPUTED_FIRST_YEAR..=COMPUTED_LAST_YEAR).contains(&year) {
+        return Err(CalendarError::Overflow);
+    }
+    let (mut nisannu, mut intercalation) = year_structure(year)?;
+    if month_start(nisannu) > julian_day {
+        year -= 1;
+        (nisannu, intercalation) = year_structure(year)?;
+    }
+
+    let months = year_months(intercalation);
+    let mut index = 0;
+    while (index + 1 < months.len()) && (month_start(nisannu + index as i128 + 1) <= julian_day) {
+        index += 1;
+    }
+    let (month, is_leap_month) = months[index];
+    let day = julian_day - month_start(nisannu + index as i128) + 1;
+
+    let (era, display_year) = match year > 0 {
+        true => (Era::AD, year),
+        false => (Era::BC, 1 - year),
+    };
+
+    Ok(BabylonianDate {
+        era,
+        year: display_year as u32,
+        month,
+        is_leap_month,
+        day: day as u8,
+    })
+}
+
+fn babylonian_year_months(year: i64) -> Result<Vec<(u8, bool)>, CalendarError> {
+    let (_, intercalation) = year_structure(year as i128)?;
+    Ok(year_months(intercalation))
+}
+
+// adding years keeps the named month. Ululu II and Addaru II become Ululu
+// and Addaru in a year without them
+impl CalendarArithmetic for BabylonianDate {
+    fn fields(&self) -> (i64, u8, u8) {
+        let year = astronomical_year(&self.era, self.year) as i64;
+        let position = match babylonian_year_months(year) {
+            Ok(months) => months.iter().position(|&m| m == (self.month, self.is_leap_month)).unwrap_or(0) + 1,
+            Err(_) => self.month as usize,
+        };
+        (year, position as u8, self.day)
+    }
+
+    fn from_fields(year: i64, month: u8, day: u8) -> Result<Self, CalendarError> {
+        let months = babylonian_year_months(year)?;
+        let (month, is_leap_month) = match months.get((month as usize).wrapping_sub(1)) {
+            Some(&m) => m,
+            None => return Err(CalendarError::UnknownMonth(month.to_string())),
+        };
+        let (era, year) = match year > 0 {
+            true => (Era::AD, year),
+            false => (Era::BC, 1 - year),
+        };
+        Ok(BabylonianDate {
+            era,
+            year: year as u32,
+            month,
+            is_leap_month,
+            day,
+        })
+    }
+
+    fn is_leap_year(&self) -> bool {
+        let year = astronomical_year(&self.era, self.year);
+        matches!(year_structure(year), Ok((_, intercalation)) if intercalation != Intercalation::None)
+    }
+
+    fn month_count(year: i64) -> Result<u8, CalendarError> {
+        Ok(babylonian_year_months(year)?.len() as u8)
+    }
+
+    fn month_in_year(from_year: i64, month: u8, to_year: i64) -> Result<u8, CalendarError> {
+        let (month, is_leap_month) = match babylonian_year_months(from_year)?.get((month as usize).wrapping_sub(1)) {
+            Some(&m) => m,
+            None => return Err(CalendarError::UnknownMonth(month.to_string())),
+        };
+        let to_months = babylonian_year_months(to_year)?;
+        let position = match to_months.iter().position(|&m| m == (month, is_leap_month)) {
+            Some(i) => Some(i),
+            None => to_months.iter().position(|&m| m == (month, false)),
+        };
+        match position {
+            Some(i) => Ok(i as u8 + 1),
+            None => Err(CalendarError::InvalidInput),
+        }
+    }
+}
+
+impl DateFormat for BabylonianDate {
+    const CALENDAR: &'static str = "Babylonian";
+
+    fn month_name(&self) -> String {
+        BabylonianDate::month_name(self)
+    }
+
+    fn era(&self) -> Option<Era> {
+        Some(self.era)
+    }
+
+    fn year_in_era(&self) -> i64 {
+        self.year as i64
+    }
+}
+
+impl From<BabylonianDate> for GenericDate {
+    fn from(date: BabylonianDate) -> GenericDate {
+        // names: Nisannu (1) ... Addaru (12), Ululu II (13), Addaru II (14)
+        let month_name = match (date.month, date.is_leap_month) {
+            (6, true) => 13,
+            (12, true) => 14,
+            (month, _) => month,
+        };
+
+        GenericDate {
+            is_valid: true,
+            is_not_overflow: true,
+            era: date.era == Era::AD,
+            year: date.year,
+            month_name,
+            day: date.day,
+            is_leap_day: false,
+            weekday: 0,
+            error: String::new(),
+            month_text: String::new(),
+            weekday_text: String::new(),
+            era_text: String::new(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::julian_gregorian::JulianCalendar;
+
+    fn julian(day: u32, month: &str, year: i32, era: &str) -> JulianDayNumber {
+        JulianCalendar::new(day, month.into(), year, era.into())
+            .unwrap()
+            .to_julian_day_number()
+            .unwrap()
+    }
+
+    fn babylonian(day: u32, month: &str, year: i32, era: &str) -> BabylonianDate {
+        BabylonianDate::new(day, month.into(), year, era.into()).unwrap()
+    }
+
+    #[test]
+    fn seleucid_era_begins_3_april_311_bc() {
+        let day = julian(3, "April", 311, "BC");
+        assert_eq!(BabylonianDate::from_julian_day_number(day), Ok(babylonian(1, "Nisannu", 311, "BC")));
+        assert_eq!(babylonian(1, "Nisannu", 311, "BC").to_julian_day_number(), Ok(day));
+    }
+
+    // dates given in the chronicles, as converted by Parker and Dubberstein
+    #[test]
+    fn chronicle_dates() {
+        let dates = [
+            // Nabopolassar takes the throne
+            ((23, "November", 626), (26, "Arahsamnu", 626)),
+            // Nebuchadnezzar II takes the throne
+            ((7, "September", 605), (1, "Ululu", 605)),
+            // Jerusalem taken, in the 7th year of Nebuchadnezzar
+            ((16, "March", 597), (2, "Addaru", 598)),
+            // Cyrus enters Babylon
+            ((29, "October", 539), (3, "Arahsamnu", 539)),
+            // death of Alexander
+            ((11, "June", 323), (29, "Ayyaru", 323)),
+        ];
+        for &((day, month, year), (b_day, b_month, b_year)) in dates.iter() {
+            let expected = babylonian(b_day, b_month, b_year, "BC");
+            assert_eq!(BabylonianDate::from_julian_day_number(julian(day, month, year, "BC")), Ok(expected));
+        }
+    }
+
+    #[test]
+    fn cycle_intercalations() {
+        // year 1 of the Seleucid era has Addaru II, year 18 Ululu II
+        assert!(table_intercalation(-310) == Intercalation::AddaruII);
+        assert!(table_intercalation(-310 + 17) == Intercalation::UluluII);
+        assert!(table_intercalation(-310 + 19) == Intercalation::AddaruII);
+        assert!(table_intercalation(-310 - 2) == Intercalation::UluluII);
+        let leap = (0..19).filter(|&y| months_in_table_year(-310 + y) == 13).count();
+        assert_eq!(leap, 7);
+    }
+
+    #[test]
+    fn irregular_intercalations_break_the_cycle() {
+        let mut previous = TABLE_FIRST_YEAR - 1;
+        for &(year, intercalation) in IRREGULAR_INTERCALATIONS.iter() {
+            assert!((year > previous) & (year < CYCLE_REGULAR_FROM), "year {}", year);
+            assert!(intercalation != CYCLE[(year - SELEUCID_EPOCH_YEAR).rem_euclid(19) as usize], "year {}", year);
+            assert!(table_intercalation(year) == intercalation);
+            previous = year;
+        }
+        // from 367 BC every year follows the cycle
+        for year in CYCLE_REGULAR_FROM..=TABLE_LAST_YEAR {
+            assert!(table_intercalation(year) == CYCLE[(year - SELEUCID_EPOCH_YEAR).rem_euclid(19) as usize]);
+        }
+    }
+
+    // the rule used outside the table finds the same Nisannu as the table
+    // in the years at either end of it
+    #[test]
+    fn computed_years_agree_with_table_ends() {
+        for &year in [TABLE_FIRST_YEAR, TABLE_FIRST_YEAR + 1, TABLE_LAST_YEAR - 1, TABLE_LAST_YEAR].iter() {
+            assert_eq!(computed_nisannu(year), table_nisannu(year), "year {}", year);
+        }
+        let before = computed_nisannu(TABLE_FIRST_YEAR - 1);
+        assert!(matches!(table_nisannu(TABLE_FIRST_YEAR) - before, 12 | 13));
+        let after = computed_nisannu(TABLE_LAST_YEAR + 1);
+        assert_eq!(after - table_nisannu(TABLE_LAST_YEAR), months_in_table_year(TABLE_LAST_YEAR));
+    }
+
+    // every day converts back to itself across the two years either side of
+    // each end of the table
+    #[test]
+    fn no_gap_where_table_ends() {
+        for &(year, era) in [(628, "BC"), (74, "AD")].iter() {
+            let first = julian(1, "January", year, era);
+            let mut previous = BabylonianDate::from_julian_day_number(first).unwrap();
+            for n in 1..(4 * 366) {
+                let day = first.offset(n).unwrap();
+                let date = BabylonianDate::from_julian_day_number(day).unwrap();
+                assert_eq!(date.to_julian_day_number(), Ok(day));
+                assert!(date > previous);
+                assert!(date.day == previous.day + 1 || date.day == 1);
+                previous = date;
+            }
+        }
+    }
+
+    #[test]
+    fn years_around_table_ends_begin_with_nisannu_1() {
+        for &(year, era) in [(627, "BC"), (626, "BC"), (75, "AD"), (76, "AD")].iter() {
+            let start = babylonian(1, "Nisannu", year, era).to_julian_day_number().unwrap();
+            let last = BabylonianDate::from_julian_day_number(start.offset(-1).unwrap()).unwrap();
+            assert_eq!(last.month, 12);
+            assert!(last.day >= 29);
+        }
+    }
+
+    #[test]
+    fn years_count_from_1() {
+        assert_eq!(
+            BabylonianDate::new(1, "Nisannu".into(), 0, "BC".into()),
+            Err(CalendarError::InvalidYear { year: 0, era: String::from("BC") })
+        );
+        let date = BabylonianDate { era: Era::AD, year: 0, month: 1, is_leap_month: false, day: 1 };
+        assert_eq!(date.to_julian_day_number(), Err(CalendarError::InvalidYear { year: 0, era: String::from("AD") }));
+    }
+}
@@ -1,9 +1,141 @@
-use crate::invalid_year_err::CalendarError;
-
-pub trait Calendar {
-    fn to_julian_day(&self) -> Result<i128, CalendarError>;
-
-    fn from_julian_day(julian_day: i128) -> Result<Self, CalendarError> where Self: Sized;
-
-    fn new(day: u32, month: String, year: i32, era: String) -> Result<Self, CalendarError>  where Self: Sized;
-}
\ No newline at end of file
+use crate::invalid_year_err::CalendarError;
+use crate::julian_day::JulianDayNumber;
+use std::cmp::Ordering;
+use std::ops::RangeInclusive;
+
+pub trait Calendar {
+    fn to_julian_day_number(&self) -> Result<JulianDayNumber, CalendarError>;
+
+    fn from_julian_day_number(day: JulianDayNumber) -> Result<Self, CalendarError> where Self: Sized;
+
+    fn new(day: u32, month: String, year: i32, era: String) -> Result<Self, CalendarError>  where Self: Sized;
+
+    // first and last days the calendar can convert. Days outside of it give
+    // CalendarError::OutOfSupportedRange
+    fn supported_range() -> RangeInclusive<JulianDayNumber> where Self: Sized;
+
+    fn weekday(&self) -> Result<Weekday, CalendarError> {
+        Ok(Weekday::from_julian_day_number(self.to_julian_day_number()?))
+    }
+
+    #[deprecated(note = "use to_julian_day_number, this returns one less than the Julian Day Number")]
+    fn to_julian_day(&self) -> Result<i128, CalendarError> {
+        Ok(self.to_julian_day_number()?.to_legacy())
+    }
+
+    #[deprecated(note = "use from_julian_day_number, this takes one less than the Julian Day Number")]
+    fn from_julian_day(julian_day: i128) -> Result<Self, CalendarError> where Self: Sized {
+        Self::from_julian_day_number(JulianDayNumber::from_legacy(julian_day)?)
+    }
+}
+
+// `day` if it is in the supported range of T, otherwise
+// CalendarError::OutOfSupportedRange
+pub(crate) fn supported<T: Calendar>(day: JulianDayNumber) -> Result<JulianDayNumber, CalendarError> {
+    let range = T::supported_range();
+    match range.contains(&day) {
+        true => Ok(day),
+        false => Err(CalendarError::OutOfSupportedRange { min: *range.start(), max: *range.end() }),
+    }
+}
+
+/*
+Comparison of dates on any two calendars, through the julian day. Dates on
+the same calendar can also be compared with ==, < and so on, which compare
+the fields and work for dates that are not valid
+ */
+pub fn same_day<A: Calendar, B: Calendar>(a: &A, b: &B) -> Result<bool, CalendarError> {
+    Ok(a.to_julian_day_number()? == b.to_julian_day_number()?)
+}
+
+pub fn cmp_day<A: Calendar, B: Calendar>(a: &A, b: &B) -> Result<Ordering, CalendarError> {
+    Ok(a.to_julian_day_number()?.cmp(&b.to_julian_day_number()?))
+}
+
+// day of the week, numbered from 0 for Sunday
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Weekday {
+    Sunday,
+    Monday,
+    Tuesday,
+    Wednesday,
+    Thursday,
+    Friday,
+    Saturday,
+}
+
+const WEEKDAYS: [Weekday; 7] = [
+    Weekday::Sunday,
+    Weekday::Monday,
+    Weekday::Tuesday,
+    Weekday::Wednesday,
+    Weekday::Thursday,
+    Weekday::Friday,
+    Weekday::Saturday,
+];
+
+impl Weekday {
+    pub fn from_julian_day_number(day: JulianDayNumber) -> Weekday {
+        WEEKDAYS[(day.value() as i128 + 1).rem_euclid(7) as usize]
+    }
+
+    // 0 for Sunday to 6 for Saturday, larger numbers wrap around
+    pub fn from_number(number: u8) -> Weekday {
+        WEEKDAYS[(number % 7) as usize]
+    }
+
+    pub fn number(self) -> u8 {
+        self as u8
+    }
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Weekday::Sunday => "Sunday",
+            Weekday::Monday => "Monday",
+            Weekday::Tuesday => "Tuesday",
+            Weekday::Wednesday => "Wednesday",
+            Weekday::Thursday => "Thursday",
+            Weekday::Friday => "Friday",
+            Weekday::Saturday => "Saturday",
+        }
+    }
+}
+
+impl std::fmt::Display for Weekday {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(f, "{}", self.name())
+    }
+}
+
+// lower case with spaces, apostrophes, hyphens and full stops removed, so
+// that different spellings of a month name can be compared
+pub(crate) fn normalize_name(name: &str) -> String {
+    name.chars()
+        .filter(|c| !c.is_whitespace() && !matches!(c, '\'' | '\u{2019}' | '-' | '.'))
+        .flat_map(|c| c.to_lowercase())
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::hebrew::{HebrewDate, HebrewMonth};
+    use crate::julian_gregorian::{GregorianCalendar, JulianCalendar};
+
+    #[test]
+    fn weekdays() {
+        let gregorian = |day, month: &str, year| GregorianCalendar::new(day, month.into(), year, "AD".into()).unwrap();
+        assert_eq!(gregorian(15, "October", 1582).weekday(), Ok(Weekday::Friday));
+        assert_eq!(gregorian(1, "January", 2000).weekday(), Ok(Weekday::Saturday));
+        assert_eq!(gregorian(29, "February", 2024).weekday(), Ok(Weekday::Thursday));
+        let julian = JulianCalendar::new(4, "October".into(), 1582, "AD".into()).unwrap();
+        assert_eq!(julian.weekday(), Ok(Weekday::Thursday));
+
+        // Rosh Hashanah 5781 and 5785, and the first day of Pesach 5784
+        let hebrew = |day, month, year| HebrewDate::from_month(day, month, year).unwrap();
+        assert_eq!(hebrew(1, HebrewMonth::Tishrei, 5781).weekday(), Ok(Weekday::Saturday));
+        assert_eq!(hebrew(1, HebrewMonth::Tishrei, 5785).weekday(), Ok(Weekday::Thursday));
+        assert_eq!(hebrew(15, HebrewMonth::Nisan, 5784).weekday(), Ok(Weekday::Tuesday));
+        assert_eq!(Weekday::Saturday.number(), 6);
+    }
+}
@@ -0,0 +1,307 @@
+use wasm_bindgen::prelude::*;
+use crate::julian_day::JulianDayNumber;
+
+/*
+Cyclic day counts which repeat without counting years, so they can be
+computed from a julian day but cannot be converted back to a single date.
+
+Each count is tied to the julian day calendar by a correlation: the Julian
+Day Number of a reference day and the position of that day in each count,
+counted from 0. Scholars disagree about some of these, so the correlation
+can be chosen by the caller
+ */
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub struct Correlation {
+    pub julian_day: JulianDayNumber,
+    pub xihuitl_position: u16,
+    pub tonalpohualli_position: u16,
+}
+
+impl Correlation {
+    // a reference day which starts every count
+    pub const fn new(julian_day: JulianDayNumber) -> Correlation {
+        Correlation { julian_day, xihuitl_position: 0, tonalpohualli_position: 0 }
+    }
+
+    // a reference day on the given days of the Aztec counts
+    pub const fn aztec(julian_day: JulianDayNumber, xihuitl: AztecXihuitl, tonalpohualli: AztecTonalpohualli) -> Correlation {
+        let number = tonalpohualli.number as i32 - 1;
+        let sign = tonalpohualli.sign as i32 - 1;
+        Correlation {
+            julian_day,
+            xihuitl_position: (xihuitl.month as u16 - 1) * 20 + xihuitl.day as u16 - 1,
+            // 40 is 1 mod 13 and 0 mod 20, so adding 40 * (number - sign)
+            // to the sign reaches the number and keeps the sign
+            tonalpohualli_position: (sign + 40 * (number - sign)).rem_euclid(260) as u16,
+        }
+    }
+}
+
+// Alfonso Caso's correlation: the fall of Tenochtitlan on 13 August 1521
+// (Julian) was the day 1 Coatl in the month Xocotlhuetzi, day 2
+pub const AZTEC_CASO_CORRELATION: Correlation = Correlation::aztec(
+    JulianDayNumber::new(2276828),
+    AztecXihuitl { month: 11, day: 2 },
+    AztecTonalpohualli { number: 1, sign: 5 },
+);
+
+// Sahagún's account that the year began with Atlcahualo on 2 February
+// (Julian), applied to 1521 and keeping Caso's tonalpohualli: 11 days later
+// in the xiuhpohualli than Caso
+pub const AZTEC_SAHAGUN_CORRELATION: Correlation = Correlation::aztec(
+    JulianDayNumber::new(2276636),
+    AztecXihuitl { month: 2, day: 1 },
+    AztecTonalpohualli { number: 4, sign: 13 },
+);
+
+// a day named Nwona Wukuo, counted so that Wukuo always falls on a Wednesday
+pub const AKAN_CORRELATION: Correlation = Correlation::new(JulianDayNumber::new(1721463));
+
+pub const XIHUITL_MONTH_NAMES: [&str; 19] = [
+    "Izcalli",
+    "Atlcahualo",
+    "Tlacaxipehualiztli",
+    "Tozoztontli",
+    "Huei Tozoztli",
+    "Toxcatl",
+    "Etzalcualiztli",
+    "Tecuilhuitontli",
+    "Huei Tecuilhuitl",
+    "Tlaxochimaco",
+    "Xocotlhuetzi",
+    "Ochpaniztli",
+    "Teotleco",
+    "Tepeilhuitl",
+    "Quecholli",
+    "Panquetzaliztli",
+    "Atemoztli",
+    "Tititl",
+    "Nemontemi",
+];
+
+pub const TONALPOHUALLI_DAY_SIGNS: [&str; 20] = [
+    "Cipactli",
+    "Ehecatl",
+    "Calli",
+    "Cuetzpallin",
+    "Coatl",
+    "Miquiztli",
+    "Mazatl",
+    "Tochtli",
+    "Atl",
+    "Itzcuintli",
+    "Ozomatli",
+    "Malinalli",
+    "Acatl",
+    "Ocelotl",
+    "Quauhtli",
+    "Cozcaquauhtli",
+    "Ollin",
+    "Tecpatl",
+    "Quiahuitl",
+    "Xochitl",
+];
+
+pub const AKAN_PREFIXES: [&str; 6] = ["Nwona", "Nkyi", "Kuru", "Kwa", "Mono", "Fo"];
+
+pub const AKAN_STEMS: [&str; 7] = ["Wukuo", "Yaw", "Fie", "Memene", "Kwasi", "Dwo", "Bene"];
+
+/*
+Aztec solar year (xiuhpohualli): 18 months of 20 days followed by the 5
+nemontemi days, which are counted as month 19
+ */
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub struct AztecXihuitl {
+    pub month: u8,
+    pub day: u8,
+}
+
+impl AztecXihuitl {
+    pub fn from_julian_day_number(day: JulianDayNumber, correlation: Correlation) -> AztecXihuitl {
+        let count = (day.days_since(correlation.julian_day) + correlation.xihuitl_position as i128).rem_euclid(365);
+        AztecXihuitl {
+            month: (count / 20 + 1) as u8,
+            day: (count % 20 + 1) as u8,
+        }
+    }
+
+    pub fn month_name(&self) -> &'static str {
+        XIHUITL_MONTH_NAMES[(self.month - 1) as usize]
+    }
+}
+
+impl std::fmt::Display for AztecXihuitl {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(f, "{} {}", self.day, self.month_name())
+    }
+}
+
+/*
+Aztec 260-day ritual count: a number from 1 to 13 and one of 20 day signs,
+both advancing by one each day
+ */
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub struct AztecTonalpohualli {
+    pub number: u8,
+    pub sign: u8,
+}
+
+impl AztecTonalpohualli {
+    pub fn from_julian_day_number(day: JulianDayNumber, correlation: Correlation) -> AztecTonalpohualli {
+        let count = (day.days_since(correlation.julian_day) + correlation.tonalpohualli_position as i128).rem_euclid(260);
+        AztecTonalpohualli {
+            number: (count % 13 + 1) as u8,
+            sign: (count % 20 + 1) as u8,
+        }
+    }
+
+    pub fn sign_name(&self) -> &'static str {
+        TONALPOHUALLI_DAY_SIGNS[(self.sign - 1) as usize]
+    }
+}
+
+impl std::fmt::Display for AztecTonalpohualli {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(f, "{} {}", self.number, self.sign_name())
+    }
+}
+
+/*
+Akan 42-day cycle: a 6-day prefix combined with a 7-day stem (the stems
+line up with the days of the week)
+ */
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub struct AkanDay {
+    pub prefix: u8,
+    pub stem: u8,
+}
+
+impl AkanDay {
+    pub fn from_julian_day_number(day: JulianDayNumber, correlation: Correlation) -> AkanDay {
+        let count = day.days_since(correlation.julian_day);
+        AkanDay {
+            prefix: (count.rem_euclid(6) + 1) as u8,
+            stem: (count.rem_euclid(7) + 1) as u8,
+        }
+    }
+
+    pub fn name(&self) -> String {
+        format!(
+            "{} {}",
+            AKAN_PREFIXES[(self.prefix - 1) as usize],
+            AKAN_STEMS[(self.stem - 1) as usize]
+        )
+    }
+}
+
+impl std::fmt::Display for AkanDay {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(f, "{}", self.name())
+    }
+}
+
+/*
+Cyclic counts for a single day which can be returned to javascript code via
+web assembly. Uses the default correlations. Like GenericDate, errors are
+reported with is_valid and is_not_overflow
+ */
+#[wasm_bindgen]
+pub struct CyclicDate {
+    is_valid: bool,
+    is_not_overflow: bool,
+    xihuitl: AztecXihuitl,
+    tonalpohualli: AztecTonalpohualli,
+    akan: AkanDay,
+}
+
+impl CyclicDate {
+    pub(crate) fn from_julian_day_number(day: JulianDayNumber) -> CyclicDate {
+        CyclicDate {
+            is_valid: true,
+            is_not_overflow: true,
+            xihuitl: AztecXihuitl::from_julian_day_number(day, AZTEC_CASO_CORRELATION),
+            tonalpohualli: AztecTonalpohualli::from_julian_day_number(day, AZTEC_CASO_CORRELATION),
+            akan: AkanDay::from_julian_day_number(day, AKAN_CORRELATION),
+        }
+    }
+
+    pub(crate) fn error(is_valid: bool, is_not_overflow: bool) -> CyclicDate {
+        let mut date = CyclicDate::from_julian_day_number(JulianDayNumber::new(0));
+        date.is_valid = is_valid;
+        date.is_not_overflow = is_not_overflow;
+        date
+    }
+}
+
+#[wasm_bindgen]
+impl CyclicDate {
+
+    pub fn get_is_not_overflow (&self) -> bool {
+        self.is_not_overflow
+    }
+
+    pub fn get_is_valid (&self) -> bool {
+        self.is_valid
+    }
+
+    pub fn get_xihuitl (&self) -> String {
+        self.xihuitl.to_string()
+    }
+
+    pub fn get_tonalpohualli (&self) -> String {
+        self.tonalpohualli.to_string()
+    }
+
+    pub fn get_akan (&self) -> String {
+        self.akan.to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn fall_of_tenochtitlan() {
+        let day = JulianDayNumber::new(2276828);
+        let xihuitl = AztecXihuitl::from_julian_day_number(day, AZTEC_CASO_CORRELATION);
+        let tonalpohualli = AztecTonalpohualli::from_julian_day_number(day, AZTEC_CASO_CORRELATION);
+        assert_eq!(xihuitl.to_string(), "2 Xocotlhuetzi");
+        assert_eq!(tonalpohualli.to_string(), "1 Coatl");
+
+        // the tonalpohualli runs on through the nemontemi and the new year
+        let day = JulianDayNumber::new(2276828 + 260 * 3 + 1);
+        let tonalpohualli = AztecTonalpohualli::from_julian_day_number(day, AZTEC_CASO_CORRELATION);
+        assert_eq!(tonalpohualli.to_string(), "2 Miquiztli");
+    }
+
+    #[test]
+    fn sahagun_correlation() {
+        // 2 February 1521 (Julian)
+        let day = JulianDayNumber::new(2276636);
+        let xihuitl = AztecXihuitl::from_julian_day_number(day, AZTEC_SAHAGUN_CORRELATION);
+        assert_eq!(xihuitl.to_string(), "1 Atlcahualo");
+        let caso = AztecXihuitl::from_julian_day_number(day, AZTEC_CASO_CORRELATION);
+        assert_eq!(caso.to_string(), "10 Izcalli");
+
+        for offset in 0..520 {
+            let day = JulianDayNumber::new(2276636 + offset);
+            assert_eq!(
+                AztecTonalpohualli::from_julian_day_number(day, AZTEC_SAHAGUN_CORRELATION),
+                AztecTonalpohualli::from_julian_day_number(day, AZTEC_CASO_CORRELATION)
+            );
+        }
+    }
+
+    #[test]
+    fn akan_days() {
+        // 3 January 2024 was a Wednesday, 42 days later is the same Akan day
+        let day = JulianDayNumber::new(2460313);
+        let akan = AkanDay::from_julian_day_number(day, AKAN_CORRELATION);
+        assert_eq!(AKAN_STEMS[(akan.stem - 1) as usize], "Wukuo");
+        let later = AkanDay::from_julian_day_number(JulianDayNumber::new(2460313 + 42), AKAN_CORRELATION);
+        assert_eq!(later, akan);
+        let next = AkanDay::from_julian_day_number(JulianDayNumber::new(2460314), AKAN_CORRELATION);
+        assert_eq!(AKAN_STEMS[(next.stem - 1) as usize], "Yaw");
+    }
+}
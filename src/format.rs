@@ -0,0 +1,204 @@
+use std::convert::TryFrom;
+
+use crate::arithmetic::CalendarArithmetic;
+use crate::gematria::{to_hebrew_numeral, to_hebrew_year};
+use crate::roman_numerals::to_roman_numeral;
+use crate::invalid_year_err::CalendarError;
+use crate::julian_gregorian::Era;
+use crate::locale;
+
+/*
+Writing dates with strftime-style patterns, the same on every calendar.
+
+%d  day, two digits (05)        %e  day (5)          %o  day as an ordinal (5th)
+%m  month number, two digits    %B  month name       %b  month abbreviation
+%Y  year                        %E  era (AD, BC), empty without eras
+%A  weekday (Sunday)            %a  weekday (Sun)    %w  weekday number, 0 for Sunday
+%j  day of the year, three digits                    %O  day of the year as an ordinal
+%h  day in Hebrew numerals (ט״ו)
+%H  year in Hebrew numerals without its thousands (תשפ״ה for 5785)
+%R  year in Roman numerals (MDCCLII), up to 3999
+%%  a percent sign
+
+The month number is the month's position in its year, so Nisan is 7 in a
+common Hebrew year and 8 in a leap year. The year is counted within its
+era, so 44 BC is 44.
+
+Any other specifier gives CalendarError::InvalidInput.
+
+format_in writes the names in a locale (see the locale module), falling
+back to English for names it does not have. Ordinals (%o, %O) are always
+English (5th), since the locale tables only hold names
+ */
+pub trait DateFormat: CalendarArithmetic {
+    // the calendar's id, as used by js_api and the locale tables
+    const CALENDAR: &'static str;
+
+    // the English name, as taken by Calendar::new
+    fn month_name(&self) -> String;
+
+    fn localized_month_name(&self, locale: &str) -> String {
+        let name = DateFormat::month_name(self);
+        match locale::translate_month(Self::CALENDAR, &name, locale) {
+            Some(localized) => String::from(localized),
+            None => name,
+        }
+    }
+
+    // the first three letters for one word names, otherwise the whole name
+    // (Adar II, Leap Month 3)
+    fn month_abbreviation(&self) -> String {
+        let name = DateFormat::month_name(self);
+        match name.contains(' ') {
+            true => name,
+            false => name.chars().take(3).collect(),
+        }
+    }
+
+    fn era(&self) -> Option<Era> {
+        None
+    }
+
+    fn year_in_era(&self) -> i64 {
+        self.fields().0
+    }
+
+    fn format(&self, pattern: &str) -> Result<String, CalendarError> {
+        self.format_in(pattern, "en")
+    }
+
+    fn format_in(&self, pattern: &str, locale: &str) -> Result<String, CalendarError> {
+        let (_, month, day) = self.fields();
+        let mut output = String::new();
+        let mut chars = pattern.chars();
+        while let Some(c) = chars.next() {
+            if c != '%' {
+                output.push(c);
+                continue;
+            }
+            match chars.next() {
+                Some('d') => output += &format!("{:02}", day),
+                Some('e') => output += &day.to_string(),
+                Some('o') => output += &ordinal(day as u32),
+                Some('m') => output += &format!("{:02}", month),
+                Some('B') => output += &self.localized_month_name(locale),
+                Some('b') => {
+                    // English names from the fallback are cut as in English
+                    let name = self.localized_month_name(locale);
+                    let truncates = name == DateFormat::month_name(self) || locale::truncates_months(locale);
+                    match !name.contains(' ') && truncates {
+                        true => output.extend(name.chars().take(3)),
+                        false => output += &name,
+                    }
+                }
+                Some('Y') => output += &self.year_in_era().to_string(),
+                Some('E') => {
+                    if let Some(era) = self.era() {
+                        output += locale::era_name(era, locale);
+                    }
+                }
+                Some('A') => output += locale::weekday_name(self.weekday()?, locale),
+                Some('a') => output += locale::weekday_abbreviation(self.weekday()?, locale),
+                Some('w') => output += &self.weekday()?.number().to_string(),
+                Some('j') => output += &format!("{:03}", self.day_of_year()?),
+                Some('O') => output += &ordinal(self.day_of_year()? as u32),
+                Some('h') => output += &to_hebrew_numeral(day as u32),
+                Some('H') => match u32::try_from(self.year_in_era()) {
+                    Ok(year) => output += &to_hebrew_year(year, true),
+                    Err(_) => return Err(CalendarError::Overflow),
+                },
+                Some('R') => match u32::try_from(self.year_in_era()) {
+                    Ok(year) => output += &to_roman_numeral(year)?,
+                    Err(_) => return Err(CalendarError::Overflow),
+                },
+                Some('%') => output.push('%'),
+                _ => return Err(CalendarError::InvalidInput),
+            }
+        }
+        Ok(output)
+    }
+}
+
+// 1st, 2nd, 3rd, 4th, 11th, 12th, 13th, 21st ...
+pub fn ordinal(n: u32) -> String {
+    let suffix = match (n % 10, n % 100) {
+        (_, 11..=13) => "th",
+        (1, _) => "st",
+        (2, _) => "nd",
+        (3, _) => "rd",
+        _ => "th",
+    };
+    format!("{}{}", n, suffix)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cal::Calendar;
+    use crate::hebrew::{HebrewDate, HebrewMonth};
+    use crate::julian_gregorian::{GregorianCalendar, JulianCalendar};
+
+    const EVERY_SPECIFIER: &str = "%d %e %o %m %B %b %Y %E %A %a %w %j %O %%";
+
+    #[test]
+    fn gregorian() {
+        let date = GregorianCalendar::new(5, "March".into(), 2024, "AD".into()).unwrap();
+        assert_eq!(date.format(EVERY_SPECIFIER).unwrap(), "05 5 5th 03 March Mar 2024 AD Tuesday Tue 2 065 65th %");
+        assert_eq!(date.format("%R").unwrap(), "MMXXIV");
+        assert_eq!(date.format_in("%A %e %B %b %E", "fr").unwrap(), "mardi 5 mars mar ap. J.-C.");
+        assert_eq!(date.format_in("%o", "fr").unwrap(), "5th");
+    }
+
+    #[test]
+    fn julian_bc() {
+        // the Ides of March, 44 BC (45 BC was the leap year)
+        let date = JulianCalendar::new(15, "March".into(), 44, "BC".into()).unwrap();
+        assert_eq!(date.format(EVERY_SPECIFIER).unwrap(), "15 15 15th 03 March Mar 44 BC Wednesday Wed 3 074 74th %");
+        assert_eq!(date.format("%R %E").unwrap(), "XLIV BC");
+    }
+
+    #[test]
+    fn hebrew() {
+        // Nisan is the 8th month of a leap year
+        let date = HebrewDate::from_month(15, HebrewMonth::Nisan, 5784).unwrap();
+        assert_eq!(date.format(EVERY_SPECIFIER).unwrap(), "15 15 15th 08 Nisan Nis 5784  Tuesday Tue 2 221 221st %");
+        assert_eq!(date.format("%h %H").unwrap(), "ט״ו תשפ״ד");
+
+        let adar_i = HebrewDate::from_month(1, HebrewMonth::AdarI, 5784).unwrap();
+        assert_eq!(adar_i.format("%m %B %b").unwrap(), "06 Adar I Adar I");
+        assert_eq!(adar_i.format_in("%B", "he").unwrap(), "אדר א׳");
+        let adar = HebrewDate::from_month(14, HebrewMonth::Adar, 5785).unwrap();
+        assert_eq!(adar.format("%m %B %b").unwrap(), "06 Adar Ada");
+        assert_eq!(adar.format_in("%B", "he").unwrap(), "אדר");
+    }
+
+    #[test]
+    fn roman_years() {
+        let year = |year, era: &str| GregorianCalendar::new(1, "January".into(), year, era.into()).unwrap();
+        assert_eq!(year(1752, "AD").format("%R").unwrap(), "MDCCLII");
+        assert_eq!(year(1, "AD").format("%R").unwrap(), "I");
+        assert_eq!(year(44, "BC").format("%R %E").unwrap(), "XLIV BC");
+        assert_eq!(year(3999, "AD").format("%R").unwrap(), "MMMCMXCIX");
+        assert_eq!(year(4000, "AD").format("%R"), Err(CalendarError::Overflow));
+    }
+
+    #[test]
+    fn invalid_specifiers() {
+        let date = GregorianCalendar::new(5, "March".into(), 2024, "AD".into()).unwrap();
+        assert_eq!(date.format("%q"), Err(CalendarError::InvalidInput));
+        assert_eq!(date.format("100%"), Err(CalendarError::InvalidInput));
+        assert_eq!(date.format("100%%").unwrap(), "100%");
+        assert_eq!(date.format("").unwrap(), "");
+    }
+
+    #[test]
+    fn ordinals() {
+        let suffixes = [
+            (1, "1st"), (2, "2nd"), (3, "3rd"), (4, "4th"), (11, "11th"), (12, "12th"), (13, "13th"),
+            (21, "21st"), (111, "111th"), (122, "122nd"),
+        ];
+        for &(n, expected) in suffixes.iter() {
+            assert_eq!(ordinal(n), expected);
+        }
+    }
+}
@@ -0,0 +1,253 @@
+use crate::invalid_year_err::CalendarError;
+
+/*
+Hebrew numerals, as used for days and years in Hebrew dates
+("ט״ו בשבט תשפ״ה" is 15 Shevat 5785).
+
+Each letter has a value and a number is written as its hundreds, tens and
+units, largest first. 400 is the largest letter, so 900 is תתק. 15 and 16
+are written טו and טז (9 + 6, 9 + 7) instead of יה and יו, which spell the
+name of God, including in larger numbers (תשט״ו).
+
+A number of one letter is followed by a geresh (א׳), otherwise a gershayim
+goes before the last letter (י״א). Thousands are written before the rest
+with a geresh (ה׳תשפ״ה is 5785), and are usually left out of years, so
+תשפ״ה is also 5785. Whole thousands are written out, as ה׳ אלפים (5000)
+or אלף (1000), since ה׳ alone is 5
+ */
+
+pub const GERESH: char = '׳';
+pub const GERSHAYIM: char = '״';
+
+const UNITS: [char; 9] = ['א', 'ב', 'ג', 'ד', 'ה', 'ו', 'ז', 'ח', 'ט'];
+const TENS: [char; 9] = ['י', 'כ', 'ל', 'מ', 'נ', 'ס', 'ע', 'פ', 'צ'];
+const HUNDREDS: [char; 4] = ['ק', 'ר', 'ש', 'ת'];
+
+// years without their thousands are in the sixth millennium
+const IMPLIED_THOUSANDS: u32 = 5000;
+
+// the letters of 1 to 999, without punctuation
+fn letters(n: u32) -> String {
+    let mut output = String::new();
+    let mut rest = n % 1000;
+    while rest >= 400 {
+        output.push('ת');
+        rest -= 400;
+    }
+    if rest >= 100 {
+        output.push(HUNDREDS[(rest / 100 - 1) as usize]);
+        rest %= 100;
+    }
+    match rest {
+        15 => output.push_str("טו"),
+        16 => output.push_str("טז"),
+        _ => {
+            if rest >= 10 {
+                output.push(TENS[(rest / 10 - 1) as usize]);
+            }
+            if rest % 10 != 0 {
+                output.push(UNITS[(rest % 10 - 1) as usize]);
+            }
+        }
+    }
+    output
+}
+
+// a geresh after one letter, otherwise a gershayim before the last
+fn punctuate(letters: &str) -> String {
+    let mut chars: Vec<char> = letters.chars().collect();
+    match chars.len() {
+        0 => String::new(),
+        1 => format!("{}{}", chars[0], GERESH),
+        n => {
+            chars.insert(n - 1, GERSHAYIM);
+            chars.into_iter().collect()
+        }
+    }
+}
+
+const THOUSAND: &str = "אלף";
+const THOUSANDS: &str = "אלפים";
+
+// e.g. 15 is ט״ו and 5785 is ה׳תשפ״ה. 0 has no letters and is empty
+pub fn to_hebrew_numeral(n: u32) -> String {
+    match (n / 1000, n % 1000) {
+        (0, _) => punctuate(&letters(n)),
+        (1, 0) => String::from(THOUSAND),
+        (thousands, 0) => format!("{} {}", to_hebrew_numeral(thousands), THOUSANDS),
+        (thousands, _) => format!("{}{}{}", to_hebrew_numeral(thousands).trim_end_matches(GERESH), GERESH, punctuate(&letters(n))),
+    }
+}
+
+// a year as it is usually written, without its thousands (תשפ״ה for 5785)
+pub fn to_hebrew_year(year: u32, omit_thousands: bool) -> String {
+    match omit_thousands && year % 1000 != 0 {
+        true => to_hebrew_numeral(year % 1000),
+        false => to_hebrew_numeral(year),
+    }
+}
+
+fn value(letter: char) -> Option<u32> {
+    let final_form = match letter {
+        'ך' => 'כ',
+        'ם' => 'מ',
+        'ן' => 'נ',
+        'ף' => 'פ',
+        'ץ' => 'צ',
+        letter => letter,
+    };
+    let position = |letters: &[char]| letters.iter().position(|&l| l == final_form).map(|p| p as u32 + 1);
+    position(&UNITS)
+        .or_else(|| position(&TENS).map(|p| p * 10))
+        .or_else(|| position(&HUNDREDS).map(|p| p * 100))
+}
+
+// vowel points and cantillation marks, which do not change the letters
+pub fn is_point(c: char) -> bool {
+    ('\u{0591}'..='\u{05C7}').contains(&c) && c != '\u{05BE}'
+}
+
+fn is_geresh(c: char) -> bool {
+    c == GERESH || c == '\''
+}
+
+fn is_gershayim(c: char) -> bool {
+    c == GERSHAYIM || c == '"'
+}
+
+/*
+The value of a Hebrew numeral, with or without its punctuation. ASCII ' and
+" are read as geresh and gershayim, final letters (ך) as their usual forms,
+and the letters may be in any order. A geresh followed by more letters ends
+the thousands.
+
+Anything else gives CalendarError::InvalidInput
+ */
+pub fn from_hebrew_numeral(text: &str) -> Result<u32, CalendarError> {
+    let text = text.trim();
+    if text == THOUSAND {
+        return Ok(1000);
+    }
+    if let Some(thousands) = text.strip_suffix(THOUSANDS) {
+        return from_hebrew_numeral(thousands)?.checked_mul(1000).ok_or(CalendarError::Overflow);
+    }
+    let chars: Vec<char> = text.trim().chars().filter(|&c| !is_point(c)).collect();
+    let mut total: u32 = 0;
+    let mut group: u32 = 0;
+    let mut has_letters = false;
+    for (i, &c) in chars.iter().enumerate() {
+        match value(c) {
+            Some(v) => {
+                group += v;
+                has_letters = true;
+            }
+            None if is_geresh(c) && i + 1 < chars.len() && group > 0 => {
+                total = group.checked_mul(1000).ok_or(CalendarError::Overflow)?;
+                group = 0;
+            }
+            None if is_geresh(c) | is_gershayim(c) => {}
+            None => return Err(CalendarError::InvalidInput),
+        }
+    }
+    match has_letters {
+        true => total.checked_add(group).ok_or(CalendarError::Overflow),
+        false => Err(CalendarError::InvalidInput),
+    }
+}
+
+// whether a numeral has its thousands written, as in ה׳תשפ״ה
+pub fn has_thousands(text: &str) -> bool {
+    let text = text.trim();
+    if text == THOUSAND || text.ends_with(THOUSANDS) {
+        return true;
+    }
+    let chars: Vec<char> = text.trim().chars().filter(|&c| !is_point(c)).collect();
+    chars.iter().enumerate().any(|(i, &c)| is_geresh(c) && i > 0 && i + 1 < chars.len())
+}
+
+// a year, in the sixth millennium if its thousands are left out
+pub fn from_hebrew_year(text: &str) -> Result<u32, CalendarError> {
+    let year = from_hebrew_numeral(text)?;
+    match has_thousands(text) {
+        true => Ok(year),
+        false => Ok(year + IMPLIED_THOUSANDS),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn fifteen_and_sixteen() {
+        assert_eq!(to_hebrew_numeral(15), "ט״ו");
+        assert_eq!(to_hebrew_numeral(16), "ט״ז");
+        assert_eq!(to_hebrew_numeral(115), "קט״ו");
+        assert_eq!(to_hebrew_numeral(716), "תשט״ז");
+        assert_eq!(from_hebrew_numeral("ט״ו"), Ok(15));
+        assert_eq!(from_hebrew_numeral("קט״ו"), Ok(115));
+        // the usual letters are still read
+        assert_eq!(from_hebrew_numeral("יה"), Ok(15));
+    }
+
+    #[test]
+    fn single_letters() {
+        assert_eq!(to_hebrew_numeral(1), "א׳");
+        assert_eq!(to_hebrew_numeral(10), "י׳");
+        assert_eq!(to_hebrew_numeral(400), "ת׳");
+        assert_eq!(to_hebrew_numeral(11), "י״א");
+        assert_eq!(to_hebrew_numeral(0), "");
+        assert_eq!(from_hebrew_numeral("ה׳"), Ok(5));
+        assert_eq!(from_hebrew_numeral("ה'"), Ok(5));
+        assert_eq!(from_hebrew_numeral("ה"), Ok(5));
+    }
+
+    #[test]
+    fn whole_thousands() {
+        assert_eq!(to_hebrew_numeral(1000), "אלף");
+        assert_eq!(to_hebrew_numeral(5000), "ה׳ אלפים");
+        assert_eq!(from_hebrew_numeral("אלף"), Ok(1000));
+        assert_eq!(from_hebrew_numeral("ה׳ אלפים"), Ok(5000));
+        assert_eq!(to_hebrew_year(5000, true), "ה׳ אלפים");
+    }
+
+    #[test]
+    fn years() {
+        assert_eq!(to_hebrew_numeral(5785), "ה׳תשפ״ה");
+        assert_eq!(to_hebrew_year(5785, true), "תשפ״ה");
+        assert_eq!(to_hebrew_year(5785, false), "ה׳תשפ״ה");
+        assert_eq!(from_hebrew_year("תשפ״ה"), Ok(5785));
+        assert_eq!(from_hebrew_year("ה׳תשפ״ה"), Ok(5785));
+        assert_eq!(from_hebrew_year("ה'תשפ\"ה"), Ok(5785));
+        assert!(!has_thousands("תשפ״ה"));
+        assert!(has_thousands("ה׳תשפ״ה"));
+        // with vowel points
+        assert_eq!(from_hebrew_year("תַּשְׁפ״ה"), Ok(5785));
+    }
+
+    #[test]
+    fn final_letters() {
+        assert_eq!(from_hebrew_numeral("ך"), Ok(20));
+        assert_eq!(from_hebrew_numeral("ם"), Ok(40));
+        assert_eq!(from_hebrew_numeral("ן"), Ok(50));
+        assert_eq!(from_hebrew_numeral("תשף"), Ok(780));
+        assert_eq!(from_hebrew_numeral("ץ"), Ok(90));
+        // but they are never written
+        assert_eq!(to_hebrew_numeral(780), "תש״פ");
+    }
+
+    #[test]
+    fn not_numerals() {
+        assert_eq!(from_hebrew_numeral(""), Err(CalendarError::InvalidInput));
+        assert_eq!(from_hebrew_numeral("״"), Err(CalendarError::InvalidInput));
+        assert_eq!(from_hebrew_numeral("abc"), Err(CalendarError::InvalidInput));
+        assert_eq!(from_hebrew_numeral("ט״ו5"), Err(CalendarError::InvalidInput));
+    }
+
+    #[test]
+    fn round_trip() {
+        for n in 1..=6000 {
+            assert_eq!(from_hebrew_numeral(&to_hebrew_numeral(n)), Ok(n), "{}", to_hebrew_numeral(n));
+        }
+    }
+}
@@ -1,64 +1,507 @@
 use crate::invalid_year_err::CalendarError;
-use crate::cal::Calendar;
-use std::convert::TryInto;
+use crate::cal::{normalize_name, supported, Calendar, Weekday};
+use crate::julian_day::JulianDayNumber;
+use crate::arithmetic::CalendarArithmetic;
+use crate::format::DateFormat;
+use crate::gematria::{is_point, to_hebrew_numeral, to_hebrew_year, GERESH, GERSHAYIM};
+use std::convert::{TryFrom, TryInto};
+use std::ops::RangeInclusive;
+use std::str::FromStr;
 use crate::GenericDate;
 
+pub mod anniversary;
+pub mod holidays;
+pub mod parashah;
+
+#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct HebrewDate {
     pub year: u32,
     pub month: u8,
     pub day: u8,
 }
 
-impl Calendar for HebrewDate {
-    fn to_julian_day(&self) -> Result<i128, CalendarError> {
-        return from_hebrew(self);
+/*
+Month of the Hebrew calendar.
+
+Adar: the only Adar of a common year. In a leap year this is Adar II, the
+month in which Purim is kept
+
+AdarI, AdarII: the two Adars of a leap year. They are not valid in common
+years
+ */
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum HebrewMonth {
+    Tishrei,
+    Cheshvan,
+    Kislev,
+    Tevet,
+    Shevat,
+    Adar,
+    AdarI,
+    AdarII,
+    Nisan,
+    Iyar,
+    Sivan,
+    Tammuz,
+    Av,
+    Elul,
+}
+
+impl HebrewMonth {
+    // number of the month within the given year, as stored in HebrewDate
+    pub fn number(self, year: u32) -> Result<u8, CalendarError> {
+        let leap_offset = match is_leap_year(year) {
+            true => 1,
+            false => 0,
+        };
+
+        match self {
+            HebrewMonth::Tishrei => Ok(1),
+            HebrewMonth::Cheshvan => Ok(2),
+            HebrewMonth::Kislev => Ok(3),
+            HebrewMonth::Tevet => Ok(4),
+            HebrewMonth::Shevat => Ok(5),
+            HebrewMonth::Adar => Ok(6 + leap_offset),
+            HebrewMonth::AdarI | HebrewMonth::AdarII if leap_offset == 0 => Err(CalendarError::MonthNotInYear {
+                month: String::from(self.name()),
+                year: year as i64,
+            }),
+            HebrewMonth::AdarI => Ok(6),
+            HebrewMonth::AdarII => Ok(7),
+            HebrewMonth::Nisan => Ok(7 + leap_offset),
+            HebrewMonth::Iyar => Ok(8 + leap_offset),
+            HebrewMonth::Sivan => Ok(9 + leap_offset),
+            HebrewMonth::Tammuz => Ok(10 + leap_offset),
+            HebrewMonth::Av => Ok(11 + leap_offset),
+            HebrewMonth::Elul => Ok(12 + leap_offset),
+        }
     }
 
-    fn from_julian_day(julian_day: i128) -> Result<Self, CalendarError> where Self: Sized {
-        return to_hebrew(julian_day);
+    // month at the given number within the year, as stored in HebrewDate
+    pub fn from_number(number: u8, year: u32) -> Result<HebrewMonth, CalendarError> {
+        let months: &[HebrewMonth] = match is_leap_year(year) {
+            true => &HEBREW_LEAP_YEAR_MONTHS,
+            false => &HEBREW_COMMON_YEAR_MONTHS,
+        };
+        match months.get((number as usize).wrapping_sub(1)) {
+            Some(month) => Ok(*month),
+            None => Err(CalendarError::UnknownMonth(number.to_string())),
+        }
     }
-    
-    fn new(day: u32, month: String, year: i32, _era: String) -> Result<Self, CalendarError>  where Self: Sized{
-        let day_error: Result<u8, _> = day.try_into();
-        let day;
-        match day_error {
-            Ok(n) => day = n,
-            Err(_) => return Err(CalendarError::InvalidInput),
+
+    pub fn name(self) -> &'static str {
+        match self {
+            HebrewMonth::Tishrei => "Tishrei",
+            HebrewMonth::Cheshvan => "Cheshvan",
+            HebrewMonth::Kislev => "Kislev",
+            HebrewMonth::Tevet => "Tevet",
+            HebrewMonth::Shevat => "Shevat",
+            HebrewMonth::Adar => "Adar",
+            HebrewMonth::AdarI => "Adar I",
+            HebrewMonth::AdarII => "Adar II",
+            HebrewMonth::Nisan => "Nisan",
+            HebrewMonth::Iyar => "Iyar",
+            HebrewMonth::Sivan => "Sivan",
+            HebrewMonth::Tammuz => "Tammuz",
+            HebrewMonth::Av => "Av",
+            HebrewMonth::Elul => "Elul",
         }
+    }
+}
+
+const HEBREW_COMMON_YEAR_MONTHS: [HebrewMonth; 12] = [
+    HebrewMonth::Tishrei,
+    HebrewMonth::Cheshvan,
+    HebrewMonth::Kislev,
+    HebrewMonth::Tevet,
+    HebrewMonth::Shevat,
+    HebrewMonth::Adar,
+    HebrewMonth::Nisan,
+    HebrewMonth::Iyar,
+    HebrewMonth::Sivan,
+    HebrewMonth::Tammuz,
+    HebrewMonth::Av,
+    HebrewMonth::Elul,
+];
 
-        let year_error: Result<u32, _> = year.try_into();
-        let year: u32;
-        match year_error{
-            Ok(n) => year = n,
-            Err(_) => return Err(CalendarError::InvalidInput),
+const HEBREW_LEAP_YEAR_MONTHS: [HebrewMonth; 13] = [
+    HebrewMonth::Tishrei,
+    HebrewMonth::Cheshvan,
+    HebrewMonth::Kislev,
+    HebrewMonth::Tevet,
+    HebrewMonth::Shevat,
+    HebrewMonth::AdarI,
+    HebrewMonth::AdarII,
+    HebrewMonth::Nisan,
+    HebrewMonth::Iyar,
+    HebrewMonth::Sivan,
+    HebrewMonth::Tammuz,
+    HebrewMonth::Av,
+    HebrewMonth::Elul,
+];
+
+// accepts common transliterations, ignoring case, spaces and apostrophes
+impl TryFrom<&str> for HebrewMonth {
+    type Error = CalendarError;
+
+    fn try_from(name: &str) -> Result<Self, Self::Error> {
+        match normalize_name(name).as_str() {
+            "tishrei" | "tishri" | "tishre" => Ok(HebrewMonth::Tishrei),
+            "cheshvan" | "chesvan" | "heshvan" | "hesvan" | "marcheshvan" | "marheshvan" | "marchesvan" => Ok(HebrewMonth::Cheshvan),
+            "kislev" | "kislew" | "chislev" => Ok(HebrewMonth::Kislev),
+            "tevet" | "teves" | "tebet" | "tebeth" => Ok(HebrewMonth::Tevet),
+            "shevat" | "shvat" | "shebat" | "sebat" => Ok(HebrewMonth::Shevat),
+            "adar" | "adar/adarii" => Ok(HebrewMonth::Adar),
+            "adari" | "adar1" | "adarrishon" | "adaraleph" | "adaralef" => Ok(HebrewMonth::AdarI),
+            "adarii" | "adar2" | "adarsheni" | "adarbet" | "adarbeth" | "veadar" | "vadar" => Ok(HebrewMonth::AdarII),
+            "nisan" | "nissan" => Ok(HebrewMonth::Nisan),
+            "iyar" | "iyyar" | "iyyur" => Ok(HebrewMonth::Iyar),
+            "sivan" | "siwan" => Ok(HebrewMonth::Sivan),
+            "tammuz" | "tamuz" => Ok(HebrewMonth::Tammuz),
+            "av" | "ab" | "menachemav" => Ok(HebrewMonth::Av),
+            "elul" => Ok(HebrewMonth::Elul),
+            _ => from_hebrew_script(name).ok_or_else(|| CalendarError::UnknownMonth(String::from(name))),
         }
+    }
+}
 
-        let is_leap_year = is_leap_year(year);
-        let is_leap_year_int = match is_leap_year {
-            true => 1,
-            false => 0,
-        };
+// names in Hebrew script, with or without points and the prefix ב ("in"),
+// as in בְּשְׁבָט
+fn from_hebrew_script(name: &str) -> Option<HebrewMonth> {
+    let name: String = normalize_name(name)
+        .chars()
+        .filter(|&c| !is_point(c) && !matches!(c, GERESH | GERSHAYIM | '"'))
+        .collect();
+    let month = |name: &str| match name {
+        "תשרי" => Some(HebrewMonth::Tishrei),
+        "חשון" | "חשוון" | "מרחשון" | "מרחשוון" => Some(HebrewMonth::Cheshvan),
+        "כסלו" | "כסליו" => Some(HebrewMonth::Kislev),
+        "טבת" => Some(HebrewMonth::Tevet),
+        "שבט" => Some(HebrewMonth::Shevat),
+        "אדר" => Some(HebrewMonth::Adar),
+        "אדרא" | "אדר1" | "אדרראשון" => Some(HebrewMonth::AdarI),
+        "אדרב" | "אדר2" | "אדרשני" | "ואדר" => Some(HebrewMonth::AdarII),
+        "ניסן" => Some(HebrewMonth::Nisan),
+        "אייר" | "איר" => Some(HebrewMonth::Iyar),
+        "סיון" | "סיוון" => Some(HebrewMonth::Sivan),
+        "תמוז" => Some(HebrewMonth::Tammuz),
+        "אב" | "מנחםאב" => Some(HebrewMonth::Av),
+        "אלול" => Some(HebrewMonth::Elul),
+        _ => None,
+    };
+    month(&name).or_else(|| name.strip_prefix('ב').and_then(month))
+}
+
+impl FromStr for HebrewMonth {
+    type Err = CalendarError;
+
+    fn from_str(name: &str) -> Result<Self, Self::Err> {
+        HebrewMonth::try_from(name)
+    }
+}
+
+impl std::fmt::Display for HebrewMonth {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(f, "{}", self.name())
+    }
+}
+
+// where the calendar is kept, for the festival days and Torah readings that
+// differ outside Israel
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+pub enum Location {
+    Israel,
+    Diaspora,
+}
 
-        if !is_leap_year && (month == String::from("Adar I")) {
-            return Err(CalendarError::InvalidInput);
+/*
+The kind of a Hebrew year, by how many days Cheshvan and Kislev have
+
+Chaserah: deficient, both have 29 days (353 or 383 days)
+Kesidrah: regular, Kislev has 30 (354 or 384 days)
+Shelemah: complete, both have 30 (355 or 385 days)
+ */
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+pub enum YearKind {
+    Chaserah,
+    Kesidrah,
+    Shelemah,
+}
+
+impl YearKind {
+    pub fn name(self) -> &'static str {
+        match self {
+            YearKind::Chaserah => "Chaserah",
+            YearKind::Kesidrah => "Kesidrah",
+            YearKind::Shelemah => "Shelemah",
         }
+    }
+
+    // the letter used for the kind in a keviyah
+    pub fn letter(self) -> char {
+        match self {
+            YearKind::Chaserah => 'ח',
+            YearKind::Kesidrah => 'כ',
+            YearKind::Shelemah => 'ש',
+        }
+    }
+}
+
+/*
+A rule postponing Rosh Hashanah from the day of the molad of Tishrei. Each
+moves it by a day. Days begin at 6 pm, so a molad in the evening is on the
+next day and the hours are counted from then
+
+MoladZaken: the molad is at or after noon
+LoAdu: Rosh Hashanah would be on Sunday, Wednesday or Friday. This also
+follows the other rules when they move it onto one of those days
+Gatarad: in a common year, the molad is on Tuesday at or after 9 hours
+and 204 parts (3:11:20 am)
+Betutakpat: after a leap year, the molad is on Monday at or after 15
+hours and 589 parts (9:32:43 am)
+ */
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+pub enum Dehiyyah {
+    MoladZaken,
+    LoAdu,
+    Gatarad,
+    Betutakpat,
+}
+
+impl Dehiyyah {
+    pub fn name(self) -> &'static str {
+        match self {
+            Dehiyyah::MoladZaken => "Molad Zaken",
+            Dehiyyah::LoAdu => "Lo ADU Rosh",
+            Dehiyyah::Gatarad => "GaTaRaD",
+            Dehiyyah::Betutakpat => "BeTU'TaKPaT",
+        }
+    }
+}
+
+/*
+A year of the Hebrew calendar and the characteristics calendar tables list
+for it: its length and kind, the weekdays of Rosh Hashanah and Pesach, the
+keviyah and the postponements of Rosh Hashanah
+ */
+#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Debug)]
+pub struct HebrewYear {
+    year: u32,
+    rosh_hashanah: JulianDayNumber,
+    length: u16,
+}
+
+impl HebrewYear {
+    // years outside the calendar's supported range give an error
+    pub fn new(year: u32) -> Result<HebrewYear, CalendarError> {
+        let next_year = hebrew_year(year as i64 + 1)?;
+        hebrew_year(year as i64)?;
+        let rosh_hashanah = supported::<HebrewDate>(JulianDayNumber::from_legacy(new_year_julian_day(year))?)?;
+        Ok(HebrewYear {
+            year,
+            rosh_hashanah,
+            length: (new_year_julian_day(next_year) - new_year_julian_day(year)) as u16,
+        })
+    }
+
+    pub fn year(&self) -> u32 {
+        self.year
+    }
+
+    pub fn is_leap(&self) -> bool {
+        is_leap_year(self.year)
+    }
+
+    // 353, 354 or 355 days, or 383, 384 or 385 in leap years
+    pub fn length(&self) -> u16 {
+        self.length
+    }
+
+    pub fn kind(&self) -> YearKind {
+        match self.length % 10 {
+            3 => YearKind::Chaserah,
+            4 => YearKind::Kesidrah,
+            _ => YearKind::Shelemah,
+        }
+    }
+
+    // the days of the months, Tishrei first
+    pub fn month_lengths(&self) -> Vec<u8> {
+        month_lengths(self.year)
+    }
+
+    // the day of 1 Tishrei
+    pub fn rosh_hashanah(&self) -> JulianDayNumber {
+        self.rosh_hashanah
+    }
+
+    // Monday, Tuesday, Thursday or Saturday
+    pub fn rosh_hashanah_weekday(&self) -> Weekday {
+        Weekday::from_julian_day_number(self.rosh_hashanah)
+    }
+
+    // the weekday of 15 Nisan
+    pub fn pesach_weekday(&self) -> Weekday {
+        let nisan = match self.is_leap() {
+            true => 7,
+            false => 6,
+        };
+        let days: u32 = self.month_lengths()[..nisan].iter().map(|&d| d as u32).sum::<u32>() + 14;
+        Weekday::from_number(((self.rosh_hashanah_weekday().number() as u32 + days) % 7) as u8)
+    }
+
+    /*
+    The three letters naming the year's type: the weekday of Rosh
+    Hashanah, the kind of year, and the weekday of Pesach, with the
+    weekdays counted from א for Sunday. "בחג" is a deficient year starting
+    on Monday with Pesach on Tuesday
+     */
+    pub fn keviyah(&self) -> String {
+        const WEEKDAY_LETTERS: [char; 7] = ['א', 'ב', 'ג', 'ד', 'ה', 'ו', 'ז'];
+        [
+            WEEKDAY_LETTERS[self.rosh_hashanah_weekday().number() as usize],
+            self.kind().letter(),
+            WEEKDAY_LETTERS[self.pesach_weekday().number() as usize],
+        ]
+        .iter()
+        .collect()
+    }
+
+    pub fn molad(&self, month: HebrewMonth) -> Result<Molad, CalendarError> {
+        Molad::new(self.year, month)
+    }
+
+    // the rules that moved Rosh Hashanah from the day of the molad, in the
+    // order they apply
+    pub fn dehiyyot(&self) -> Vec<Dehiyyah> {
+        dehiyyot(molad_tishrei_parts(self.year), self.year)
+    }
+}
+
+/*
+The molad (mean new moon) of a month, in Jerusalem mean time as it is
+announced: the day, weekday and time from midnight in hours, minutes and
+chalakim. A chelek (part) is 1/18 of a minute, so 1080 make an hour.
+
+The molad of Tishrei of year 1 (BaHaRaD) was on Sunday at 23:11 and 6
+chalakim, the night before the Monday. Each month's molad is 29 days, 12
+hours, 44 minutes and 1 chelek after the last
+ */
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub struct Molad {
+    pub day: JulianDayNumber,
+    pub weekday: Weekday,
+    pub hour: u8,
+    pub minute: u8,
+    pub chalakim: u8,
+}
+
+impl Molad {
+    // the molad of the month, which must be in the year (Adar I and Adar II
+    // only in leap years)
+    pub fn new(year: u32, month: HebrewMonth) -> Result<Molad, CalendarError> {
+        let months_since_tishrei = month.number(hebrew_year(year as i64)?)? as i128 - 1;
+        let parts = molad_tishrei_parts(year) + months_since_tishrei * MOLAD_LENGTH_PARTS;
+        let day = JulianDayNumber::from_legacy(parts / DAY_LENGTH_PARTS)?;
+        let time = parts % DAY_LENGTH_PARTS;
+        Ok(Molad {
+            day,
+            weekday: Weekday::from_julian_day_number(day),
+            hour: (time / HOUR_PARTS) as u8,
+            minute: (time % HOUR_PARTS / MINUTE_PARTS) as u8,
+            chalakim: (time % MINUTE_PARTS) as u8,
+        })
+    }
+
+    // the time since midnight in chalakim
+    pub fn parts(&self) -> u32 {
+        self.hour as u32 * HOUR_PARTS as u32 + self.minute as u32 * MINUTE_PARTS as u32 + self.chalakim as u32
+    }
+
+    // the julian day with the time as a fraction, counted from noon as
+    // julian days are, in Jerusalem mean time rather than universal time
+    pub fn julian_day(&self) -> f64 {
+        self.day.value() as f64 - 0.5 + self.parts() as f64 / DAY_LENGTH_PARTS as f64
+    }
+}
+
+// e.g. "Wednesday 21:09 and 1 chelek"
+impl std::fmt::Display for Molad {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        let parts = match self.chalakim {
+            1 => "chelek",
+            _ => "chalakim",
+        };
+        write!(f, "{} {:02}:{:02} and {} {}", self.weekday.name(), self.hour, self.minute, self.chalakim, parts)
+    }
+}
+
+// the molad of a month of the year, see Molad
+pub fn molad(year: u32, month: HebrewMonth) -> Result<Molad, CalendarError> {
+    Molad::new(year, month)
+}
+
+impl HebrewDate {
+    pub fn hebrew_year(&self) -> Result<HebrewYear, CalendarError> {
+        HebrewYear::new(self.year)
+    }
+
+    // e.g. "Adar II", empty if the month does not exist
+    pub fn month_name(&self) -> &'static str {
+        HebrewMonth::from_number(self.month, self.year).map(|m| m.name()).unwrap_or("")
+    }
+
+    // in Hebrew script with Hebrew numerals, e.g. "ט״ו בשבט תשפ״ה". The
+    // thousands of the year are left out, as usual
+    pub fn to_hebrew_string(&self) -> String {
+        format!(
+            "{} ב{} {}",
+            to_hebrew_numeral(self.day as u32),
+            self.localized_month_name("he"),
+            to_hebrew_year(self.year, true)
+        )
+    }
+
+    // the day is checked when the date is converted
+    pub fn from_month(day: u8, month: HebrewMonth, year: u32) -> Result<HebrewDate, CalendarError> {
+        Ok(HebrewDate {
+            year,
+            month: month.number(year)?,
+            day,
+        })
+    }
+}
+
+impl Calendar for HebrewDate {
+    fn to_julian_day_number(&self) -> Result<JulianDayNumber, CalendarError> {
+        supported::<Self>(JulianDayNumber::from_legacy(from_hebrew(self)?)?)
+    }
+
+    fn from_julian_day_number(day: JulianDayNumber) -> Result<Self, CalendarError> where Self: Sized {
+        to_hebrew(supported::<Self>(day)?.to_legacy())
+    }
+
+    // 1 Tishrei AM 1 to the end of AM 4294967292
+    fn supported_range() -> RangeInclusive<JulianDayNumber> where Self: Sized {
+        JulianDayNumber::new(347998)..=JulianDayNumber::new(1568723502857)
+    }
+    
+    fn new(day: u32, month: String, year: i32, _era: String) -> Result<Self, CalendarError>  where Self: Sized{
+        // checked before the month lengths, which need a year after the epoch
+        let year: u32 = match year.try_into() {
+            Ok(n) if n > 0 => n,
+            _ => return Err(CalendarError::InvalidYear { year: year as i64, era: String::from("AM") }),
+        };
 
-       
-        let month_num = match month.as_str() {
-            "Tishrei" => 1,
-            "Chesvan" => 2,
-            "Kislev" => 3,
-            "Tevet" => 4,
-            "Shevat" => 5,
-            "Adar I" => 6,
-            "Adar/Adar II" => 6 + is_leap_year_int,
-            "Nisan" => 7 + is_leap_year_int,
-            "Iyar" => 8 + is_leap_year_int,
-            "Sivan" => 9 + is_leap_year_int,
-            "Tammuz" => 10 + is_leap_year_int,
-            "Av" => 11 + is_leap_year_int,
-            "Elul" => 12 + is_leap_year_int,
-            _ => 100,
+        let month_num = HebrewMonth::try_from(month.as_str())?.number(year)?;
+
+        let day: u8 = match day.try_into() {
+            Ok(n) => n,
+            Err(_) => return Err(CalendarError::DayOutOfRange {
+                day,
+                max: month_lengths(year)[month_num as usize - 1] as u32,
+            }),
         };
 
         return Ok(HebrewDate  {
@@ -70,37 +513,68 @@ impl Calendar for HebrewDate {
     }
 }
 
+fn hebrew_year(year: i64) -> Result<u32, CalendarError> {
+    match year.try_into() {
+        Ok(n) if n > 0 => Ok(n),
+        _ => Err(CalendarError::Overflow),
+    }
+}
+
+/*
+Adding years keeps the named month. Adar I and Adar II become Adar in a
+common year, and Adar of a common year becomes Adar II in a leap year
+(the month in which Purim and most anniversaries from Adar are kept)
+ */
+impl CalendarArithmetic for HebrewDate {
+    fn fields(&self) -> (i64, u8, u8) {
+        (self.year as i64, self.month, self.day)
+    }
+
+    fn from_fields(year: i64, month: u8, day: u8) -> Result<Self, CalendarError> {
+        Ok(HebrewDate { year: hebrew_year(year)?, month, day })
+    }
+
+    fn is_leap_year(&self) -> bool {
+        is_leap_year(self.year)
+    }
+
+    fn month_count(year: i64) -> Result<u8, CalendarError> {
+        match is_leap_year(hebrew_year(year)?) {
+            true => Ok(13),
+            false => Ok(12),
+        }
+    }
+
+    // 7 leap years in every 19
+    const MONTH_CYCLE: Option<(i64, i64)> = Some((19, 235));
+
+    fn month_in_year(from_year: i64, month: u8, to_year: i64) -> Result<u8, CalendarError> {
+        let to_year = hebrew_year(to_year)?;
+        let month = match HebrewMonth::from_number(month, hebrew_year(from_year)?)? {
+            HebrewMonth::AdarI | HebrewMonth::AdarII if !is_leap_year(to_year) => HebrewMonth::Adar,
+            m => m,
+        };
+        month.number(to_year)
+    }
+}
+
 impl std::fmt::Display for HebrewDate {
     fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
         let year_string = self.year.to_string();
         let year = year_string.as_str();
 
-        let month: &str = match self.month {
-            1 => "January",
-            2 => "February",
-            3 => "March",
-            4 => "April",
-            5 => "May",
-            6 => "June",
-            7 => "July",
-            8 => "August",
-            9 => "September",
-            10 => "October",
-            11 => "November",
-            12 => "December",
-            _ => "",
-        };
-        
         let day_string = self.day.to_string();
         let day = day_string.as_str();
         write!(
             f,
             "year: {}, month: {}, day: {}",
-            year, month, day
+            year, self.month_name(), day
         )
     }
 }
 
+const MINUTE_PARTS: i128 = 18;
+const HOUR_PARTS: i128 = 60 * MINUTE_PARTS;
 const DAY_LENGTH_PARTS: i128 = 24 * 60 * 18;
 const MOLAD_LENGTH_PARTS: i128 = 765433;
 const NINETEEN_YEAR_CYCLE_MONTHS: i128 = 19 * 12 + 7;
@@ -118,9 +592,6 @@ const MOLAD_TISHREI_1_PARTS: i128 =
 const COMMON_YEAR_MONTH_LENGTHS: [u8; 12] = [30, 29, 29, 29, 30, 29, 30, 29, 30, 29, 30, 29];
 const LEAP_YEAR_MONTH_LENGTHS: [u8; 13] = [30, 29, 29, 29, 30, 30, 29, 30, 29, 30, 29, 30, 29];
 //const LEAP_YEARS: [u8; 7] = [3, 6, 8, 11, 14, 17, 19];
-const MOLAD_NUM_LENGTH: [u8; 19] = [
-    12, 12, 13, 12, 12, 13, 12, 13, 12, 12, 13, 12, 12, 13, 12, 12, 13, 12, 13,
-];
 const MOLAD_NUM_LENGTH_SUM: [u8; 19] = [
     12,
     12 * 2,
@@ -149,270 +620,167 @@ fn is_leap_year(year: u32) -> bool {
         6 => return true,
         8 => return true,
         11 => return true,
+        14 => return true,
         17 => return true,
         0 => return true, // year 19 in cycle
         _ => return false,
     }
 }
 
-fn tishrei_1_julian_day(molad_tishrei_parts: i128, year: u32) -> (i128, i128) {
-    let molad_tishrei_julian_day = molad_tishrei_parts / DAY_LENGTH_PARTS;
-    let time_parts = molad_tishrei_parts % DAY_LENGTH_PARTS;
+// the day of a molad and its time in parts, counted from 6 pm the evening
+// before, when the Hebrew day begins
+fn molad_day(molad_parts: i128) -> (i128, i128) {
+    let parts = molad_parts + 6 * HOUR_PARTS;
+    (parts / DAY_LENGTH_PARTS, parts % DAY_LENGTH_PARTS)
+}
+
+// the postponements of rosh hashanah from the day of the molad
+fn dehiyyot(molad_tishrei_parts: i128, year: u32) -> Vec<Dehiyyah> {
+    let (molad_tishrei_julian_day, time_parts) = molad_day(molad_tishrei_parts);
     let molad_tishrei_weekday = (molad_tishrei_julian_day + 2) % 7;
     //println!("molad_tishrei_weekday: {}", molad_tishrei_weekday);
 
-    let mut postponement: i128 = 0;
     //postponement A
     if (molad_tishrei_weekday == 0) | (molad_tishrei_weekday == 3) | (molad_tishrei_weekday == 5) {
-        postponement += 1;
+        vec![Dehiyyah::LoAdu]
     // postponement B
-    } else if time_parts > (DAY_LENGTH_PARTS / 2) {
-        // unclear what happens when the molad falls on sunday, wednesday or friday after noon
-        postponement += 1;
-        if (molad_tishrei_weekday == 6)
+    } else if time_parts >= 18 * HOUR_PARTS {
+        // after noon on sunday, wednesday or friday only A applies
+        match (molad_tishrei_weekday == 6)
             | (molad_tishrei_weekday == 2)
             | (molad_tishrei_weekday == 4)
         {
-            postponement += 1;
+            true => vec![Dehiyyah::MoladZaken, Dehiyyah::LoAdu],
+            false => vec![Dehiyyah::MoladZaken],
         }
 
-    // postponement C
-    } else if (time_parts > (3 * 60 * 18 + 204))
+    // postponement C, which moves it to wednesday and then A to thursday
+    } else if (time_parts >= (9 * HOUR_PARTS + 204))
         & (molad_tishrei_weekday == 2)
         & !is_leap_year(year)
     {
-        postponement += 2;
+        vec![Dehiyyah::Gatarad, Dehiyyah::LoAdu]
     // postponement D
-    } else if (time_parts > (9 * 60 * 18 + 589))
+    } else if (time_parts >= (15 * HOUR_PARTS + 589))
         & (molad_tishrei_weekday == 1)
         & is_leap_year(year - 1)
     {
-        postponement += 1;
+        vec![Dehiyyah::Betutakpat]
+    } else {
+        Vec::new()
     }
+}
 
-    return (postponement, postponement + molad_tishrei_julian_day);
+fn tishrei_1_julian_day(molad_tishrei_parts: i128, year: u32) -> (i128, i128) {
+    let postponement = dehiyyot(molad_tishrei_parts, year).len() as i128;
+    (postponement, postponement + molad_day(molad_tishrei_parts).0)
 }
 
-pub(crate) fn to_hebrew(julian_day: i128) -> Result<HebrewDate, CalendarError> {
-    let parts_since_molad_tishrei_1;
-    {
-        let temp = julian_day.checked_mul(DAY_LENGTH_PARTS);
-        if temp.is_none() {
-            return Err(CalendarError::Overflow);
-        }
-        parts_since_molad_tishrei_1 = temp.unwrap() - MOLAD_TISHREI_1_PARTS;
-    }
+// the number of "julian parts" at molad tishrei of the given year
+fn molad_tishrei_parts(year: u32) -> i128 {
+    let cycles: i128 = ((year - 1) / 19).into();
+    let years_after_cycle = ((year - 1) % 19) as usize;
+    let months_from_years_after_cycle: i128 = match years_after_cycle {
+        0 => 0,
+        _ => MOLAD_NUM_LENGTH_SUM[years_after_cycle - 1] as i128,
+    };
+    (cycles * NINETEEN_YEAR_CYCLE_MONTHS + months_from_years_after_cycle) * MOLAD_LENGTH_PARTS + MOLAD_TISHREI_1_PARTS
+}
 
-    // zeroeth: get rid of previous 19-year cycles
-    let cycles = parts_since_molad_tishrei_1 / ((19 * 12 + 7) * MOLAD_LENGTH_PARTS); // if this is 1, that means it is year 20. if it is 2, it is year 39
-    let mut parts_left_since_molad =
-        parts_since_molad_tishrei_1 % ((19 * 12 + 7) * MOLAD_LENGTH_PARTS);
+fn new_year_julian_day(year: u32) -> i128 {
+    let (_, julian_day) = tishrei_1_julian_day(molad_tishrei_parts(year), year);
+    julian_day
+}
 
-    // first: figure out molad tisrhei that precedes this day or falls on the same day as. both when it occurs and what year it is
-    let mut year = cycles * 19 + 1;
-    let mut i = 0;
-    while parts_left_since_molad >= (MOLAD_NUM_LENGTH[i] as i128) * MOLAD_LENGTH_PARTS {
-        if i >= 19 {
-            println!("SOMETHING WENT WRONG");
+// Cheshvan and Kislev each have 29 or 30 days, depending on how many days
+// the postponements of rosh hashanah leave in the year
+fn month_lengths(year: u32) -> Vec<u8> {
+    let mut lengths = match is_leap_year(year) {
+        true => LEAP_YEAR_MONTH_LENGTHS.to_vec(),
+        false => COMMON_YEAR_MONTH_LENGTHS.to_vec(),
+    };
+    let year_length = new_year_julian_day(year + 1) - new_year_julian_day(year);
+    match year_length % 10 {
+        // complete year (355 or 385 days)
+        5 => {
+            lengths[1] += 1;
+            lengths[2] += 1;
         }
-        parts_left_since_molad =
-            parts_left_since_molad - (MOLAD_NUM_LENGTH[i] as i128) * MOLAD_LENGTH_PARTS;
-        year += 1;
-        i += 1;
+        // regular year (354 or 384 days)
+        4 => lengths[2] += 1,
+        // deficient year (353 or 383 days)
+        _ => {}
     }
+    lengths
+}
 
-    let new_year = match year.try_into() {
-        Ok(num) => Some(num),
-        Err(_e) => None,
-    };
-    let mut year;
-    match new_year {
-        Some(num) => year = num,
-        None => return Err(CalendarError::Overflow),
+pub(crate) fn to_hebrew(julian_day: i128) -> Result<HebrewDate, CalendarError> {
+    let first_day = new_year_julian_day(1);
+    if julian_day < first_day {
+        return Err(CalendarError::Overflow);
     }
 
-    let last_tishrei_1_julian_day: i128;
-    {
-        /*let last_molad_tishrei_julian_day = (julian_day * DAY_LENGTH_PARTS - parts_left_since_molad) / DAY_LENGTH_PARTS;
-        println!("last_molad_tishrei_julian_day: {}", last_molad_tishrei_julian_day);
-        let time_parts = (julian_day * DAY_LENGTH_PARTS - parts_left_since_molad) % DAY_LENGTH_PARTS;*/
-        let last_molad_tishrei_parts = julian_day * DAY_LENGTH_PARTS - parts_left_since_molad;
-        (_, last_tishrei_1_julian_day) = tishrei_1_julian_day(last_molad_tishrei_parts, year);
+    // estimate the year from the mean length of a year, which is within one
+    // year of the right answer
+    let estimate = match (julian_day - first_day).checked_mul(19 * DAY_LENGTH_PARTS) {
+        Some(n) => n / (NINETEEN_YEAR_CYCLE_MONTHS * MOLAD_LENGTH_PARTS) + 1,
+        None => return Err(CalendarError::Overflow),
+    };
+    if estimate >= (u32::MAX - 2) as i128 {
+        return Err(CalendarError::Overflow);
+    }
+    let mut year = estimate as u32;
+    while new_year_julian_day(year + 1) <= julian_day {
+        year += 1;
+    }
+    while (year > 1) & (new_year_julian_day(year) > julian_day) {
+        year -= 1;
     }
 
-    //let next_tishrei_1_julian_day: i128;
-    let next_tishrei_1_postponement: i128;
-    {
-        let next_molad_tishrei_parts: i128;
-        if is_leap_year(year) {
-            next_molad_tishrei_parts =
-                julian_day * DAY_LENGTH_PARTS - parts_left_since_molad + MOLAD_LENGTH_PARTS * 13;
-        } else {
-            next_molad_tishrei_parts =
-                julian_day * DAY_LENGTH_PARTS - parts_left_since_molad + MOLAD_LENGTH_PARTS * 12;
-        }
-        (next_tishrei_1_postponement, _) =
-            tishrei_1_julian_day(next_molad_tishrei_parts, year + 1);
-    }
-
-    //println!("last_tishrei_1_julian_day: {}", last_tishrei_1_julian_day);
-    //println!("next_tishrei_1_julian_day: {}", next_tishrei_1_julian_day);
-
-    //let day: i128;
-
-    // if we are at the end of a year but after molad tishrei of the next
-    let mut days_left;
-    let mut month;
-    if last_tishrei_1_julian_day > julian_day {
-        year = year - 1;
-        if is_leap_year(year) {
-            return Ok(HebrewDate {
-                year: year,
-                month: 13,
-                day: (29 - (last_tishrei_1_julian_day - julian_day - 1)) as u8,
-            });
-        } else {
-            return Ok(HebrewDate {
-                year: year,
-                month: 12,
-                day: (29 - (last_tishrei_1_julian_day - julian_day - 1)) as u8,
-            });
-        }
-    } else {
-        days_left = julian_day - last_tishrei_1_julian_day;
-        //println!("days_left: {}", days_left);
-        month = 0;
-        if is_leap_year(year) {
-            let postponements: [u8; 13];
-            if next_tishrei_1_postponement == 1 {
-                postponements = [0, 0, 1, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0]
-            } else if next_tishrei_1_postponement == 2 {
-                postponements = [0, 1, 1, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0]
-            } else {
-                postponements = [0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0]
-            }
-            while days_left >= (LEAP_YEAR_MONTH_LENGTHS[month] + postponements[month]) as i128 {
-                days_left =
-                    days_left - (LEAP_YEAR_MONTH_LENGTHS[month] + postponements[month]) as i128;
-                month = month + 1;
-            }
-        } else {
-            let postponements: [u8; 12];
-            if next_tishrei_1_postponement == 1 {
-                postponements = [0, 0, 1, 0, 0, 0, 0, 0, 0, 0, 0, 0]
-            } else if next_tishrei_1_postponement == 2 {
-                postponements = [0, 1, 1, 0, 0, 0, 0, 0, 0, 0, 0, 0]
-            } else {
-                postponements = [0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0]
-            }
-
-            while days_left >= (COMMON_YEAR_MONTH_LENGTHS[month] + postponements[month]) as i128 {
-                days_left =
-                    days_left - (COMMON_YEAR_MONTH_LENGTHS[month] + postponements[month]) as i128;
-                month = month + 1;
-            }
-        }
-
-        return Ok(HebrewDate {
-            year: year,
-            month: month as u8 + 1,
-            day: days_left as u8 + 1,
-        });
+    let mut days_left = julian_day - new_year_julian_day(year);
+    let mut month = 0;
+    let lengths = month_lengths(year);
+    while (month + 1 < lengths.len()) && (days_left >= lengths[month] as i128) {
+        days_left -= lengths[month] as i128;
+        month += 1;
     }
+
+    Ok(HebrewDate {
+        year,
+        month: month as u8 + 1,
+        day: days_left as u8 + 1,
+    })
 }
 
 fn from_hebrew(date: &HebrewDate) -> Result<i128, CalendarError> {
-    if date.month > 13 {
-        return Err(CalendarError::InvalidInput);
-    }
-    if !is_leap_year(date.year) & (date.month == 13) {
-        return Err(CalendarError::InvalidInput);
+    if date.year < 1 {
+        return Err(CalendarError::InvalidYear { year: 0, era: String::from("AM") });
     }
-    if (date.year < 1) | (date.day < 1) | (date.month < 1) {
-        return Err(CalendarError::InvalidInput);
+    if date.year >= u32::MAX - 1 {
+        return Err(CalendarError::Overflow);
     }
-    
-    // find out julian day of the first day of the year
-    let tishrei_1_julian_day_num: i128;
-    let molad_tishrei_parts;
-    {
-        let cycles: i128 = ((date.year - 1) / 19).into();
-        //let years_after_cycle: i128 = ((date.year - 1) % 19).into();
-        let years_after_cycle: i128 = ((date.year - 1) % 19).into();
-
-        let months_from_years_after_cycle: i128 = match years_after_cycle {
-            0 => 0,
-            _ => MOLAD_NUM_LENGTH_SUM[years_after_cycle as usize - 1] as i128
-        };
 
-        println!("estimated year: {}", cycles * 19 + years_after_cycle);
-        let molad_tishrei_molad_num: i128 = cycles * (19 * 12 + 7) + months_from_years_after_cycle;
-        molad_tishrei_parts = molad_tishrei_molad_num * MOLAD_LENGTH_PARTS + MOLAD_TISHREI_1_PARTS;
-
-        (_, tishrei_1_julian_day_num) = tishrei_1_julian_day(
-            molad_tishrei_parts,
-            date.year,
-        );
-
-        // check if the day is too larger for the month
-        
+    let lengths = month_lengths(date.year);
+    if (date.month < 1) | (date.month as usize > lengths.len()) {
+        return Err(CalendarError::UnknownMonth(date.month.to_string()));
+    }
+    let max = lengths[date.month as usize - 1];
+    if (date.day < 1) | (date.day > max) {
+        return Err(CalendarError::DayOutOfRange { day: date.day as u32, max: max as u32 });
     }
 
-    // find out how many days this date is from the first day of the year
-    let mut days_since_tishrei_1: i128 = 0;
-    {
-        days_since_tishrei_1 += date.day as i128 - 1;
-        let mut month = date.month as usize;
-        if is_leap_year(date.year) {
-            let (next_tishrei_1_postponement, _) = tishrei_1_julian_day(molad_tishrei_parts + 13 * MOLAD_LENGTH_PARTS, date.year);
-            let postponements: [u8; 13];
-            if next_tishrei_1_postponement == 1 {
-                postponements = [0, 0, 1, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0]
-            } else if next_tishrei_1_postponement == 2 {
-                postponements = [0, 1, 1, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0]
-            } else {
-                postponements = [0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0]
-            }
-
-            if date.day > (LEAP_YEAR_MONTH_LENGTHS[date.month as usize - 1]  + postponements[date.month as usize - 1]) {
-                return Err(CalendarError::InvalidInput);
-            }
+    // days from the first day of the year to the first day of the month
+    let month_days: i128 = lengths[..date.month as usize - 1].iter().map(|&n| n as i128).sum();
 
-            while month - 1 >= 1 {
-                days_since_tishrei_1 += (LEAP_YEAR_MONTH_LENGTHS[month - 1 - 1] + postponements[month - 1 - 1]) as i128;
-                month -= 1;
-            }
-        } else {
-            let (next_tishrei_1_postponement, _) = tishrei_1_julian_day(molad_tishrei_parts + 12 * MOLAD_LENGTH_PARTS, date.year);
-            let postponements: [u8; 12];
-            if next_tishrei_1_postponement == 1 {
-                postponements = [0, 0, 1, 0, 0, 0, 0, 0, 0, 0, 0, 0]
-            } else if next_tishrei_1_postponement == 2 {
-                postponements = [0, 1, 1, 0, 0, 0, 0, 0, 0, 0, 0, 0]
-            } else {
-                postponements = [0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0]
-            }
-
-            if date.day > (COMMON_YEAR_MONTH_LENGTHS[date.month as usize - 1]  + postponements[date.month as usize - 1]) {
-                return Err(CalendarError::InvalidInput);
-            }
+    Ok(new_year_julian_day(date.year) + month_days + date.day as i128 - 1)
+}
 
-            while month > 1 {  
-                days_since_tishrei_1 += (COMMON_YEAR_MONTH_LENGTHS[month - 1] + postponements[month - 1]) as i128;
-                month -= 1;
-            }
-        }
+impl DateFormat for HebrewDate {
+    const CALENDAR: &'static str = "Hebrew";
 
-        /*if is_leap_year(date.year) {
-            
-        } else {
-            while month > 1 {
-                days_since_tishrei_1 += COMMON_YEAR_MONTH_LENGTHS[month - 1] as i128;
-                month -= 1;
-            }
-        }*/
+    fn month_name(&self) -> String {
+        String::from(HebrewDate::month_name(self))
     }
-    return Ok(tishrei_1_julian_day_num + days_since_tishrei_1);
 }
 
 impl Into<GenericDate> for HebrewDate{
@@ -441,7 +809,182 @@ impl Into<GenericDate> for HebrewDate{
            era: false,
            year: self.year,
            month_name: month_name,
-           day: self.day 
+           day: self.day,
+           is_leap_day: false,
+           weekday: 0,
+           error: String::new(),
+           month_text: String::new(),
+           weekday_text: String::new(),
+           era_text: String::new(),
         }
     }
-}
\ No newline at end of file
+}
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::julian_gregorian::GregorianCalendar;
+
+    fn gregorian(day: u32, month: &str, year: i32) -> JulianDayNumber {
+        GregorianCalendar::new(day, month.into(), year, "AD".into())
+            .unwrap()
+            .to_julian_day_number()
+            .unwrap()
+    }
+
+    fn hebrew(day: u8, month: HebrewMonth, year: u32) -> HebrewDate {
+        HebrewDate::from_month(day, month, year).unwrap()
+    }
+
+    #[test]
+    fn rosh_hashanah() {
+        let new_years = [
+            (5771, (9, "September", 2010)),
+            (5772, (29, "September", 2011)),
+            (5784, (16, "September", 2023)),
+            (5785, (3, "October", 2024)),
+            (5786, (23, "September", 2025)),
+        ];
+        for &(year, (day, month, g_year)) in new_years.iter() {
+            assert_eq!(hebrew(1, HebrewMonth::Tishrei, year).to_julian_day_number(), Ok(gregorian(day, month, g_year)));
+            assert_eq!(HebrewDate::from_julian_day_number(gregorian(day, month, g_year)), Ok(hebrew(1, HebrewMonth::Tishrei, year)));
+        }
+    }
+
+    // year 14 of the 19 year cycle is a leap year
+    #[test]
+    fn leap_year_14_of_the_cycle() {
+        assert_eq!(5771 % 19, 14);
+        assert!(is_leap_year(5771));
+        assert_eq!(month_lengths(5771).len(), 13);
+        // Pesach 5771 was on 19 April 2011
+        assert_eq!(hebrew(15, HebrewMonth::Nisan, 5771).to_julian_day_number(), Ok(gregorian(19, "April", 2011)));
+        assert_eq!(hebrew(14, HebrewMonth::AdarII, 5771).to_julian_day_number(), Ok(gregorian(20, "March", 2011)));
+    }
+
+    fn molad_on(weekday: i128, parts: i128) -> i128 {
+        let day = (2451545..2451552).find(|d| (d + 2) % 7 == weekday).unwrap();
+        day * DAY_LENGTH_PARTS + parts
+    }
+
+    // a molad exactly on a postponement limit is postponed
+    #[test]
+    fn postponement_limits_are_inclusive() {
+        // molad zaken from noon, here on a monday
+        let noon = molad_on(1, DAY_LENGTH_PARTS / 2);
+        assert_eq!(dehiyyot(noon, 5785), vec![Dehiyyah::MoladZaken]);
+        assert_eq!(dehiyyot(noon - 1, 5785), vec![Dehiyyah::Betutakpat]);
+        let noon = molad_on(4, DAY_LENGTH_PARTS / 2);
+        assert_eq!(tishrei_1_julian_day(noon, 5785).0, 2);
+        assert_eq!(tishrei_1_julian_day(noon - 1, 5785).0, 0);
+
+        // a molad from 6 pm is on the next day, so one on sunday evening is
+        // on monday and not postponed
+        let evening = molad_on(0, 18 * HOUR_PARTS);
+        assert_eq!(dehiyyot(evening, 5785), Vec::new());
+        assert_eq!(tishrei_1_julian_day(evening, 5785).1, evening / DAY_LENGTH_PARTS + 1);
+        assert_eq!(dehiyyot(evening - 1, 5785), vec![Dehiyyah::LoAdu]);
+
+        // gatarad, in a common year (5785)
+        let gatarad = molad_on(2, 3 * 60 * 18 + 204);
+        assert_eq!(dehiyyot(gatarad, 5785), vec![Dehiyyah::Gatarad, Dehiyyah::LoAdu]);
+        assert_eq!(dehiyyot(gatarad - 1, 5785), Vec::new());
+
+        // betutakpat, after a leap year (5784)
+        let betutakpat = molad_on(1, 9 * 60 * 18 + 589);
+        assert_eq!(dehiyyot(betutakpat, 5785), vec![Dehiyyah::Betutakpat]);
+        assert_eq!(dehiyyot(betutakpat - 1, 5785), Vec::new());
+    }
+
+    // Cheshvan and Kislev follow the length of the year
+    #[test]
+    fn cheshvan_and_kislev_lengths() {
+        // 5784 is deficient (383 days), 5785 complete (355) and 5786 regular (354)
+        assert_eq!(&month_lengths(5784)[1..3], &[29, 29]);
+        assert_eq!(&month_lengths(5785)[1..3], &[30, 30]);
+        assert_eq!(&month_lengths(5786)[1..3], &[29, 30]);
+
+        assert_eq!(hebrew(30, HebrewMonth::Cheshvan, 5785).to_julian_day_number(), Ok(gregorian(1, "December", 2024)));
+        assert_eq!(hebrew(30, HebrewMonth::Kislev, 5785).to_julian_day_number(), Ok(gregorian(31, "December", 2024)));
+        assert!(hebrew(30, HebrewMonth::Cheshvan, 5784).to_julian_day_number().is_err());
+        assert!(hebrew(30, HebrewMonth::Kislev, 5784).to_julian_day_number().is_err());
+        assert_eq!(HebrewDate::from_julian_day_number(gregorian(1, "December", 2024)), Ok(hebrew(30, HebrewMonth::Cheshvan, 5785)));
+    }
+
+    #[test]
+    fn every_day_converts_back() {
+        let first = hebrew(1, HebrewMonth::Tishrei, 5700).to_julian_day_number().unwrap();
+        let last = hebrew(1, HebrewMonth::Tishrei, 5900).to_julian_day_number().unwrap();
+        for day in first.value()..last.value() {
+            let day = JulianDayNumber::new(day);
+            assert_eq!(HebrewDate::from_julian_day_number(day).unwrap().to_julian_day_number(), Ok(day));
+        }
+        for year in 5700..5900 {
+            let length: i64 = month_lengths(year).iter().map(|&n| n as i64).sum();
+            assert_eq!(length as i128, new_year_julian_day(year + 1) - new_year_julian_day(year));
+        }
+    }
+
+    #[test]
+    fn year_characteristics() {
+        let years = [
+            (5782, 384, YearKind::Kesidrah, "גכז", Weekday::Tuesday, Vec::new()),
+            (5783, 355, YearKind::Shelemah, "בשה", Weekday::Monday, Vec::new()),
+            (5784, 383, YearKind::Chaserah, "זחג", Weekday::Saturday, vec![Dehiyyah::LoAdu]),
+            (5785, 355, YearKind::Shelemah, "השא", Weekday::Thursday, Vec::new()),
+            (5786, 354, YearKind::Kesidrah, "גכה", Weekday::Tuesday, vec![Dehiyyah::MoladZaken]),
+        ];
+        for (year, length, kind, keviyah, weekday, dehiyyot) in years.iter() {
+            let hebrew_year = HebrewYear::new(*year).unwrap();
+            assert_eq!(hebrew_year.length(), *length, "{}", year);
+            assert_eq!(hebrew_year.kind(), *kind, "{}", year);
+            assert_eq!(hebrew_year.keviyah(), *keviyah, "{}", year);
+            assert_eq!(hebrew_year.rosh_hashanah_weekday(), *weekday, "{}", year);
+            assert_eq!(&hebrew_year.dehiyyot(), dehiyyot, "{}", year);
+        }
+        // Pesach 5785 was on Sunday 13 April 2025
+        assert_eq!(HebrewYear::new(5785).unwrap().pesach_weekday(), Weekday::Sunday);
+        assert_eq!(HebrewYear::new(5785).unwrap().rosh_hashanah(), gregorian(3, "October", 2024));
+    }
+
+    #[test]
+    fn molads() {
+        let molads = [
+            (5782, HebrewMonth::Tishrei, gregorian(6, "September", 2021), (23, 27, 11)),
+            (5784, HebrewMonth::Tishrei, gregorian(15, "September", 2023), (5, 49, 0)),
+            (5785, HebrewMonth::Tishrei, gregorian(3, "October", 2024), (3, 21, 13)),
+            (5786, HebrewMonth::Tishrei, gregorian(22, "September", 2025), (12, 10, 7)),
+            (5782, HebrewMonth::Nisan, gregorian(1, "April", 2022), (16, 36, 0)),
+            (5784, HebrewMonth::Nisan, gregorian(8, "April", 2024), (22, 57, 7)),
+            (5785, HebrewMonth::Nisan, gregorian(29, "March", 2025), (7, 46, 1)),
+        ];
+        for &(year, month, day, (hour, minute, chalakim)) in molads.iter() {
+            let expected = Molad { day, weekday: Weekday::from_julian_day_number(day), hour, minute, chalakim };
+            assert_eq!(molad(year, month), Ok(expected), "{} {}", month, year);
+        }
+
+        let tishrei = molad(5784, HebrewMonth::Tishrei).unwrap();
+        assert_eq!(tishrei.to_string(), "Friday 05:49 and 0 chalakim");
+        assert_eq!(tishrei.parts(), 5 * 1080 + 49 * 18);
+        assert!((tishrei.julian_day() - (2460202.5 + (5.0 + 49.0 / 60.0) / 24.0)).abs() < 1e-9);
+        // Adar I only in leap years
+        assert!(molad(5785, HebrewMonth::AdarI).is_err());
+        assert_eq!(molad(5784, HebrewMonth::AdarII).map(|m| m.day), Ok(gregorian(10, "March", 2024)));
+    }
+
+    #[test]
+    fn year_zero_is_an_error() {
+        let error = CalendarError::InvalidYear { year: 0, era: String::from("AM") };
+        // a day too large for u8 used to look up the month length of year 0
+        assert_eq!(HebrewDate::new(300, "Tishrei".into(), 0, "".into()), Err(error.clone()));
+        assert_eq!(HebrewDate::new(1, "Tishrei".into(), 0, "".into()), Err(error.clone()));
+        assert_eq!(HebrewDate { year: 0, month: 1, day: 1 }.to_julian_day_number(), Err(error));
+        assert_eq!(
+            HebrewDate::new(1, "Tishrei".into(), -5, "".into()),
+            Err(CalendarError::InvalidYear { year: -5, era: String::from("AM") })
+        );
+        assert_eq!(
+            HebrewDate::new(300, "Tishrei".into(), 5785, "".into()),
+            Err(CalendarError::DayOutOfRange { day: 300, max: 30 })
+        );
+    }
+}
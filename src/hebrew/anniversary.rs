@@ -0,0 +1,242 @@
+use crate::cal::Calendar;
+use crate::hebrew::{HebrewDate, HebrewMonth, HebrewYear};
+use crate::invalid_year_err::CalendarError;
+use crate::julian_day::JulianDayNumber;
+use crate::julian_gregorian::GregorianCalendar;
+
+/*
+Anniversaries of Hebrew dates: the yahrzeit of a death, and the Hebrew
+birthday, bar mitzvah and bat mitzvah of a birth.
+
+Most dates are kept on the same day and month, but the months do not all
+exist, or have 30 days, every year:
+Adar of a common year is kept in Adar I in leap years for a yahrzeit, and
+in Adar II for a birthday (AnniversaryRules::adar)
+Adar I and Adar II are kept in Adar in common years. 30 Adar I is kept on
+30 Shevat for a yahrzeit, being the day before 1 Adar as it was the day
+before 1 Adar II, and on 1 Nisan for a birthday, the day after 29 Adar
+(AnniversaryRules::adar_i_thirtieth)
+the 30th of Cheshvan or Kislev is kept on the 1st of the next month in
+years the month has 29 days. A yahrzeit is kept on the last day of the
+month instead if the month had 29 days in the first year after the death
+(AnniversaryRules::thirtieth)
+
+The defaults are those of Calendrical Calculations (Dershowitz and
+Reingold)
+ */
+
+// the Adar a date in Adar of a common year is kept in, in leap years
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+pub enum AdarRule {
+    AdarI,
+    AdarII,
+}
+
+// the day the 30th of Cheshvan or Kislev is kept on, in years the month has 29 days
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+pub enum ThirtiethRule {
+    // the 29th
+    DayBefore,
+    // the 1st of the next month
+    NextDay,
+    // the last day of the month every year (the 29th or 30th) if the month
+    // had 29 days in the year after the date, otherwise as NextDay
+    FirstYear,
+}
+
+// the day 30 Adar I is kept on in common years
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+pub enum AdarIThirtiethRule {
+    // 30 Shevat, the day before 1 Adar
+    Shevat,
+    // 1 Nisan, the day after 29 Adar
+    NextDay,
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+pub struct AnniversaryRules {
+    pub adar: AdarRule,
+    pub adar_i_thirtieth: AdarIThirtiethRule,
+    pub thirtieth: ThirtiethRule,
+}
+
+impl AnniversaryRules {
+    pub const YAHRZEIT: AnniversaryRules = AnniversaryRules {
+        adar: AdarRule::AdarI,
+        adar_i_thirtieth: AdarIThirtiethRule::Shevat,
+        thirtieth: ThirtiethRule::FirstYear,
+    };
+
+    pub const BIRTHDAY: AnniversaryRules = AnniversaryRules {
+        adar: AdarRule::AdarII,
+        adar_i_thirtieth: AdarIThirtiethRule::NextDay,
+        thirtieth: ThirtiethRule::NextDay,
+    };
+}
+
+// the day an anniversary is kept, which begins the evening before
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub struct Anniversary {
+    pub date: HebrewDate,
+    pub day: JulianDayNumber,
+}
+
+impl Anniversary {
+    pub fn gregorian(&self) -> Result<GregorianCalendar, CalendarError> {
+        GregorianCalendar::from_julian_day_number(self.day)
+    }
+
+    // the day of the evening the anniversary begins, e.g. when the yahrzeit candle is lit
+    pub fn gregorian_eve(&self) -> Result<GregorianCalendar, CalendarError> {
+        GregorianCalendar::from_julian_day_number(self.day.offset(-1)?)
+    }
+}
+
+fn month_length(year: u32, month: HebrewMonth) -> Result<u8, CalendarError> {
+    Ok(HebrewYear::new(year)?.month_lengths()[month.number(year)? as usize - 1])
+}
+
+impl HebrewDate {
+    // the day the date is kept on in a year, by the given rules
+    pub fn anniversary(&self, year: u32, rules: AnniversaryRules) -> Result<Anniversary, CalendarError> {
+        self.to_julian_day_number()?;
+        let is_leap = HebrewYear::new(year)?.is_leap();
+
+        let month = match (HebrewMonth::from_number(self.month, self.year)?, is_leap) {
+            (HebrewMonth::Adar, true) => match rules.adar {
+                AdarRule::AdarI => HebrewMonth::AdarI,
+                AdarRule::AdarII => HebrewMonth::AdarII,
+            },
+            (HebrewMonth::AdarI, false) if self.day == 30 => {
+                return match rules.adar_i_thirtieth {
+                    AdarIThirtiethRule::Shevat => kept_on(HebrewDate::from_month(30, HebrewMonth::Shevat, year)?),
+                    AdarIThirtiethRule::NextDay => kept_on(HebrewDate::from_month(1, HebrewMonth::Nisan, year)?),
+                }
+            }
+            (HebrewMonth::AdarI, false) | (HebrewMonth::AdarII, false) => HebrewMonth::Adar,
+            (month, _) => month,
+        };
+
+        let short_first_year = self.day == 30
+            && rules.thirtieth == ThirtiethRule::FirstYear
+            && matches!(month, HebrewMonth::Cheshvan | HebrewMonth::Kislev)
+            && month_length(self.year + 1, month)? < 30;
+        let last_day = month_length(year, month)?;
+        let missing = self.day == 30 && last_day < 30;
+
+        let day_before = HebrewDate::from_month(29, month, year)?;
+        match (short_first_year, missing, rules.thirtieth) {
+            (true, _, _) => kept_on(HebrewDate::from_month(last_day, month, year)?),
+            (false, true, ThirtiethRule::DayBefore) => kept_on(day_before),
+            (false, true, _) => kept_on(HebrewDate::from_julian_day_number(day_before.to_julian_day_number()?.offset(1)?)?),
+            (false, false, _) => kept_on(HebrewDate::from_month(self.day, month, year)?),
+        }
+    }
+
+    // the yahrzeit in a year of a death on this date
+    pub fn yahrzeit(&self, year: u32) -> Result<Anniversary, CalendarError> {
+        self.anniversary(year, AnniversaryRules::YAHRZEIT)
+    }
+
+    // the Hebrew birthday in a year of a birth on this date
+    pub fn birthday(&self, year: u32) -> Result<Anniversary, CalendarError> {
+        self.anniversary(year, AnniversaryRules::BIRTHDAY)
+    }
+
+    // the 13th Hebrew birthday
+    pub fn bar_mitzvah(&self) -> Result<Anniversary, CalendarError> {
+        self.birthday(self.year.checked_add(13).ok_or(CalendarError::Overflow)?)
+    }
+
+    // the 12th Hebrew birthday
+    pub fn bat_mitzvah(&self) -> Result<Anniversary, CalendarError> {
+        self.birthday(self.year.checked_add(12).ok_or(CalendarError::Overflow)?)
+    }
+}
+
+fn kept_on(date: HebrewDate) -> Result<Anniversary, CalendarError> {
+    Ok(Anniversary {
+        date,
+        day: date.to_julian_day_number()?,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use HebrewMonth::*;
+
+    fn hebrew(day: u8, month: HebrewMonth, year: u32) -> HebrewDate {
+        HebrewDate::from_month(day, month, year).unwrap()
+    }
+
+    fn yahrzeit(date: HebrewDate, year: u32) -> HebrewDate {
+        date.yahrzeit(year).unwrap().date
+    }
+
+    fn birthday(date: HebrewDate, year: u32) -> HebrewDate {
+        date.birthday(year).unwrap().date
+    }
+
+    #[test]
+    fn adar_in_leap_years() {
+        // a yahrzeit in Adar I, a birthday in Adar II
+        assert_eq!(yahrzeit(hebrew(15, Adar, 5783), 5784), hebrew(15, AdarI, 5784));
+        assert_eq!(birthday(hebrew(15, Adar, 5783), 5784), hebrew(15, AdarII, 5784));
+        // Adar II is kept in Adar II, or Adar
+        assert_eq!(yahrzeit(hebrew(10, AdarII, 5784), 5785), hebrew(10, Adar, 5785));
+        assert_eq!(yahrzeit(hebrew(10, AdarII, 5784), 5787), hebrew(10, AdarII, 5787));
+        assert_eq!(birthday(hebrew(10, AdarI, 5784), 5785), hebrew(10, Adar, 5785));
+
+        let rules = AnniversaryRules { adar: AdarRule::AdarII, ..AnniversaryRules::YAHRZEIT };
+        assert_eq!(hebrew(15, Adar, 5783).anniversary(5784, rules).map(|a| a.date), Ok(hebrew(15, AdarII, 5784)));
+    }
+
+    #[test]
+    fn thirtieth_of_adar_i() {
+        let date = hebrew(30, AdarI, 5784);
+        assert_eq!(yahrzeit(date, 5785), hebrew(30, Shevat, 5785));
+        assert_eq!(birthday(date, 5785), hebrew(1, Nisan, 5785));
+        assert_eq!(yahrzeit(date, 5787), hebrew(30, AdarI, 5787));
+        assert_eq!(birthday(date, 5787), hebrew(30, AdarI, 5787));
+    }
+
+    #[test]
+    fn thirtieth_of_cheshvan_and_kislev() {
+        // Cheshvan 5701 had 29 days, so the yahrzeit is the last day of
+        // Cheshvan, which has 30 days in 5702 and 29 in 5703
+        let date = hebrew(30, Cheshvan, 5700);
+        assert_eq!(yahrzeit(date, 5702), hebrew(30, Cheshvan, 5702));
+        assert_eq!(yahrzeit(date, 5703), hebrew(29, Cheshvan, 5703));
+
+        // Cheshvan 5780 had 30 days, so it is the 1st of Kislev when Cheshvan is short
+        let date = hebrew(30, Cheshvan, 5779);
+        assert_eq!(yahrzeit(date, 5781), hebrew(1, Kislev, 5781));
+        assert_eq!(yahrzeit(date, 5783), hebrew(30, Cheshvan, 5783));
+        let rules = AnniversaryRules { thirtieth: ThirtiethRule::DayBefore, ..AnniversaryRules::YAHRZEIT };
+        assert_eq!(date.anniversary(5781, rules).map(|a| a.date), Ok(hebrew(29, Cheshvan, 5781)));
+
+        // Kislev 5784 had 29 days, Kislev 5780 30
+        assert_eq!(yahrzeit(hebrew(30, Kislev, 5783), 5784), hebrew(29, Kislev, 5784));
+        assert_eq!(yahrzeit(hebrew(30, Kislev, 5783), 5786), hebrew(30, Kislev, 5786));
+        assert_eq!(yahrzeit(hebrew(30, Kislev, 5779), 5781), hebrew(1, Tevet, 5781));
+
+        // birthdays are kept on the next day
+        assert_eq!(birthday(hebrew(30, Cheshvan, 5785), 5786), hebrew(1, Kislev, 5786));
+        assert_eq!(birthday(hebrew(30, Cheshvan, 5700), 5703), hebrew(1, Kislev, 5703));
+    }
+
+    #[test]
+    fn bar_and_bat_mitzvah() {
+        let birth = hebrew(1, Adar, 5770);
+        assert_eq!(birth.bar_mitzvah().map(|a| a.date), Ok(hebrew(1, Adar, 5783)));
+        assert_eq!(birth.bat_mitzvah().map(|a| a.date), Ok(hebrew(1, AdarII, 5782)));
+    }
+
+    #[test]
+    fn gregorian_days() {
+        let anniversary = hebrew(15, Adar, 5783).yahrzeit(5784).unwrap();
+        assert_eq!(anniversary.gregorian(), GregorianCalendar::new(24, "February".into(), 2024, "AD".into()));
+        assert_eq!(anniversary.gregorian_eve(), GregorianCalendar::new(23, "February".into(), 2024, "AD".into()));
+    }
+}
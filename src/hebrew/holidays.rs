@@ -0,0 +1,356 @@
+use crate::cal::{Calendar, Weekday};
+use crate::hebrew::{HebrewDate, HebrewMonth, HebrewYear, Location};
+use crate::invalid_year_err::CalendarError;
+use crate::julian_day::JulianDayNumber;
+use crate::julian_gregorian::GregorianCalendar;
+
+/*
+The holidays of a Hebrew year: festivals, minor holidays, fasts, Rosh
+Chodesh and the Israeli days of remembrance and independence.
+
+Festival days kept for two days outside Israel (the second days of
+Sukkot, Pesach and Shavuot, Simchat Torah and the eighth day of Pesach)
+depend on the Location. Fasts that would fall on Shabbat are moved: Tzom
+Gedaliah, the 17th of Tammuz and Tisha B'Av to Sunday, Ta'anit Esther and
+Ta'anit Bechorot to Thursday. The Israeli days are moved off Friday,
+Shabbat and the days next to them the way the Knesset set out:
+Yom HaShoah: from Friday to Thursday, from Sunday to Monday
+Yom Ha'atzmaut: from Friday or Shabbat to Thursday, from Monday to Tuesday,
+with Yom HaZikaron always the day before
+ */
+
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+pub enum HolidayKind {
+    // work is forbidden: Rosh Hashanah, Yom Kippur and the festival days
+    Festival,
+    CholHamoed,
+    Minor,
+    Fast,
+    RoshChodesh,
+    // Yom HaShoah, Yom HaZikaron, Yom Ha'atzmaut and Yom Yerushalayim
+    Modern,
+}
+
+#[derive(Clone, PartialEq, Debug)]
+pub struct Holiday {
+    pub name: String,
+    pub kind: HolidayKind,
+    pub date: HebrewDate,
+    pub day: JulianDayNumber,
+}
+
+impl Holiday {
+    pub fn weekday(&self) -> Weekday {
+        Weekday::from_julian_day_number(self.day)
+    }
+
+    pub fn gregorian(&self) -> Result<GregorianCalendar, CalendarError> {
+        GregorianCalendar::from_julian_day_number(self.day)
+    }
+}
+
+// the holidays of a year, in order
+struct Holidays {
+    year: u32,
+    holidays: Vec<Holiday>,
+}
+
+impl Holidays {
+    fn day(&self, month: HebrewMonth, day: u8) -> Result<JulianDayNumber, CalendarError> {
+        HebrewDate::from_month(day, month, self.year)?.to_julian_day_number()
+    }
+
+    fn add_day(&mut self, name: &str, kind: HolidayKind, day: JulianDayNumber) -> Result<(), CalendarError> {
+        self.holidays.push(Holiday {
+            name: String::from(name),
+            kind,
+            date: HebrewDate::from_julian_day_number(day)?,
+            day,
+        });
+        Ok(())
+    }
+
+    fn add(&mut self, name: &str, kind: HolidayKind, month: HebrewMonth, day: u8) -> Result<(), CalendarError> {
+        let day = self.day(month, day)?;
+        self.add_day(name, kind, day)
+    }
+
+    // on the given day, moved by `shift` days depending on its weekday
+    fn add_shifted(
+        &mut self,
+        name: &str,
+        kind: HolidayKind,
+        month: HebrewMonth,
+        day: u8,
+        shift: impl Fn(Weekday) -> i64,
+    ) -> Result<(), CalendarError> {
+        let day = self.day(month, day)?;
+        let day = day.offset(shift(Weekday::from_julian_day_number(day)))?;
+        self.add_day(name, kind, day)
+    }
+
+    // `count` days from `first`, named "name 1", "name 2" ...
+    fn add_days(&mut self, name: &str, kind: HolidayKind, month: HebrewMonth, first: u8, count: i64) -> Result<(), CalendarError> {
+        let start = self.day(month, first)?;
+        for i in 0..count {
+            self.add_day(&format!("{} {}", name, i + 1), kind, start.offset(i)?)?;
+        }
+        Ok(())
+    }
+}
+
+// fasts are not kept on Shabbat
+fn after_shabbat(weekday: Weekday) -> i64 {
+    match weekday {
+        Weekday::Saturday => 1,
+        _ => 0,
+    }
+}
+
+fn before_shabbat(weekday: Weekday) -> i64 {
+    match weekday {
+        Weekday::Saturday => -2,
+        _ => 0,
+    }
+}
+
+fn add_tishrei(holidays: &mut Holidays, location: Location) -> Result<(), CalendarError> {
+    use HebrewMonth::Tishrei;
+    use HolidayKind::*;
+
+    holidays.add("Rosh Hashanah I", Festival, Tishrei, 1)?;
+    holidays.add("Rosh Hashanah II", Festival, Tishrei, 2)?;
+    holidays.add_shifted("Tzom Gedaliah", Fast, Tishrei, 3, after_shabbat)?;
+    holidays.add("Yom Kippur", Festival, Tishrei, 10)?;
+    holidays.add("Sukkot I", Festival, Tishrei, 15)?;
+    let first_chol_hamoed = match location {
+        Location::Israel => 16,
+        Location::Diaspora => {
+            holidays.add("Sukkot II", Festival, Tishrei, 16)?;
+            17
+        }
+    };
+    for day in first_chol_hamoed..=20 {
+        holidays.add("Sukkot (Chol HaMoed)", CholHamoed, Tishrei, day)?;
+    }
+    holidays.add("Hoshana Rabbah", CholHamoed, Tishrei, 21)?;
+    match location {
+        Location::Israel => holidays.add("Shemini Atzeret / Simchat Torah", Festival, Tishrei, 22),
+        Location::Diaspora => {
+            holidays.add("Shemini Atzeret", Festival, Tishrei, 22)?;
+            holidays.add("Simchat Torah", Festival, Tishrei, 23)
+        }
+    }
+}
+
+fn add_winter(holidays: &mut Holidays, is_leap: bool) -> Result<(), CalendarError> {
+    use HebrewMonth::*;
+    use HolidayKind::*;
+
+    holidays.add_days("Chanukah", Minor, Kislev, 25, 8)?;
+    // the only fast kept on Friday
+    holidays.add("Asara B'Tevet", Fast, Tevet, 10)?;
+    holidays.add("Tu BiShvat", Minor, Shevat, 15)?;
+    if is_leap {
+        holidays.add("Purim Katan", Minor, AdarI, 14)?;
+        holidays.add("Shushan Purim Katan", Minor, AdarI, 15)?;
+    }
+    // Adar is Adar II in leap years
+    holidays.add_shifted("Ta'anit Esther", Fast, Adar, 13, before_shabbat)?;
+    holidays.add("Purim", Minor, Adar, 14)?;
+    holidays.add("Shushan Purim", Minor, Adar, 15)
+}
+
+fn add_pesach(holidays: &mut Holidays, location: Location) -> Result<(), CalendarError> {
+    use HebrewMonth::Nisan;
+    use HolidayKind::*;
+
+    holidays.add_shifted("Ta'anit Bechorot", Fast, Nisan, 14, before_shabbat)?;
+    holidays.add("Pesach I", Festival, Nisan, 15)?;
+    let first_chol_hamoed = match location {
+        Location::Israel => 16,
+        Location::Diaspora => {
+            holidays.add("Pesach II", Festival, Nisan, 16)?;
+            17
+        }
+    };
+    for day in first_chol_hamoed..=20 {
+        holidays.add("Pesach (Chol HaMoed)", CholHamoed, Nisan, day)?;
+    }
+    holidays.add("Pesach VII", Festival, Nisan, 21)?;
+    if location == Location::Diaspora {
+        holidays.add("Pesach VIII", Festival, Nisan, 22)?;
+    }
+    Ok(())
+}
+
+fn add_summer(holidays: &mut Holidays, location: Location) -> Result<(), CalendarError> {
+    use HebrewMonth::*;
+    use HolidayKind::*;
+
+    holidays.add("Pesach Sheni", Minor, Iyar, 14)?;
+    holidays.add("Lag BaOmer", Minor, Iyar, 18)?;
+    holidays.add("Shavuot I", Festival, Sivan, 6)?;
+    if location == Location::Diaspora {
+        holidays.add("Shavuot II", Festival, Sivan, 7)?;
+    }
+    holidays.add_shifted("Tzom Tammuz", Fast, Tammuz, 17, after_shabbat)?;
+    holidays.add_shifted("Tisha B'Av", Fast, Av, 9, after_shabbat)?;
+    holidays.add("Tu B'Av", Minor, Av, 15)
+}
+
+fn add_modern(holidays: &mut Holidays) -> Result<(), CalendarError> {
+    use HebrewMonth::*;
+    use HolidayKind::Modern;
+
+    let year = holidays.year;
+    if year >= 5711 {
+        // moved off Sunday since 1997
+        let sunday_rule = year >= 5757;
+        holidays.add_shifted("Yom HaShoah", Modern, Nisan, 27, |weekday| match weekday {
+            Weekday::Friday => -1,
+            Weekday::Sunday if sunday_rule => 1,
+            _ => 0,
+        })?;
+    }
+    if year >= 5708 {
+        // moved off Monday since 2004, so that Yom HaZikaron is not on Sunday
+        let monday_rule = year >= 5764;
+        let independence = holidays.day(Iyar, 5)?;
+        let independence = independence.offset(match Weekday::from_julian_day_number(independence) {
+            Weekday::Friday => -1,
+            Weekday::Saturday => -2,
+            Weekday::Monday if monday_rule => 1,
+            _ => 0,
+        })?;
+        holidays.add_day("Yom HaZikaron", Modern, independence.offset(-1)?)?;
+        holidays.add_day("Yom Ha'atzmaut", Modern, independence)?;
+    }
+    if year >= 5728 {
+        holidays.add("Yom Yerushalayim", Modern, Iyar, 28)?;
+    }
+    Ok(())
+}
+
+// the 30th of a month of 30 days, and the 1st of the next month
+fn add_rosh_chodesh(holidays: &mut Holidays, year: &HebrewYear) -> Result<(), CalendarError> {
+    let lengths = year.month_lengths();
+    for month in 2..=lengths.len() as u8 {
+        let name = format!("Rosh Chodesh {}", HebrewMonth::from_number(month, holidays.year)?.name());
+        let first = HebrewDate { year: holidays.year, month, day: 1 }.to_julian_day_number()?;
+        if lengths[month as usize - 2] == 30 {
+            holidays.add_day(&name, HolidayKind::RoshChodesh, first.offset(-1)?)?;
+        }
+        holidays.add_day(&name, HolidayKind::RoshChodesh, first)?;
+    }
+    Ok(())
+}
+
+// every holiday of the Hebrew year, in order of date
+pub fn holidays(year: u32, location: Location) -> Result<Vec<Holiday>, CalendarError> {
+    let hebrew_year = HebrewYear::new(year)?;
+    let mut holidays = Holidays { year, holidays: Vec::new() };
+    add_tishrei(&mut holidays, location)?;
+    add_winter(&mut holidays, hebrew_year.is_leap())?;
+    add_pesach(&mut holidays, location)?;
+    add_summer(&mut holidays, location)?;
+    add_modern(&mut holidays)?;
+    add_rosh_chodesh(&mut holidays, &hebrew_year)?;
+
+    let mut holidays = holidays.holidays;
+    holidays.sort_by_key(|holiday| holiday.day);
+    Ok(holidays)
+}
+
+// the holidays on a date, usually none or one
+pub fn holidays_on(date: HebrewDate, location: Location) -> Result<Vec<Holiday>, CalendarError> {
+    let day = date.to_julian_day_number()?;
+    let mut holidays = holidays(date.year, location)?;
+    holidays.retain(|holiday| holiday.day == day);
+    Ok(holidays)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn gregorian(day: u32, month: &str, year: i32) -> JulianDayNumber {
+        GregorianCalendar::new(day, month.into(), year, "AD".into())
+            .unwrap()
+            .to_julian_day_number()
+            .unwrap()
+    }
+
+    // the days of the holidays with the name
+    fn days(year: u32, location: Location, name: &str) -> Vec<JulianDayNumber> {
+        let holidays = holidays(year, location).unwrap();
+        holidays.iter().filter(|holiday| holiday.name == name).map(|holiday| holiday.day).collect()
+    }
+
+    #[test]
+    fn israeli_days() {
+        let years = [
+            // Yom Ha'atzmaut moved from Friday to Thursday
+            (5782, (28, "April", 2022), (4, "May", 2022), (5, "May", 2022)),
+            (5783, (18, "April", 2023), (25, "April", 2023), (26, "April", 2023)),
+            // Yom HaShoah moved from Sunday and Yom Ha'atzmaut from Monday
+            (5784, (6, "May", 2024), (13, "May", 2024), (14, "May", 2024)),
+            // Yom HaShoah moved from Friday and Yom Ha'atzmaut from Shabbat
+            (5785, (24, "April", 2025), (30, "April", 2025), (1, "May", 2025)),
+        ];
+        for &(year, shoah, zikaron, atzmaut) in years.iter() {
+            for (name, (day, month, g_year)) in [("Yom HaShoah", shoah), ("Yom HaZikaron", zikaron), ("Yom Ha'atzmaut", atzmaut)].iter() {
+                assert_eq!(days(year, Location::Israel, name), vec![gregorian(*day, month, *g_year)], "{} {}", name, year);
+            }
+        }
+        // before the Monday rule of 2004
+        assert_eq!(days(5763, Location::Israel, "Yom Ha'atzmaut"), vec![gregorian(7, "May", 2003)]);
+    }
+
+    #[test]
+    fn postponed_fasts() {
+        let fasts = [
+            // from Shabbat to Sunday
+            (5785, "Tzom Gedaliah", (6, "October", 2024)),
+            (5782, "Tzom Tammuz", (17, "July", 2022)),
+            (5782, "Tisha B'Av", (7, "August", 2022)),
+            // from Shabbat to Thursday
+            (5784, "Ta'anit Esther", (21, "March", 2024)),
+            (5785, "Ta'anit Bechorot", (10, "April", 2025)),
+            // not moved
+            (5785, "Tisha B'Av", (3, "August", 2025)),
+            (5786, "Tzom Gedaliah", (25, "September", 2025)),
+        ];
+        for &(year, name, (day, month, g_year)) in fasts.iter() {
+            assert_eq!(days(year, Location::Diaspora, name), vec![gregorian(day, month, g_year)], "{} {}", name, year);
+        }
+        for holiday in holidays(5782, Location::Israel).unwrap().iter().filter(|h| h.kind == HolidayKind::Fast) {
+            assert!(holiday.weekday() != Weekday::Saturday, "{}", holiday.name);
+        }
+    }
+
+    #[test]
+    fn israel_and_diaspora() {
+        assert_eq!(days(5785, Location::Diaspora, "Pesach II"), vec![gregorian(14, "April", 2025)]);
+        assert_eq!(days(5785, Location::Israel, "Pesach II"), Vec::new());
+        assert_eq!(days(5785, Location::Diaspora, "Pesach (Chol HaMoed)").len(), 4);
+        assert_eq!(days(5785, Location::Israel, "Pesach (Chol HaMoed)").len(), 5);
+        assert_eq!(days(5785, Location::Diaspora, "Simchat Torah"), vec![gregorian(25, "October", 2024)]);
+        assert_eq!(days(5785, Location::Israel, "Shemini Atzeret / Simchat Torah"), vec![gregorian(24, "October", 2024)]);
+        assert_eq!(days(5785, Location::Diaspora, "Shavuot II"), vec![gregorian(3, "June", 2025)]);
+        assert_eq!(days(5785, Location::Israel, "Shavuot II"), Vec::new());
+    }
+
+    #[test]
+    fn rosh_chodesh_and_leap_years() {
+        // Cheshvan 5785 has 30 days, so Rosh Chodesh Kislev is two days
+        assert_eq!(days(5785, Location::Israel, "Rosh Chodesh Kislev"), vec![gregorian(1, "December", 2024), gregorian(2, "December", 2024)]);
+        assert_eq!(days(5784, Location::Israel, "Rosh Chodesh Kislev"), vec![gregorian(14, "November", 2023)]);
+        assert_eq!(days(5784, Location::Israel, "Purim Katan"), vec![gregorian(23, "February", 2024)]);
+        assert_eq!(days(5784, Location::Israel, "Purim"), vec![gregorian(24, "March", 2024)]);
+        assert_eq!(days(5785, Location::Israel, "Purim Katan"), Vec::new());
+
+        let on_purim = holidays_on(HebrewDate::from_month(14, HebrewMonth::AdarII, 5784).unwrap(), Location::Israel).unwrap();
+        assert_eq!(on_purim.iter().map(|h| h.name.as_str()).collect::<Vec<_>>(), vec!["Purim"]);
+    }
+}
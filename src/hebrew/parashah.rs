@@ -0,0 +1,578 @@
+use wasm_bindgen::prelude::*;
+
+use crate::cal::{Calendar, Weekday};
+use crate::hebrew::holidays::{holidays, HolidayKind};
+use crate::hebrew::{HebrewDate, HebrewMonth, HebrewYear, Location};
+use crate::invalid_year_err::CalendarError;
+use crate::julian_day::JulianDayNumber;
+use crate::julian_gregorian::GregorianCalendar;
+
+/*
+The weekly Torah portions (parashiyot) read on Shabbat.
+
+The reading starts with Bereshit on the Shabbat after Simchat Torah and
+ends with Nitzavim, or Nitzavim and Vayeilech together, on the last
+Shabbat before Rosh Hashanah. A Shabbat that is a festival day has the
+festival's reading instead. There are fewer Shabbatot than portions, so
+some pairs are read together, as the year needs. The portions before
+certain days are fixed:
+Tzav (Metzora in leap years) on the Shabbat before Pesach
+Bamidbar on the Shabbat before Shavuot, outside Israel
+Devarim on the Shabbat before Tisha B'Av, or on the 9th of Av itself
+Nitzavim on the Shabbat before Rosh Hashanah
+
+Pairs are joined in the order given by JOINED, so that Matot and Masei are
+joined before the others and Chukat and Balak only when the second day of
+Shavuot is on Shabbat. Israel keeps one day of Pesach and Shavuot less, so
+when the eighth day of Pesach is on Shabbat it reads the next portion that
+day and is a week ahead until it separates a pair read together outside
+Israel (Behar and Bechukotai, or Matot and Masei in leap years).
+
+Between Rosh Hashanah and Sukkot, Vayeilech and Ha'azinu are read when
+there are two Shabbatot without a festival, and only Ha'azinu when there
+is one (Vayeilech having been read with Nitzavim)
+ */
+
+#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Debug)]
+pub enum Parashah {
+    Bereshit,
+    Noach,
+    LechLecha,
+    Vayera,
+    ChayeiSara,
+    Toldot,
+    Vayetzei,
+    Vayishlach,
+    Vayeshev,
+    Miketz,
+    Vayigash,
+    Vayechi,
+    Shemot,
+    Vaera,
+    Bo,
+    Beshalach,
+    Yitro,
+    Mishpatim,
+    Terumah,
+    Tetzaveh,
+    KiTisa,
+    Vayakhel,
+    Pekudei,
+    Vayikra,
+    Tzav,
+    Shmini,
+    Tazria,
+    Metzora,
+    AchreiMot,
+    Kedoshim,
+    Emor,
+    Behar,
+    Bechukotai,
+    Bamidbar,
+    Nasso,
+    Behaalotcha,
+    Shlach,
+    Korach,
+    Chukat,
+    Balak,
+    Pinchas,
+    Matot,
+    Masei,
+    Devarim,
+    Vaetchanan,
+    Eikev,
+    Reeh,
+    Shoftim,
+    KiTeitzei,
+    KiTavo,
+    Nitzavim,
+    Vayeilech,
+    Haazinu,
+    // read on Simchat Torah, never on Shabbat
+    VezotHaberakhah,
+}
+
+// in the order they are read
+pub const PARASHIYOT: [Parashah; 54] = {
+    use Parashah::*;
+    [
+        Bereshit, Noach, LechLecha, Vayera, ChayeiSara, Toldot, Vayetzei, Vayishlach, Vayeshev, Miketz, Vayigash,
+        Vayechi, Shemot, Vaera, Bo, Beshalach, Yitro, Mishpatim, Terumah, Tetzaveh, KiTisa, Vayakhel, Pekudei,
+        Vayikra, Tzav, Shmini, Tazria, Metzora, AchreiMot, Kedoshim, Emor, Behar, Bechukotai, Bamidbar, Nasso,
+        Behaalotcha, Shlach, Korach, Chukat, Balak, Pinchas, Matot, Masei, Devarim, Vaetchanan, Eikev, Reeh,
+        Shoftim, KiTeitzei, KiTavo, Nitzavim, Vayeilech, Haazinu, VezotHaberakhah,
+    ]
+};
+
+// the first of each pair that may be read together, in the order they are joined
+const JOINED: [Parashah; 7] = [
+    Parashah::Vayakhel,
+    Parashah::Matot,
+    Parashah::Tazria,
+    Parashah::AchreiMot,
+    Parashah::Behar,
+    Parashah::Chukat,
+    Parashah::Nitzavim,
+];
+
+impl Parashah {
+    pub fn name(self) -> &'static str {
+        use Parashah::*;
+        match self {
+            Bereshit => "Bereshit",
+            Noach => "Noach",
+            LechLecha => "Lech-Lecha",
+            Vayera => "Vayera",
+            ChayeiSara => "Chayei Sara",
+            Toldot => "Toldot",
+            Vayetzei => "Vayetzei",
+            Vayishlach => "Vayishlach",
+            Vayeshev => "Vayeshev",
+            Miketz => "Miketz",
+            Vayigash => "Vayigash",
+            Vayechi => "Vayechi",
+            Shemot => "Shemot",
+            Vaera => "Vaera",
+            Bo => "Bo",
+            Beshalach => "Beshalach",
+            Yitro => "Yitro",
+            Mishpatim => "Mishpatim",
+            Terumah => "Terumah",
+            Tetzaveh => "Tetzaveh",
+            KiTisa => "Ki Tisa",
+            Vayakhel => "Vayakhel",
+            Pekudei => "Pekudei",
+            Vayikra => "Vayikra",
+            Tzav => "Tzav",
+            Shmini => "Shmini",
+            Tazria => "Tazria",
+            Metzora => "Metzora",
+            AchreiMot => "Achrei Mot",
+            Kedoshim => "Kedoshim",
+            Emor => "Emor",
+            Behar => "Behar",
+            Bechukotai => "Bechukotai",
+            Bamidbar => "Bamidbar",
+            Nasso => "Nasso",
+            Behaalotcha => "Beha'alotcha",
+            Shlach => "Sh'lach",
+            Korach => "Korach",
+            Chukat => "Chukat",
+            Balak => "Balak",
+            Pinchas => "Pinchas",
+            Matot => "Matot",
+            Masei => "Masei",
+            Devarim => "Devarim",
+            Vaetchanan => "Vaetchanan",
+            Eikev => "Eikev",
+            Reeh => "Re'eh",
+            Shoftim => "Shoftim",
+            KiTeitzei => "Ki Teitzei",
+            KiTavo => "Ki Tavo",
+            Nitzavim => "Nitzavim",
+            Vayeilech => "Vayeilech",
+            Haazinu => "Ha'azinu",
+            VezotHaberakhah => "Vezot Haberakhah",
+        }
+    }
+
+    // 0 for Bereshit to 53 for Vezot Haberakhah
+    pub fn number(self) -> usize {
+        self as usize
+    }
+}
+
+impl std::fmt::Display for Parashah {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(f, "{}", self.name())
+    }
+}
+
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub enum Reading {
+    Parashah(Parashah),
+    // two portions read together, e.g. Vayakhel-Pekudei
+    Combined(Parashah, Parashah),
+    // a Shabbat that is a festival day, with the festival's name
+    Festival(String),
+}
+
+impl Reading {
+    // the portions read, none on a festival
+    pub fn parashiyot(&self) -> Vec<Parashah> {
+        match self {
+            Reading::Parashah(parashah) => vec![*parashah],
+            Reading::Combined(first, second) => vec![*first, *second],
+            Reading::Festival(_) => Vec::new(),
+        }
+    }
+
+    pub fn is_festival(&self) -> bool {
+        matches!(self, Reading::Festival(_))
+    }
+}
+
+// e.g. "Vayakhel-Pekudei" or "Pesach (Chol HaMoed)"
+impl std::fmt::Display for Reading {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            Reading::Parashah(parashah) => write!(f, "{}", parashah),
+            Reading::Combined(first, second) => write!(f, "{}-{}", first, second),
+            Reading::Festival(name) => write!(f, "{}", name),
+        }
+    }
+}
+
+#[derive(Clone, PartialEq, Debug)]
+pub struct ShabbatReading {
+    pub date: HebrewDate,
+    pub day: JulianDayNumber,
+    pub reading: Reading,
+}
+
+impl ShabbatReading {
+    pub fn gregorian(&self) -> Result<GregorianCalendar, CalendarError> {
+        GregorianCalendar::from_julian_day_number(self.day)
+    }
+}
+
+// the Shabbat on or after a day
+fn next_shabbat(day: JulianDayNumber) -> Result<JulianDayNumber, CalendarError> {
+    let weekday = Weekday::from_julian_day_number(day).number() as i64;
+    day.offset((Weekday::Saturday.number() as i64 - weekday).rem_euclid(7))
+}
+
+/*
+The readings for the Shabbatot of a part of the year, from `first` to
+`last`, joining pairs until they fit. The fixed portions make sure there
+are never more Shabbatot than portions, or more portions than can be joined
+ */
+fn fill(shabbatot: &[JulianDayNumber], first: Parashah, last: Parashah) -> Vec<Reading> {
+    let portions = last.number() + 1 - first.number();
+    let joins = portions.saturating_sub(shabbatot.len());
+    let joined: Vec<Parashah> = JOINED
+        .iter()
+        .filter(|&&p| p >= first && p < last)
+        .take(joins)
+        .cloned()
+        .collect();
+
+    let mut readings = Vec::new();
+    let mut number = first.number();
+    while number <= last.number() {
+        let parashah = PARASHIYOT[number];
+        match joined.contains(&parashah) {
+            true => {
+                readings.push(Reading::Combined(parashah, PARASHIYOT[number + 1]));
+                number += 2;
+            }
+            false => {
+                readings.push(Reading::Parashah(parashah));
+                number += 1;
+            }
+        }
+    }
+    readings
+}
+
+// the readings of every Shabbat of the Hebrew year, Rosh Hashanah to the end of Elul
+pub fn schedule(year: u32, location: Location) -> Result<Vec<ShabbatReading>, CalendarError> {
+    let hebrew_year = HebrewYear::new(year)?;
+    let next_year = HebrewYear::new(year + 1)?;
+    let day = |month: HebrewMonth, day: u8| HebrewDate::from_month(day, month, year)?.to_julian_day_number();
+
+    let festivals: Vec<_> = holidays(year, location)?
+        .into_iter()
+        .filter(|holiday| holiday.kind == HolidayKind::Festival || holiday.kind == HolidayKind::CholHamoed)
+        .collect();
+
+    let mut shabbatot = Vec::new();
+    let mut shabbat = next_shabbat(hebrew_year.rosh_hashanah())?;
+    while shabbat < next_year.rosh_hashanah() {
+        shabbatot.push(shabbat);
+        shabbat = shabbat.offset(7)?;
+    }
+    let regular: Vec<JulianDayNumber> = shabbatot
+        .iter()
+        .cloned()
+        .filter(|&shabbat| !festivals.iter().any(|holiday| holiday.day == shabbat))
+        .collect();
+
+    let simchat_torah = day(HebrewMonth::Tishrei, 22)?;
+    let pesach = day(HebrewMonth::Nisan, 15)?;
+    let shavuot = day(HebrewMonth::Sivan, 6)?;
+    let tisha_bav = day(HebrewMonth::Av, 9)?;
+    let between = |from: JulianDayNumber, to: JulianDayNumber| -> Vec<JulianDayNumber> {
+        regular.iter().cloned().filter(|&shabbat| shabbat > from && shabbat <= to).collect()
+    };
+
+    // Vayeilech is read with Nitzavim when only Ha'azinu fits before Sukkot next year
+    let last = match next_year.rosh_hashanah_weekday() {
+        Weekday::Thursday | Weekday::Saturday => Parashah::Vayeilech,
+        _ => Parashah::Nitzavim,
+    };
+    let before_pesach = match hebrew_year.is_leap() {
+        true => Parashah::Metzora,
+        false => Parashah::Tzav,
+    };
+    let mut parts = vec![
+        (between(simchat_torah, pesach), Parashah::Bereshit, before_pesach),
+    ];
+    match location {
+        Location::Israel => parts.push((between(pesach, tisha_bav), PARASHIYOT[before_pesach.number() + 1], Parashah::Devarim)),
+        Location::Diaspora => {
+            parts.push((between(pesach, shavuot), PARASHIYOT[before_pesach.number() + 1], Parashah::Bamidbar));
+            parts.push((between(shavuot, tisha_bav), Parashah::Nasso, Parashah::Devarim));
+        }
+    }
+    parts.push((between(tisha_bav, next_year.rosh_hashanah()), Parashah::Vaetchanan, last));
+
+    let tishrei = between(hebrew_year.rosh_hashanah(), simchat_torah);
+    let mut readings: Vec<(JulianDayNumber, Reading)> = tishrei
+        .iter()
+        .rev()
+        .zip([Parashah::Haazinu, Parashah::Vayeilech].iter())
+        .map(|(&shabbat, &parashah)| (shabbat, Reading::Parashah(parashah)))
+        .collect();
+    for (shabbatot, first, last) in parts {
+        readings.extend(shabbatot.iter().cloned().zip(fill(&shabbatot, first, last)));
+    }
+    for holiday in festivals.iter().filter(|holiday| shabbatot.contains(&holiday.day)) {
+        readings.push((holiday.day, Reading::Festival(holiday.name.clone())));
+    }
+    readings.sort_by_key(|(shabbat, _)| *shabbat);
+
+    readings
+        .into_iter()
+        .map(|(day, reading)| {
+            Ok(ShabbatReading {
+                date: HebrewDate::from_julian_day_number(day)?,
+                day,
+                reading,
+            })
+        })
+        .collect()
+}
+
+// the reading of the Shabbat on or after a date, that is of the date's week
+pub fn parashah(date: HebrewDate, location: Location) -> Result<ShabbatReading, CalendarError> {
+    let shabbat = HebrewDate::from_julian_day_number(next_shabbat(date.to_julian_day_number()?)?)?;
+    schedule(shabbat.year, location)?
+        .into_iter()
+        .find(|reading| reading.date == shabbat)
+        .ok_or(CalendarError::InvalidInput)
+}
+
+/*
+The reading of a Shabbat which can be returned to javascript code via web
+assembly, with the Shabbat as a Hebrew date (the month by its English name,
+as js_api takes it). Like GenericDate, errors are reported with is_valid,
+is_not_overflow and error
+ */
+#[wasm_bindgen]
+pub struct ParashahWeek {
+    is_valid: bool,
+    is_not_overflow: bool,
+    error: String,
+    name: String,
+    is_festival: bool,
+    day: u8,
+    month: String,
+    year: u32,
+}
+
+impl From<ShabbatReading> for ParashahWeek {
+    fn from(reading: ShabbatReading) -> ParashahWeek {
+        ParashahWeek {
+            is_valid: true,
+            is_not_overflow: true,
+            error: String::new(),
+            name: reading.reading.to_string(),
+            is_festival: reading.reading.is_festival(),
+            day: reading.date.day,
+            month: String::from(reading.date.month_name()),
+            year: reading.date.year,
+        }
+    }
+}
+
+impl From<CalendarError> for ParashahWeek {
+    fn from(error: CalendarError) -> ParashahWeek {
+        ParashahWeek {
+            is_valid: error.is_overflow(),
+            is_not_overflow: !error.is_overflow(),
+            error: error.to_string(),
+            name: String::new(),
+            is_festival: false,
+            day: 0,
+            month: String::new(),
+            year: 0,
+        }
+    }
+}
+
+#[wasm_bindgen]
+impl ParashahWeek {
+
+    pub fn get_is_not_overflow (&self) -> bool {
+        self.is_not_overflow
+    }
+
+    pub fn get_is_valid (&self) -> bool {
+        self.is_valid
+    }
+
+    pub fn get_error (&self) -> String {
+        self.error.clone()
+    }
+
+    pub fn get_name (&self) -> String {
+        self.name.clone()
+    }
+
+    pub fn get_is_festival (&self) -> bool {
+        self.is_festival
+    }
+
+    pub fn get_day (&self) -> u8 {
+        self.day
+    }
+
+    pub fn get_month (&self) -> String {
+        self.month.clone()
+    }
+
+    pub fn get_year (&self) -> u32 {
+        self.year
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn gregorian(day: u32, month: &str, year: i32) -> JulianDayNumber {
+        GregorianCalendar::new(day, month.into(), year, "AD".into())
+            .unwrap()
+            .to_julian_day_number()
+            .unwrap()
+    }
+
+    // the reading of the Shabbat on a gregorian date
+    fn reading(year: u32, location: Location, (day, month, g_year): (u32, &str, i32)) -> String {
+        let shabbat = gregorian(day, month, g_year);
+        let schedule = schedule(year, location).unwrap();
+        schedule.iter().find(|reading| reading.day == shabbat).unwrap().reading.to_string()
+    }
+
+    // every portion from Bereshit is read once and in order, until Nitzavim
+    // or Vayeilech
+    #[test]
+    fn every_portion_is_read() {
+        for &year in [5780, 5782, 5784, 5785, 5786].iter() {
+            for &location in [Location::Israel, Location::Diaspora].iter() {
+                let read: Vec<Parashah> = schedule(year, location)
+                    .unwrap()
+                    .iter()
+                    .flat_map(|reading| reading.reading.parashiyot())
+                    .skip_while(|&parashah| parashah != Parashah::Bereshit)
+                    .collect();
+                assert_eq!(&read[..], &PARASHIYOT[..read.len()], "{} {:?}", year, location);
+                assert!(read.len() == 51 || read.len() == 52, "{} {:?}", year, location);
+            }
+        }
+    }
+
+    #[test]
+    fn leap_year_with_pesach_on_shabbat() {
+        // 5782: the eighth day of Pesach is on Shabbat, so Israel is a week
+        // ahead until Matot and Masei are read apart
+        let weeks = [
+            ((16, "April", 2022), "Pesach I", "Pesach I"),
+            ((23, "April", 2022), "Pesach VIII", "Achrei Mot"),
+            ((30, "April", 2022), "Achrei Mot", "Kedoshim"),
+            ((21, "May", 2022), "Behar", "Bechukotai"),
+            ((23, "July", 2022), "Pinchas", "Matot"),
+            ((30, "July", 2022), "Matot-Masei", "Masei"),
+            ((6, "August", 2022), "Devarim", "Devarim"),
+        ];
+        for &(day, diaspora, israel) in weeks.iter() {
+            assert_eq!(reading(5782, Location::Diaspora, day), diaspora, "{:?}", day);
+            assert_eq!(reading(5782, Location::Israel, day), israel, "{:?}", day);
+        }
+        // pairs are read apart in leap years
+        assert_eq!(reading(5782, Location::Diaspora, (26, "February", 2022)), "Vayakhel");
+        assert_eq!(reading(5782, Location::Diaspora, (9, "April", 2022)), "Metzora");
+        // Rosh Hashanah 5783 is on Monday, so Nitzavim is read alone
+        assert_eq!(reading(5782, Location::Diaspora, (11, "September", 2021)), "Vayeilech");
+        assert_eq!(reading(5782, Location::Diaspora, (18, "September", 2021)), "Ha'azinu");
+        assert_eq!(reading(5782, Location::Diaspora, (24, "September", 2022)), "Nitzavim");
+    }
+
+    #[test]
+    fn deficient_leap_year() {
+        // 5784 begins on Shabbat
+        let weeks = [
+            ((16, "September", 2023), "Rosh Hashanah I"),
+            ((23, "September", 2023), "Ha'azinu"),
+            ((14, "October", 2023), "Bereshit"),
+            ((16, "March", 2024), "Pekudei"),
+            ((20, "April", 2024), "Metzora"),
+            ((27, "April", 2024), "Pesach (Chol HaMoed)"),
+            ((25, "May", 2024), "Behar"),
+            ((3, "August", 2024), "Matot-Masei"),
+            ((28, "September", 2024), "Nitzavim-Vayeilech"),
+        ];
+        for &(day, expected) in weeks.iter() {
+            assert_eq!(reading(5784, Location::Diaspora, day), expected, "{:?}", day);
+            assert_eq!(reading(5784, Location::Israel, day), expected, "{:?}", day);
+        }
+        assert_eq!(reading(5784, Location::Israel, (7, "October", 2023)), "Shemini Atzeret / Simchat Torah");
+        assert_eq!(reading(5784, Location::Diaspora, (7, "October", 2023)), "Shemini Atzeret");
+    }
+
+    #[test]
+    fn common_year_pairs() {
+        let weeks = [
+            ((5, "October", 2024), "Ha'azinu"),
+            ((12, "October", 2024), "Yom Kippur"),
+            ((26, "October", 2024), "Bereshit"),
+            ((22, "March", 2025), "Vayakhel"),
+            ((12, "April", 2025), "Tzav"),
+            ((19, "April", 2025), "Pesach VII"),
+            ((3, "May", 2025), "Tazria-Metzora"),
+            ((10, "May", 2025), "Achrei Mot-Kedoshim"),
+            ((24, "May", 2025), "Behar-Bechukotai"),
+            ((26, "July", 2025), "Matot-Masei"),
+            ((2, "August", 2025), "Devarim"),
+            ((20, "September", 2025), "Nitzavim"),
+        ];
+        for &(day, expected) in weeks.iter() {
+            assert_eq!(reading(5785, Location::Diaspora, day), expected, "{:?}", day);
+            assert_eq!(reading(5785, Location::Israel, day), expected, "{:?}", day);
+        }
+    }
+
+    #[test]
+    fn second_day_of_shavuot_on_shabbat() {
+        // 5780: Chukat and Balak are read together outside Israel
+        assert_eq!(reading(5780, Location::Diaspora, (30, "May", 2020)), "Shavuot II");
+        assert_eq!(reading(5780, Location::Israel, (30, "May", 2020)), "Nasso");
+        assert_eq!(reading(5780, Location::Diaspora, (4, "July", 2020)), "Chukat-Balak");
+        assert_eq!(reading(5780, Location::Israel, (27, "June", 2020)), "Chukat");
+        assert_eq!(reading(5780, Location::Israel, (4, "July", 2020)), "Balak");
+        assert_eq!(reading(5780, Location::Israel, (11, "July", 2020)), "Pinchas");
+    }
+
+    #[test]
+    fn reading_of_a_week() {
+        // a Wednesday gives the coming Shabbat
+        let wednesday = HebrewDate::from_julian_day_number(gregorian(7, "May", 2025)).unwrap();
+        let week = parashah(wednesday, Location::Israel).unwrap();
+        assert_eq!(week.day, gregorian(10, "May", 2025));
+        assert_eq!(week.reading, Reading::Combined(Parashah::AchreiMot, Parashah::Kedoshim));
+        // the last days of the year read in the next one
+        let elul = HebrewDate::from_month(29, HebrewMonth::Elul, 5784).unwrap();
+        assert_eq!(parashah(elul, Location::Israel).map(|week| week.reading.to_string()), Ok(String::from("Ha'azinu")));
+    }
+}
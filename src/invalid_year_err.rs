@@ -1,10 +1,56 @@
 use std::error::Error;
 use std::fmt;
 
-#[derive(Debug)]
+use crate::julian_day::JulianDayNumber;
+
+/*
+Overflow: a number got too large while converting or doing arithmetic
+
+InvalidInput: the date is not valid in a way the other variants do not
+cover, such as a day number the Tibetan calendar skips
+
+DayOutOfRange: the day is 0 or past the end of its month, which has `max`
+days
+
+UnknownMonth: the month name or number is not one the calendar has
+
+MonthNotInYear: the month exists but not in that year, such as Adar I in a
+common Hebrew year or a leap month in a year without one
+
+InvalidYear: the era has no such year. Eras count their years from 1, so
+this is year 0 or a negative year
+
+UnknownEra, UnknownCalendar: the era or calendar name is not recognized
+
+OutOfSupportedRange: the date is before `min` or after `max`, the first
+and last days the calendar can convert
+
+There is no error for a day dropped by a calendar reform, such as 5 to 14
+October 1582. The Julian and Gregorian calendars here are proleptic: each
+runs unbroken through every year, so those days exist on both (10 October
+1582 Gregorian is 30 September Julian). Which calendar a historical date
+was written in is left to the caller
+ */
+#[derive(Clone, PartialEq, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum CalendarError {
     Overflow,
     InvalidInput,
+    DayOutOfRange { day: u32, max: u32 },
+    UnknownMonth(String),
+    MonthNotInYear { month: String, year: i64 },
+    InvalidYear { year: i64, era: String },
+    UnknownEra(String),
+    UnknownCalendar(String),
+    OutOfSupportedRange { min: JulianDayNumber, max: JulianDayNumber },
+}
+
+impl CalendarError {
+    // whether the date could not be handled because it was too early or
+    // late, rather than being wrong
+    pub fn is_overflow(&self) -> bool {
+        matches!(self, Self::Overflow | Self::OutOfSupportedRange { .. })
+    }
 }
 
 impl fmt::Display for CalendarError {
@@ -12,6 +58,16 @@ impl fmt::Display for CalendarError {
         let details: String = match self {
             Self::Overflow => String::from(crate::OVERFLOW_ERROR_STRING),
             Self::InvalidInput => String::from(crate::INVALID_DATE_ERROR_STRING),
+            Self::DayOutOfRange { day, max } => format!("Day {} does not exist, the month has {} days", day, max),
+            Self::UnknownMonth(month) => format!("Unknown month \"{}\"", month),
+            Self::MonthNotInYear { month, year } => format!("{} is not a month of the year {}", month, year),
+            Self::InvalidYear { year, era } => format!("There is no year {} {}, years are counted from 1", year, era),
+            Self::UnknownEra(era) => format!("Unknown era \"{}\"", era),
+            Self::UnknownCalendar(calendar) => format!("Unknown calendar \"{}\"", calendar),
+            Self::OutOfSupportedRange { min, max } => format!(
+                "The date is outside the supported range, Julian Day Numbers {} to {}",
+                min, max
+            ),
         };
         write!(f, "{}", details)
     }
@@ -22,6 +78,13 @@ impl Error for CalendarError {
         match self {
             Self::Overflow => return crate::OVERFLOW_ERROR_STRING,
             Self::InvalidInput => return crate::INVALID_DATE_ERROR_STRING,
+            Self::DayOutOfRange { .. } => "The day does not exist in its month",
+            Self::UnknownMonth(_) => "Unknown month",
+            Self::MonthNotInYear { .. } => "The month does not exist in that year",
+            Self::InvalidYear { .. } => "The year does not exist in its era",
+            Self::UnknownEra(_) => "Unknown era",
+            Self::UnknownCalendar(_) => "Unknown calendar",
+            Self::OutOfSupportedRange { .. } => crate::OVERFLOW_ERROR_STRING,
         }
     }
-}
\ No newline at end of file
+}
@@ -0,0 +1,108 @@
+use crate::invalid_year_err::CalendarError;
+use std::convert::TryFrom;
+
+/*
+Julian Day Number: the number of a whole day, counted from 1 January 4713 BC
+on the proleptic Julian calendar, which is day 0. The day runs from midnight
+to midnight, and its number is the astronomical julian date at noon of that
+day, so 1 January 2000 (Gregorian) is 2451545.
+
+Every calendar converts through this. The crate used to count days with a
+bare i128 one less than this, the julian date at the midnight starting the
+day rounded down. The deprecated Calendar::to_julian_day and from_julian_day
+still use that count, and from_legacy and to_legacy convert to and from it.
+
+Arithmetic is checked: adding or subtracting days returns None when the
+result does not fit instead of wrapping around
+ */
+#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct JulianDayNumber(i64);
+
+impl JulianDayNumber {
+    pub const MIN: JulianDayNumber = JulianDayNumber(i64::MIN);
+    pub const MAX: JulianDayNumber = JulianDayNumber(i64::MAX);
+
+    pub const fn new(day: i64) -> JulianDayNumber {
+        JulianDayNumber(day)
+    }
+
+    pub const fn value(self) -> i64 {
+        self.0
+    }
+
+    pub fn checked_add(self, days: i64) -> Option<JulianDayNumber> {
+        self.0.checked_add(days).map(JulianDayNumber)
+    }
+
+    pub fn checked_sub(self, days: i64) -> Option<JulianDayNumber> {
+        self.0.checked_sub(days).map(JulianDayNumber)
+    }
+
+    // checked_add with an error the calendars can return
+    pub(crate) fn offset(self, days: i64) -> Result<JulianDayNumber, CalendarError> {
+        match self.checked_add(days) {
+            Some(day) => Ok(day),
+            None => Err(CalendarError::Overflow),
+        }
+    }
+
+    // days from `earlier` to this day, negative if `earlier` is the later day
+    pub fn days_since(self, earlier: JulianDayNumber) -> i128 {
+        self.0 as i128 - earlier.0 as i128
+    }
+
+    // from the count used by the deprecated julian day methods
+    pub fn from_legacy(julian_day: i128) -> Result<JulianDayNumber, CalendarError> {
+        match julian_day.checked_add(1).map(i64::try_from) {
+            Some(Ok(day)) => Ok(JulianDayNumber(day)),
+            _ => Err(CalendarError::Overflow),
+        }
+    }
+
+    // the count used by the deprecated julian day methods
+    pub fn to_legacy(self) -> i128 {
+        self.0 as i128 - 1
+    }
+}
+
+impl From<i64> for JulianDayNumber {
+    fn from(day: i64) -> JulianDayNumber {
+        JulianDayNumber(day)
+    }
+}
+
+impl From<i32> for JulianDayNumber {
+    fn from(day: i32) -> JulianDayNumber {
+        JulianDayNumber(day as i64)
+    }
+}
+
+impl TryFrom<i128> for JulianDayNumber {
+    type Error = CalendarError;
+
+    fn try_from(day: i128) -> Result<Self, Self::Error> {
+        match i64::try_from(day) {
+            Ok(day) => Ok(JulianDayNumber(day)),
+            Err(_) => Err(CalendarError::Overflow),
+        }
+    }
+}
+
+impl From<JulianDayNumber> for i64 {
+    fn from(day: JulianDayNumber) -> i64 {
+        day.0
+    }
+}
+
+impl From<JulianDayNumber> for i128 {
+    fn from(day: JulianDayNumber) -> i128 {
+        day.0 as i128
+    }
+}
+
+impl std::fmt::Display for JulianDayNumber {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
@@ -1,11 +1,18 @@
 use crate::GenericDate;
 use crate::invalid_year_err::CalendarError;
-use crate::cal::Calendar;
+use crate::cal::{normalize_name, supported, Calendar};
+use crate::julian_day::JulianDayNumber;
+use crate::arithmetic::CalendarArithmetic;
+use crate::format::DateFormat;
 use std::cmp;
-use std::convert::TryInto;
-
-#[derive(PartialEq, Clone)]
-enum Era {
+use std::cmp::Ordering;
+use std::convert::{TryFrom, TryInto};
+use std::ops::RangeInclusive;
+use std::str::FromStr;
+
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Era {
     AD,
     BC,
 }
@@ -20,6 +27,129 @@ impl std::fmt::Display for Era {
     }
 }
 
+// accepts AD or CE and BC or BCE, ignoring case
+impl FromStr for Era {
+    type Err = CalendarError;
+
+    fn from_str(name: &str) -> Result<Self, Self::Err> {
+        match normalize_name(name).as_str() {
+            "ad" | "ce" => Ok(Era::AD),
+            "bc" | "bce" => Ok(Era::BC),
+            _ => Err(CalendarError::UnknownEra(String::from(name))),
+        }
+    }
+}
+
+// month of the Julian and Gregorian calendars
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum RomanMonth {
+    January,
+    February,
+    March,
+    April,
+    May,
+    June,
+    July,
+    August,
+    September,
+    October,
+    November,
+    December,
+}
+
+const ROMAN_MONTHS: [RomanMonth; 12] = [
+    RomanMonth::January,
+    RomanMonth::February,
+    RomanMonth::March,
+    RomanMonth::April,
+    RomanMonth::May,
+    RomanMonth::June,
+    RomanMonth::July,
+    RomanMonth::August,
+    RomanMonth::September,
+    RomanMonth::October,
+    RomanMonth::November,
+    RomanMonth::December,
+];
+
+pub const ROMAN_MONTH_NAMES: [&str; 12] = [
+    "January",
+    "February",
+    "March",
+    "April",
+    "May",
+    "June",
+    "July",
+    "August",
+    "September",
+    "October",
+    "November",
+    "December",
+];
+
+impl RomanMonth {
+    // starts at 1 for January
+    pub fn number(self) -> u8 {
+        self as u8 + 1
+    }
+
+    pub fn name(self) -> &'static str {
+        ROMAN_MONTH_NAMES[self as usize]
+    }
+}
+
+impl TryFrom<u8> for RomanMonth {
+    type Error = CalendarError;
+
+    fn try_from(number: u8) -> Result<Self, Self::Error> {
+        match ROMAN_MONTHS.get((number as usize).wrapping_sub(1)) {
+            Some(month) => Ok(*month),
+            None => Err(CalendarError::UnknownMonth(number.to_string())),
+        }
+    }
+}
+
+// accepts full names, three letter abbreviations ("Sep" or "Sept" for
+// September) and month numbers, ignoring case
+impl TryFrom<&str> for RomanMonth {
+    type Error = CalendarError;
+
+    fn try_from(input: &str) -> Result<Self, Self::Error> {
+        let name = normalize_name(input);
+        if let Ok(number) = name.parse::<u8>() {
+            return RomanMonth::try_from(number);
+        }
+        if name == "sept" {
+            return Ok(RomanMonth::September);
+        }
+
+        let position = ROMAN_MONTH_NAMES.iter().position(|full| {
+            let full = full.to_lowercase();
+            (name == full) | ((name.len() == 3) & full.starts_with(name.as_str()))
+        });
+        match position {
+            Some(i) => Ok(ROMAN_MONTHS[i]),
+            None => Err(CalendarError::UnknownMonth(String::from(input))),
+        }
+    }
+}
+
+impl FromStr for RomanMonth {
+    type Err = CalendarError;
+
+    fn from_str(name: &str) -> Result<Self, Self::Err> {
+        RomanMonth::try_from(name)
+    }
+}
+
+impl std::fmt::Display for RomanMonth {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(f, "{}", self.name())
+    }
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct JulianCalendar {
     era: Era,
     year: u32,
@@ -29,50 +159,28 @@ pub struct JulianCalendar {
 
 impl JulianCalendar {
     fn valid(&self) -> Option<CalendarError> {
-        if self.day <= 0 {
-            return Some(CalendarError::InvalidInput);
-        }
-        if self.year <= 0 {
-            return Some(CalendarError::InvalidInput);
+        if self.year == 0 {
+            return Some(CalendarError::InvalidYear { year: 0, era: self.era.to_string() });
         }
         if (self.month > 12) | (self.month <= 0) {
-            return Some(CalendarError::InvalidInput);
+            return Some(CalendarError::UnknownMonth(self.month.to_string()));
         }
 
-        if self.month == 2 {
-            if self.is_leap_year() & (self.day > 29) {
-                return Some(CalendarError::InvalidInput);
-            }
-
-            if !self.is_leap_year() & (self.day > 28) {
-                return Some(CalendarError::InvalidInput);
-            }
-        } else {
-            if (ROMAN_MONTH_LENGTHS[(self.month - 1) as usize] as u8) < self.day {
-                return Some(CalendarError::InvalidInput);
-            }
+        let max = match self.month {
+            2 if self.is_leap_year() => 29,
+            _ => ROMAN_MONTH_LENGTHS[(self.month - 1) as usize] as u8,
+        };
+        if (self.day == 0) | (self.day > max) {
+            return Some(CalendarError::DayOutOfRange { day: self.day as u32, max: max as u32 });
         }
         return None;
     }
-
-    #[allow(dead_code)]
-    fn is_leap_year(&self) -> bool {
-        let year: u32;
-        if self.era == Era::BC {
-            year = self.year - 1;
-        } else {
-            year = self.year;
-        }
-
-        if year % 4 == 0 {
-            return true;
-        }
-        return false;
-    }
 }
 
 
 
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct GregorianCalendar {
     era: Era,
     year: u32,
@@ -84,51 +192,56 @@ impl GregorianCalendar {
 
     #[allow(dead_code)]
     fn valid(&self) -> Option<CalendarError> {
-        if self.day <= 0 {
-            return Some(CalendarError::InvalidInput);
-        }
-        if self.year <= 0 {
-            return Some(CalendarError::InvalidInput);
+        if self.year == 0 {
+            return Some(CalendarError::InvalidYear { year: 0, era: self.era.to_string() });
         }
         if (self.month > 12) | (self.month <= 0) {
-            return Some(CalendarError::InvalidInput);
+            return Some(CalendarError::UnknownMonth(self.month.to_string()));
         }
 
-        if self.month == 2 {
-            if self.is_leap_year() & (self.day > 29) {
-                return Some(CalendarError::InvalidInput);
-            }
-
-            if !self.is_leap_year() & (self.day > 28) {
-                return Some(CalendarError::InvalidInput);
-            }
-        } else {
-            if (ROMAN_MONTH_LENGTHS[(self.month - 1) as usize] as u8) < self.day {
-                return Some(CalendarError::InvalidInput);
-            }
+        let max = match self.month {
+            2 if self.is_leap_year() => 29,
+            _ => ROMAN_MONTH_LENGTHS[(self.month - 1) as usize] as u8,
+        };
+        if (self.day == 0) | (self.day > max) {
+            return Some(CalendarError::DayOutOfRange { day: self.day as u32, max: max as u32 });
         }
         return None;
     }
 
-    
+    }
 
-    #[allow(dead_code)]
-    fn is_leap_year(&self) -> bool {
-        let year: u32;
-        if self.era == Era::BC {
-            year = self.year - 1;
-        } else {
-            year = self.year;
-        }
 
-        if ((year % 4 == 0) & (year % 100 != 0)) | ((year % 4 == 0) & (year % 400 == 0)) {
-            return true;
+
+impl JulianCalendar {
+    pub fn from_month(day: u8, month: RomanMonth, year: u32, era: Era) -> Result<JulianCalendar, CalendarError> {
+        let date = JulianCalendar {
+            era,
+            year,
+            month: month.number(),
+            day,
+        };
+        match date.valid() {
+            Some(e) => Err(e),
+            None => Ok(date),
         }
-        return false;
     }
 }
 
-
+impl GregorianCalendar {
+    pub fn from_month(day: u8, month: RomanMonth, year: u32, era: Era) -> Result<GregorianCalendar, CalendarError> {
+        let date = GregorianCalendar {
+            era,
+            year,
+            month: month.number(),
+            day,
+        };
+        match date.valid() {
+            Some(e) => Err(e),
+            None => Ok(date),
+        }
+    }
+}
 
 impl std::fmt::Display for JulianCalendar {
     fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
@@ -167,7 +280,7 @@ impl std::fmt::Display for JulianCalendar {
 impl std::fmt::Display for GregorianCalendar {
     fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
         let temp = JulianCalendar {
-            era: self.era.clone(),
+            era: self.era,
             year: self.year,
             month: self.month,
             day: self.day
@@ -180,50 +293,36 @@ impl std::fmt::Display for GregorianCalendar {
 const ROMAN_MONTH_LENGTHS: [i32; 12] = [31, 28, 31, 30, 31, 30, 31, 31, 30, 31, 30, 31];
 
 impl Calendar for GregorianCalendar {
-    fn to_julian_day(&self) -> Result<i128, CalendarError> {
-        return from_proleptic_gregorian(self);
+    fn to_julian_day_number(&self) -> Result<JulianDayNumber, CalendarError> {
+        supported::<Self>(JulianDayNumber::from_legacy(from_proleptic_gregorian(self)?)?)
     }
 
-    fn from_julian_day(julian_day: i128) -> Result<Self, CalendarError> where Self: Sized {
-        return to_proleptic_gregorian(julian_day);
+    fn from_julian_day_number(day: JulianDayNumber) -> Result<Self, CalendarError> where Self: Sized {
+        to_proleptic_gregorian(supported::<Self>(day)?.to_legacy())
+    }
+
+    // 1 January 4400 BC to 31 December AD 4294967295
+    fn supported_range() -> RangeInclusive<JulianDayNumber> where Self: Sized {
+        JulianDayNumber::new(114359)..=JulianDayNumber::new(1568706313669)
     }
 
     fn new(day: u32, month: String, year: i32, era: String) -> Result<Self, CalendarError>  where Self: Sized {
-        let day_error: Result<u8, _> = day.try_into();
-        let day;
-        match day_error {
-            Ok(n) => day = n,
-            Err(_) => return Err(CalendarError::InvalidInput),
-        }
+        let month_num = RomanMonth::try_from(month.as_str())?.number();
 
-        let month_num = match month.as_str() {
-            "January" => 1,
-            "February" => 2,
-            "March" => 3,
-            "April" => 4,
-            "May" => 5,
-            "June" => 6,
-            "July" => 7,
-            "August" => 8,
-            "September" => 9,
-            "October" => 10,
-            "November" => 11,
-            "December" => 12,
-            _ => panic!(),
-        };
+        let era: Era = era.parse()?;
 
-        let year_error: Result<u32, _> = year.try_into();
-        let year: u32;
-        match year_error{
-            Ok(n) => year = n,
-            Err(_) => return Err(CalendarError::InvalidInput),
-        }
-       
-        let era: Era = match era.as_str() {
-            "AD" => Era::AD,
-            _ => Era::BC,
+        let year: u32 = match year.try_into() {
+            Ok(n) if n > 0 => n,
+            _ => return Err(CalendarError::InvalidYear { year: year as i64, era: era.to_string() }),
         };
 
+        let day = match day.try_into() {
+            Ok(n) => n,
+            Err(_) => {
+                let max = roman_month_length::<Self>(&era, year, month_num)?;
+                return Err(CalendarError::DayOutOfRange { day, max });
+            }
+        };
 
         return Ok(GregorianCalendar {
             era: era,
@@ -236,16 +335,21 @@ impl Calendar for GregorianCalendar {
 }
 
 impl Calendar for JulianCalendar {
-    fn to_julian_day(&self) -> Result<i128, CalendarError> {
-        return from_proleptic_julian(self);
+    fn to_julian_day_number(&self) -> Result<JulianDayNumber, CalendarError> {
+        supported::<Self>(JulianDayNumber::from_legacy(from_proleptic_julian(self)?)?)
+    }
+
+    fn from_julian_day_number(day: JulianDayNumber) -> Result<Self, CalendarError> where Self: Sized {
+        to_proleptic_julian(supported::<Self>(day)?.to_legacy())
     }
 
-    fn from_julian_day(julian_day: i128) -> Result<Self, CalendarError> where Self: Sized {
-        return to_proleptic_julian(julian_day);
+    // 1 January 4712 BC to 31 December AD 4294967295
+    fn supported_range() -> RangeInclusive<JulianDayNumber> where Self: Sized {
+        JulianDayNumber::new(366)..=JulianDayNumber::new(1568738525921)
     }
 
     fn new(day: u32, month: String, year: i32, era: String) -> Result<Self, CalendarError>  where Self: Sized {
-        let greg = GregorianCalendar::new(day, month, year, era);
+        let greg = GregorianCalendar::new(day, month.clone(), year, era.clone());
         match greg {
             Ok(cal) => return Ok(JulianCalendar {
                 era: cal.era,
@@ -253,11 +357,141 @@ impl Calendar for JulianCalendar {
                 month: cal.month,
                 day: cal.day,
             }),
+            // the length of february differs between the two calendars
+            Err(CalendarError::DayOutOfRange { day, .. }) => {
+                let cal = GregorianCalendar::new(1, month, year, era)?;
+                let max = roman_month_length::<Self>(&cal.era, cal.year, cal.month)?;
+                return Err(CalendarError::DayOutOfRange { day, max });
+            }
             Err(e) => return Err(e)
         };
     }
 }
 
+fn era_year(year: i64) -> Result<(Era, u32), CalendarError> {
+    let (era, year) = match year > 0 {
+        true => (Era::AD, year),
+        false => (Era::BC, 1 - year),
+    };
+    match year.try_into() {
+        Ok(n) => Ok((era, n)),
+        Err(_) => Err(CalendarError::Overflow),
+    }
+}
+
+// length of a month on calendar T, for the day range in errors
+fn roman_month_length<T: CalendarArithmetic>(era: &Era, year: u32, month: u8) -> Result<u32, CalendarError> {
+    Ok(T::month_length(astronomical_year(era, year), month)? as u32)
+}
+
+fn astronomical_year(era: &Era, year: u32) -> i64 {
+    match era {
+        Era::AD => year as i64,
+        Era::BC => 1 - year as i64,
+    }
+}
+
+// position in time, for ordering. BC years count down, so they are compared
+// as astronomical years. The era only matters for the invalid year 0 AD
+fn order_key(era: &Era, year: u32, month: u8, day: u8) -> (i64, u8, u8, bool) {
+    (astronomical_year(era, year), month, day, *era == Era::AD)
+}
+
+impl Ord for GregorianCalendar {
+    fn cmp(&self, other: &Self) -> Ordering {
+        order_key(&self.era, self.year, self.month, self.day).cmp(&order_key(&other.era, other.year, other.month, other.day))
+    }
+}
+
+impl PartialOrd for GregorianCalendar {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for JulianCalendar {
+    fn cmp(&self, other: &Self) -> Ordering {
+        order_key(&self.era, self.year, self.month, self.day).cmp(&order_key(&other.era, other.year, other.month, other.day))
+    }
+}
+
+impl PartialOrd for JulianCalendar {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl CalendarArithmetic for GregorianCalendar {
+    fn fields(&self) -> (i64, u8, u8) {
+        (astronomical_year(&self.era, self.year), self.month, self.day)
+    }
+
+    fn is_leap_year(&self) -> bool {
+        let year = astronomical_year(&self.era, self.year);
+        (year.rem_euclid(4) == 0) & ((year.rem_euclid(100) != 0) | (year.rem_euclid(400) == 0))
+    }
+
+    fn from_fields(year: i64, month: u8, day: u8) -> Result<Self, CalendarError> {
+        let (era, year) = era_year(year)?;
+        Ok(GregorianCalendar { era, year, month, day })
+    }
+
+    fn month_count(_year: i64) -> Result<u8, CalendarError> {
+        Ok(12)
+    }
+
+    const MONTH_CYCLE: Option<(i64, i64)> = Some((1, 12));
+
+    fn month_length(year: i64, month: u8) -> Result<u8, CalendarError> {
+        let date = GregorianCalendar::from_fields(year, month, 1)?;
+        match month {
+            2 if date.is_leap_year() => Ok(29),
+            1..=12 => Ok(ROMAN_MONTH_LENGTHS[month as usize - 1] as u8),
+            _ => Err(CalendarError::UnknownMonth(month.to_string())),
+        }
+    }
+}
+
+impl CalendarArithmetic for JulianCalendar {
+    fn fields(&self) -> (i64, u8, u8) {
+        (astronomical_year(&self.era, self.year), self.month, self.day)
+    }
+
+    fn is_leap_year(&self) -> bool {
+        astronomical_year(&self.era, self.year).rem_euclid(4) == 0
+    }
+
+    fn from_fields(year: i64, month: u8, day: u8) -> Result<Self, CalendarError> {
+        let (era, year) = era_year(year)?;
+        Ok(JulianCalendar { era, year, month, day })
+    }
+
+    fn month_count(_year: i64) -> Result<u8, CalendarError> {
+        Ok(12)
+    }
+
+    const MONTH_CYCLE: Option<(i64, i64)> = Some((1, 12));
+
+    fn month_length(year: i64, month: u8) -> Result<u8, CalendarError> {
+        let date = JulianCalendar::from_fields(year, month, 1)?;
+        match month {
+            2 if date.is_leap_year() => Ok(29),
+            1..=12 => Ok(ROMAN_MONTH_LENGTHS[month as usize - 1] as u8),
+            _ => Err(CalendarError::UnknownMonth(month.to_string())),
+        }
+    }
+}
+
+// julian day of a proleptic Gregorian date given as plain numbers, with
+// astronomical year numbering (1 BC is year 0). Used by calendars which are
+// defined in terms of Gregorian dates
+pub(crate) fn gregorian_julian_day(year: i128, month: i128, day: i128) -> i128 {
+    let a = (14 - month) / 12;
+    let y = year + 4800 - a;
+    let m = month + 12 * a - 3;
+    day + (153 * m + 2) / 5 + 365 * y + y.div_euclid(4) - y.div_euclid(100) + y.div_euclid(400) - 32046
+}
+
 #[allow(dead_code)]
 pub fn to_proleptic_gregorian(
     julian_day: i128,
@@ -305,10 +539,10 @@ pub fn to_proleptic_gregorian(
             if days_left >= ROMAN_MONTH_LENGTHS[1] + 1 {
                 days_left = days_left - (ROMAN_MONTH_LENGTHS[1] + 1);
                 month = 2;
-            }
-            while days_left >= ROMAN_MONTH_LENGTHS[month] {
-                days_left = days_left - ROMAN_MONTH_LENGTHS[month];
-                month = month + 1;
+                while days_left >= ROMAN_MONTH_LENGTHS[month] {
+                    days_left = days_left - ROMAN_MONTH_LENGTHS[month];
+                    month = month + 1;
+                }
             }
         } else {
             if days_left >= ROMAN_MONTH_LENGTHS[1] {
@@ -359,8 +593,8 @@ pub fn from_proleptic_gregorian(
         return Err(CalendarError::Overflow);
     }
 
-    if cal_date.valid().is_some() {
-        return Err(CalendarError::InvalidInput);
+    if let Some(e) = cal_date.valid() {
+        return Err(e);
     }
 
     let year: i128;
@@ -394,6 +628,10 @@ pub fn from_proleptic_gregorian(
         month_days = month_days + ROMAN_MONTH_LENGTHS[(monthi - 1) as usize];
         monthi = monthi + 1;
     }
+    // the leap day comes at the end of February
+    if (cal_date.month > 2) & cal_date.is_leap_year() {
+        month_days += 1;
+    }
 
     return Ok((year_days as i128) + (month_days as i128) + (cal_date.day as i128 - 1) + MIN_DAY);
 }
@@ -436,14 +674,14 @@ fn to_proleptic_julian(
     if days_left >= ROMAN_MONTH_LENGTHS[0] {
         days_left = days_left - ROMAN_MONTH_LENGTHS[0];
         month = 1;
-        if ((year % 4 == 0) & (year % 100 != 0)) | ((year % 4 == 0) & (year % 400 == 0)) {
+        if year % 4 == 0 {
             if days_left >= ROMAN_MONTH_LENGTHS[1] + 1 {
                 days_left = days_left - (ROMAN_MONTH_LENGTHS[1] + 1);
                 month = 2;
-            }
-            while days_left >= ROMAN_MONTH_LENGTHS[month] {
-                days_left = days_left - ROMAN_MONTH_LENGTHS[month];
-                month = month + 1;
+                while days_left >= ROMAN_MONTH_LENGTHS[month] {
+                    days_left = days_left - ROMAN_MONTH_LENGTHS[month];
+                    month = month + 1;
+                }
             }
         } else {
             if days_left >= ROMAN_MONTH_LENGTHS[1] {
@@ -490,12 +728,12 @@ fn from_proleptic_julian(
     const MIN_DAY_YEAR_ADJUSTMENT: i128 = 4712; // MUST BE DIVISIBLE BY 4!!!
                                                 // 1721423-4712*365.25
     const MIN_DAY: i128 = FIRST_JANUARY_1_AD - (MIN_DAY_YEAR_ADJUSTMENT / 4) * (365 * 4 + 1);
-    if (cal_date.year < MIN_DAY_YEAR_ADJUSTMENT as u32) & (cal_date.era == Era::BC) {
+    if (cal_date.year > MIN_DAY_YEAR_ADJUSTMENT as u32) & (cal_date.era == Era::BC) {
         return Err(CalendarError::Overflow);
     }
 
-    if cal_date.valid().is_some() {
-        return Err(CalendarError::InvalidInput);
+    if let Some(e) = cal_date.valid() {
+        return Err(e);
     }
 
     let year: i128;
@@ -513,11 +751,51 @@ fn from_proleptic_julian(
         month_days = month_days + ROMAN_MONTH_LENGTHS[(monthi - 1) as usize];
         monthi = monthi + 1;
     }
+    // the leap day comes at the end of February
+    if (cal_date.month > 2) & cal_date.is_leap_year() {
+        month_days += 1;
+    }
 
     return Ok((year_days as i128) + (month_days as i128) + (cal_date.day as i128 - 1) + MIN_DAY);
 }
 
 
+impl DateFormat for JulianCalendar {
+    const CALENDAR: &'static str = "Julian";
+
+    fn month_name(&self) -> String {
+        roman_month_name(self.month)
+    }
+
+    fn era(&self) -> Option<Era> {
+        Some(self.era)
+    }
+
+    fn year_in_era(&self) -> i64 {
+        self.year as i64
+    }
+}
+
+impl DateFormat for GregorianCalendar {
+    const CALENDAR: &'static str = "Gregorian";
+
+    fn month_name(&self) -> String {
+        roman_month_name(self.month)
+    }
+
+    fn era(&self) -> Option<Era> {
+        Some(self.era)
+    }
+
+    fn year_in_era(&self) -> i64 {
+        self.year as i64
+    }
+}
+
+fn roman_month_name(month: u8) -> String {
+    String::from(RomanMonth::try_from(month).map(|m| m.name()).unwrap_or(""))
+}
+
 impl Into<GenericDate> for JulianCalendar {
     fn into(self) -> GenericDate {
         GenericDate {
@@ -529,7 +807,13 @@ impl Into<GenericDate> for JulianCalendar {
             }},
             year: self.year,
             month_name: self.month,
-            day: self.day
+            day: self.day,
+            is_leap_day: false,
+            weekday: 0,
+            error: String::new(),
+            month_text: String::new(),
+            weekday_text: String::new(),
+            era_text: String::new(),
         }
     }
 }
@@ -545,7 +829,100 @@ impl Into<GenericDate> for GregorianCalendar {
             }},
             year: self.year,
             month_name: self.month,
-            day: self.day
+            day: self.day,
+            is_leap_day: false,
+            weekday: 0,
+            error: String::new(),
+            month_text: String::new(),
+            weekday_text: String::new(),
+            era_text: String::new(),
+        }
+    }
+}
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::julian_day::JulianDayNumber;
+
+    fn julian(day: u32, month: &str, year: i32, era: &str) -> JulianCalendar {
+        JulianCalendar::new(day, month.into(), year, era.into()).unwrap()
+    }
+
+    fn gregorian(day: u32, month: &str, year: i32, era: &str) -> GregorianCalendar {
+        GregorianCalendar::new(day, month.into(), year, era.into()).unwrap()
+    }
+
+    #[test]
+    fn gregorian_days_after_leap_day() {
+        assert_eq!(gregorian(1, "January", 2000, "AD").to_julian_day_number(), Ok(JulianDayNumber::new(2451545)));
+        assert_eq!(gregorian(29, "February", 2024, "AD").to_julian_day_number(), Ok(JulianDayNumber::new(2460370)));
+        assert_eq!(gregorian(1, "March", 2024, "AD").to_julian_day_number(), Ok(JulianDayNumber::new(2460371)));
+        assert_eq!(gregorian(31, "December", 2024, "AD").to_julian_day_number(), Ok(JulianDayNumber::new(2460676)));
+        assert_eq!(GregorianCalendar::from_julian_day_number(JulianDayNumber::new(2460370)), Ok(gregorian(29, "February", 2024, "AD")));
+        assert_eq!(GregorianCalendar::from_julian_day_number(JulianDayNumber::new(2460371)), Ok(gregorian(1, "March", 2024, "AD")));
+    }
+
+    #[test]
+    fn julian_leap_years_every_fourth_year() {
+        // 1900 is a leap year in the Julian calendar only
+        let leap_day = julian(29, "February", 1900, "AD");
+        assert_eq!(leap_day.to_julian_day_number(), Ok(gregorian(13, "March", 1900, "AD").to_julian_day_number().unwrap()));
+        assert_eq!(JulianCalendar::from_julian_day_number(leap_day.to_julian_day_number().unwrap()), Ok(leap_day));
+        assert_eq!(
+            JulianCalendar::from_julian_day_number(julian(1, "March", 1900, "AD").to_julian_day_number().unwrap()),
+            Ok(julian(1, "March", 1900, "AD"))
+        );
+    }
+
+    #[test]
+    fn julian_and_gregorian_meet_at_the_reform() {
+        let thursday = julian(4, "October", 1582, "AD").to_julian_day_number().unwrap();
+        let friday = gregorian(15, "October", 1582, "AD").to_julian_day_number().unwrap();
+        assert_eq!(friday.value() - thursday.value(), 1);
+
+        // both calendars are proleptic, so the days dropped by the reform
+        // are ordinary dates on each
+        let dropped = gregorian(10, "October", 1582, "AD").to_julian_day_number().unwrap();
+        assert_eq!(JulianCalendar::from_julian_day_number(dropped), Ok(julian(30, "September", 1582, "AD")));
+        let dropped = julian(10, "October", 1582, "AD").to_julian_day_number().unwrap();
+        assert_eq!(GregorianCalendar::from_julian_day_number(dropped), Ok(gregorian(20, "October", 1582, "AD")));
+    }
+
+    #[test]
+    fn julian_bc_dates() {
+        // the Ides of March, 44 BC
+        let ides = julian(15, "March", 44, "BC");
+        assert_eq!(ides.to_julian_day_number(), Ok(JulianDayNumber::new(1705426)));
+        assert_eq!(JulianCalendar::from_julian_day_number(JulianDayNumber::new(1705426)), Ok(ides));
+        assert_eq!(julian(31, "December", 1, "BC").to_julian_day_number(), Ok(JulianDayNumber::new(1721423)));
+    }
+
+    #[test]
+    fn every_day_converts_back() {
+        for day in (1721000..1723000).chain(2451000..2453000) {
+            let day = JulianDayNumber::new(day);
+            assert_eq!(JulianCalendar::from_julian_day_number(day).unwrap().to_julian_day_number(), Ok(day));
+            assert_eq!(GregorianCalendar::from_julian_day_number(day).unwrap().to_julian_day_number(), Ok(day));
         }
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn years_count_from_1() {
+        assert_eq!(
+            GregorianCalendar::new(1, "January".into(), 0, "AD".into()),
+            Err(CalendarError::InvalidYear { year: 0, era: String::from("AD") })
+        );
+        assert_eq!(
+            JulianCalendar::new(1, "January".into(), -3, "BCE".into()),
+            Err(CalendarError::InvalidYear { year: -3, era: String::from("BC") })
+        );
+        assert_eq!(
+            JulianCalendar::from_month(1, RomanMonth::January, 0, Era::BC),
+            Err(CalendarError::InvalidYear { year: 0, era: String::from("BC") })
+        );
+        assert_eq!(
+            GregorianCalendar { era: Era::AD, year: 0, month: 1, day: 1 }.to_julian_day_number(),
+            Err(CalendarError::InvalidYear { year: 0, era: String::from("AD") })
+        );
+    }
+}
@@ -4,15 +4,31 @@ use wasm_bindgen::prelude::*;
 
 use invalid_year_err::CalendarError;
 
-use crate::hebrew::HebrewDate;
-use crate::julian_gregorian::JulianCalendar;
-use crate::julian_gregorian::GregorianCalendar;
 use crate::cal::Calendar;
+use crate::cyclic::CyclicDate;
+use crate::hebrew::parashah::ParashahWeek;
+use crate::registry::with_registry;
 
 pub mod hebrew;
 pub mod invalid_year_err;
 pub mod julian_gregorian;
+pub mod babylonian;
+pub mod tibetan;
+pub mod sidereal_solar;
 pub mod cal;
+pub mod julian_day;
+pub mod arithmetic;
+pub mod range;
+pub mod cyclic;
+pub mod mars;
+pub mod registry;
+pub mod any_date;
+pub mod parse;
+pub mod format;
+pub mod locale;
+pub mod gematria;
+pub mod roman_numerals;
+mod astronomy;
 
 pub const OVERFLOW_ERROR_STRING: &str = "The entered date was too early or late to handle"; 
 pub const INVALID_DATE_ERROR_STRING: &str = "The entered date was not valid";
@@ -50,6 +66,18 @@ this may not always correspond to the month's number (e.g. in Hebrew leap
 years)
 
 day: day in month
+
+is_leap_day: true when the day number is repeated and this is the second of
+the two days with that number (e.g. in the Tibetan calendar). otherwise false
+
+weekday: day of the week, 0 for Sunday to 6 for Saturday
+
+error: message saying what was wrong with the date when is_valid or
+is_not_overflow is false. otherwise empty
+
+month_text, weekday_text, era_text: the names of the month, weekday and era
+in the locale the date was converted for (see the locale module). era_text
+is empty for calendars without eras
  */
 #[wasm_bindgen]
 pub struct GenericDate {
@@ -59,6 +87,12 @@ pub struct GenericDate {
     year: u32,
     month_name: u8,
     day: u8,
+    is_leap_day: bool,
+    weekday: u8,
+    error: String,
+    month_text: String,
+    weekday_text: String,
+    era_text: String,
 }
 
 #[wasm_bindgen]
@@ -87,6 +121,70 @@ impl GenericDate {
     pub fn get_day (&self) -> u8 {
         self.day
     }
+
+    pub fn get_is_leap_day (&self) -> bool {
+        self.is_leap_day
+    }
+
+    pub fn get_weekday (&self) -> u8 {
+        self.weekday
+    }
+
+    pub fn get_error (&self) -> String {
+        self.error.clone()
+    }
+
+    pub fn get_month_text (&self) -> String {
+        self.month_text.clone()
+    }
+
+    pub fn get_weekday_text (&self) -> String {
+        self.weekday_text.clone()
+    }
+
+    pub fn get_era_text (&self) -> String {
+        self.era_text.clone()
+    }
+}
+
+impl GenericDate {
+    // a valid date, for calendars registered from other crates. The weekday
+    // is filled in by the registry
+    pub fn new(era: bool, year: u32, month_name: u8, day: u8, is_leap_day: bool) -> GenericDate {
+        GenericDate {
+            is_valid: true,
+            is_not_overflow: true,
+            era,
+            year,
+            month_name,
+            day,
+            is_leap_day,
+            weekday: 0,
+            error: String::new(),
+            month_text: String::new(),
+            weekday_text: String::new(),
+            era_text: String::new(),
+        }
+    }
+}
+
+impl From<CalendarError> for GenericDate {
+    fn from(error: CalendarError) -> GenericDate {
+        GenericDate {
+            is_valid: error.is_overflow(),
+            is_not_overflow: !error.is_overflow(),
+            era: false,
+            year: 0,
+            month_name: 0,
+            day: 0,
+            is_leap_day: false,
+            weekday: 0,
+            error: error.to_string(),
+            month_text: String::new(),
+            weekday_text: String::new(),
+            era_text: String::new(),
+        }
+    }
 }
 
 #[wasm_bindgen]
@@ -95,78 +193,111 @@ pub fn js_api(from: &str, to: &str,  day: u32, month: &str, year: i32, era: &str
     log_to_console("");
     utils::set_panic_hook();
     //log_to_console("log test");
-    let from = String::from(from);
-    let to = String::from(to);
-    let month = String::from(month);
-    let era = String::from(era);
-
-    match from.as_str() {
-        "Hebrew" => {
-            let cal = HebrewDate::new(day, month, year, era);
-            return js_api_helper(cal, to);
-        },
-        "Julian" => {
-            let cal = JulianCalendar::new(day, month, year, era);
-            return js_api_helper(cal, to);
-        },
-        "Gregorian" => {
-            let cal = GregorianCalendar::new(day, month, year, era);
-            return js_api_helper(cal, to);
-        },
-        _ => {
-            panic!()
-        }
+
+    js_api_localized(from, to, day, month, year, era, "en")
+}
+
+// js_api with the names of the month, weekday and era in the given locale
+#[wasm_bindgen]
+pub fn js_api_localized(from: &str, to: &str, day: u32, month: &str, year: i32, era: &str, locale: &str) -> GenericDate {
+    utils::set_panic_hook();
+    match with_registry(|registry| registry.convert_in(from, to, day, month, year, era, locale)) {
+        Ok(date) => date,
+        Err(e) => e.into(),
     }
 }
 
-fn js_api_helper<T1: Calendar + std::fmt::Display>(from_cal: Result<T1, CalendarError>, to: String) -> GenericDate{
-    let date;
-    match from_cal {
-        Err(CalendarError::InvalidInput) => return GenericDate { is_valid: false, is_not_overflow: true, era: false, year: 0, month_name: 0, day: 0 },
-        Err(CalendarError::Overflow) => return GenericDate { is_valid: true, is_not_overflow: false, era: false, year: 0, month_name: 0, day: 0 },
-        Ok(d) => date = d,
-    };
-
-    match to.as_str() {
-        "Hebrew" => {
-            let result = convert::<T1, HebrewDate>(date);
-            return js_api_helper_helper(result);
-        }
-        "Julian" => {
-            let result = convert::<T1, JulianCalendar>(date);
-            return js_api_helper_helper(result);
-        }
-        "Gregorian" => {
-            let result = convert::<T1, GregorianCalendar>(date);
-            return js_api_helper_helper(result);
-        }
-        _ => {
-            panic!()
-        }
-    };
+// the aztec and akan cyclic counts of a date, to be shown next to the converted date
+#[wasm_bindgen]
+pub fn js_cyclic_api(from: &str, day: u32, month: &str, year: i32, era: &str) -> CyclicDate {
+    utils::set_panic_hook();
+    match with_registry(|registry| registry.to_julian_day_number(from, day, month, year, era)) {
+        Ok(julian_day) => CyclicDate::from_julian_day_number(julian_day),
+        Err(e) => CyclicDate::error(e.is_overflow(), !e.is_overflow()),
+    }
 }
 
-fn js_api_helper_helper<T1: Into<GenericDate> + Calendar + std::fmt::Display>(input: Result<T1, CalendarError>) -> GenericDate {
-    match input {
-        Ok(date) => return date.into(),
-        Err(CalendarError::InvalidInput) => GenericDate { is_valid: false, is_not_overflow: true, era: false, year: 0, month_name: 0, day: 0 },
-        Err(CalendarError::Overflow) => GenericDate { is_valid: true, is_not_overflow: false, era: false, year: 0, month_name: 0, day: 0 }
+// where a calendar kept in Israel or outside it is asked for from javascript
+fn location(israel: bool) -> hebrew::Location {
+    match israel {
+        true => hebrew::Location::Israel,
+        false => hebrew::Location::Diaspora,
     }
 }
 
-fn convert<T1: Calendar + std::fmt::Display, T2: Calendar + std::fmt::Display>(from_cal: T1) -> Result<T2, CalendarError> {
-    //let julian_day = ;
-    let julian_day;
-    match from_cal.to_julian_day() {
-        Ok(day) => julian_day = day,
-        Err(e) => return Err(e)
+// the Torah reading of the week of a date, read on the Shabbat on or after it
+#[wasm_bindgen]
+pub fn js_parashah(from: &str, day: u32, month: &str, year: i32, era: &str, israel: bool) -> ParashahWeek {
+    utils::set_panic_hook();
+    let reading = with_registry(|registry| registry.to_julian_day_number(from, day, month, year, era))
+        .and_then(hebrew::HebrewDate::from_julian_day_number)
+        .and_then(|date| hebrew::parashah::parashah(date, location(israel)));
+    match reading {
+        Ok(reading) => reading.into(),
+        Err(e) => e.into(),
+    }
+}
+
+// the Torah readings of every Shabbat of a Hebrew year
+#[wasm_bindgen]
+pub fn js_parashah_schedule(year: u32, israel: bool) -> Vec<ParashahWeek> {
+    utils::set_panic_hook();
+    match hebrew::parashah::schedule(year, location(israel)) {
+        Ok(readings) => readings.into_iter().map(ParashahWeek::from).collect(),
+        Err(e) => vec![e.into()],
+    }
+}
+
+/*
+`days` consecutive days on the `to` calendar, starting with the given date
+on the `from` calendar. With a step above 1 only every `step`th of those
+days is returned, at most registry::MAX_RANGE_DATES of them. If the date is
+invalid, cannot be converted or the range has too many dates, the array
+holds a single GenericDate with the error
+ */
+// the arguments are flat because web assembly cannot take a date object
+#[allow(clippy::too_many_arguments)]
+#[wasm_bindgen]
+pub fn js_range_api(from: &str, to: &str, day: u32, month: &str, year: i32, era: &str, days: u32, step: u32) -> Vec<GenericDate> {
+    utils::set_panic_hook();
+
+    let dates = with_registry(|registry| {
+        let julian_day = registry.to_julian_day_number(from, day, month, year, era)?;
+        registry.range(to, julian_day, days, step)
+    });
+    match dates {
+        Ok(dates) => dates,
+        Err(e) => vec![e.into()],
     }
-    
-    log_to_console(format!("julian_day: {}", julian_day).as_str());
-    return T2::from_julian_day(julian_day);
 }
 
+// the ids of the calendars js_api can convert between
+#[wasm_bindgen]
+pub fn js_calendars() -> Vec<String> {
+    with_registry(|registry| registry.ids().into_iter().map(String::from).collect())
+}
 
+/*
+The month names of a calendar in a locale, in the order of
+GenericDate::month_name. With the locale "en" these are the names js_api
+takes. Empty for an unknown calendar
+ */
+#[wasm_bindgen]
+pub fn js_month_names(calendar: &str, locale: &str) -> Vec<String> {
+    with_registry(|registry| registry.get(calendar).map(|c| c.localized_month_names(locale)).unwrap_or_default())
+}
+
+// the eras of a calendar in a locale, first era first
+#[wasm_bindgen]
+pub fn js_era_names(calendar: &str, locale: &str) -> Vec<String> {
+    with_registry(|registry| registry.get(calendar).map(|c| c.localized_era_names(locale)).unwrap_or_default())
+}
+
+// the weekdays in a locale, starting with Sunday
+#[wasm_bindgen]
+pub fn js_weekday_names(locale: &str) -> Vec<String> {
+    (0..7).map(|n| String::from(locale::weekday_name(cal::Weekday::from_number(n), locale))).collect()
+}
 
 /*fn js_api_helper<T1: Calendar + std::fmt::Display, T2: Calendar + std::fmt::Display>(day: u128, month: String, year: i128, era: String) -> String{
 
@@ -0,0 +1,320 @@
+use crate::babylonian::BABYLONIAN_MONTH_NAMES;
+use crate::cal::Weekday;
+use crate::julian_gregorian::{Era, ROMAN_MONTH_NAMES};
+use crate::mars::DARIAN_MONTH_NAMES;
+use crate::sidereal_solar::{BENGALI_MONTH_NAMES, NEPALI_MONTH_NAMES, TAMIL_MONTH_NAMES};
+
+/*
+Month, weekday and era names in other languages.
+
+A locale is a language tag such as "he", "fr" or "fr-CA". Names are looked
+up along its fallback chain: the whole tag, then the tag with its last
+subtag removed, until only the language is left, and finally English. So
+"fr-CA" uses the French names, and calendars without French month names
+(e.g. Tibetan) use the English ones.
+
+Month names are in the order of GenericDate::month_name, so they can be
+indexed with month_name - 1. The English names are the ones Calendar::new
+accepts
+ */
+pub const LOCALES: [&str; 9] = ["en", "he", "ar", "fa", "ru", "fr", "de", "es", "am"];
+
+struct Names {
+    locale: &'static str,
+    // month names by table, see month_table
+    months: &'static [(&'static str, &'static [&'static str])],
+    weekdays: [&'static str; 7],
+    weekday_abbreviations: [&'static str; 7],
+    // BC, AD
+    eras: [&'static str; 2],
+    // whether %b may cut month names to three letters
+    truncates_months: bool,
+}
+
+const ENGLISH_HEBREW_MONTHS: [&str; 14] = [
+    "Tishrei", "Cheshvan", "Kislev", "Tevet", "Shevat", "Adar", "Nisan", "Iyar", "Sivan", "Tammuz",
+    "Av", "Elul", "Adar I", "Adar II",
+];
+
+const ENGLISH_BABYLONIAN_MONTHS: [&str; 14] = [
+    BABYLONIAN_MONTH_NAMES[0], BABYLONIAN_MONTH_NAMES[1], BABYLONIAN_MONTH_NAMES[2],
+    BABYLONIAN_MONTH_NAMES[3], BABYLONIAN_MONTH_NAMES[4], BABYLONIAN_MONTH_NAMES[5],
+    BABYLONIAN_MONTH_NAMES[6], BABYLONIAN_MONTH_NAMES[7], BABYLONIAN_MONTH_NAMES[8],
+    BABYLONIAN_MONTH_NAMES[9], BABYLONIAN_MONTH_NAMES[10], BABYLONIAN_MONTH_NAMES[11],
+    "Ululu II", "Addaru II",
+];
+
+const ENGLISH_TIBETAN_MONTHS: [&str; 24] = [
+    "Month 1", "Month 2", "Month 3", "Month 4", "Month 5", "Month 6", "Month 7", "Month 8",
+    "Month 9", "Month 10", "Month 11", "Month 12", "Leap Month 1", "Leap Month 2",
+    "Leap Month 3", "Leap Month 4", "Leap Month 5", "Leap Month 6", "Leap Month 7",
+    "Leap Month 8", "Leap Month 9", "Leap Month 10", "Leap Month 11", "Leap Month 12",
+];
+
+static NAMES: [Names; 9] = [
+    Names {
+        locale: "en",
+        months: &[
+            ("roman", &ROMAN_MONTH_NAMES),
+            ("hebrew", &ENGLISH_HEBREW_MONTHS),
+            ("babylonian", &ENGLISH_BABYLONIAN_MONTHS),
+            ("tibetan", &ENGLISH_TIBETAN_MONTHS),
+            ("nepali", &NEPALI_MONTH_NAMES),
+            ("bengali", &BENGALI_MONTH_NAMES),
+            ("tamil", &TAMIL_MONTH_NAMES),
+            ("darian", &DARIAN_MONTH_NAMES),
+        ],
+        weekdays: ["Sunday", "Monday", "Tuesday", "Wednesday", "Thursday", "Friday", "Saturday"],
+        weekday_abbreviations: ["Sun", "Mon", "Tue", "Wed", "Thu", "Fri", "Sat"],
+        eras: ["BC", "AD"],
+        truncates_months: true,
+    },
+    Names {
+        locale: "he",
+        months: &[
+            ("roman", &[
+                "ינואר", "פברואר", "מרץ", "אפריל", "מאי", "יוני", "יולי", "אוגוסט", "ספטמבר",
+                "אוקטובר", "נובמבר", "דצמבר",
+            ]),
+            ("hebrew", &[
+                "תשרי", "חשוון", "כסלו", "טבת", "שבט", "אדר", "ניסן", "אייר", "סיוון", "תמוז", "אב",
+                "אלול", "אדר א׳", "אדר ב׳",
+            ]),
+        ],
+        weekdays: ["יום ראשון", "יום שני", "יום שלישי", "יום רביעי", "יום חמישי", "יום שישי", "שבת"],
+        weekday_abbreviations: ["יום א׳", "יום ב׳", "יום ג׳", "יום ד׳", "יום ה׳", "יום ו׳", "שבת"],
+        eras: ["לפנה״ס", "לספירה"],
+        truncates_months: false,
+    },
+    Names {
+        locale: "ar",
+        months: &[
+            ("roman", &[
+                "يناير", "فبراير", "مارس", "أبريل", "مايو", "يونيو", "يوليو", "أغسطس", "سبتمبر",
+                "أكتوبر", "نوفمبر", "ديسمبر",
+            ]),
+            ("hebrew", &[
+                "تشري", "حشفان", "كسلو", "طيفت", "شباط", "آذار", "نيسان", "أيار", "سيفان", "تموز",
+                "آب", "أيلول", "آذار الأول", "آذار الثاني",
+            ]),
+        ],
+        weekdays: ["الأحد", "الاثنين", "الثلاثاء", "الأربعاء", "الخميس", "الجمعة", "السبت"],
+        weekday_abbreviations: ["الأحد", "الاثنين", "الثلاثاء", "الأربعاء", "الخميس", "الجمعة", "السبت"],
+        eras: ["ق.م", "م"],
+        truncates_months: false,
+    },
+    Names {
+        locale: "fa",
+        months: &[
+            ("roman", &[
+                "ژانویه", "فوریه", "مارس", "آوریل", "مه", "ژوئن", "ژوئیه", "اوت", "سپتامبر", "اکتبر",
+                "نوامبر", "دسامبر",
+            ]),
+            ("hebrew", &[
+                "تشری", "حشوان", "کسلو", "طوت", "شباط", "آذار", "نیسان", "ایار", "سیوان", "تموز", "آو",
+                "ایلول", "آذار اول", "آذار دوم",
+            ]),
+        ],
+        weekdays: ["یکشنبه", "دوشنبه", "سه‌شنبه", "چهارشنبه", "پنجشنبه", "جمعه", "شنبه"],
+        weekday_abbreviations: ["یکشنبه", "دوشنبه", "سه‌شنبه", "چهارشنبه", "پنجشنبه", "جمعه", "شنبه"],
+        eras: ["ق.م.", "م."],
+        truncates_months: false,
+    },
+    Names {
+        locale: "ru",
+        months: &[
+            ("roman", &[
+                "январь", "февраль", "март", "апрель", "май", "июнь", "июль", "август", "сентябрь",
+                "октябрь", "ноябрь", "декабрь",
+            ]),
+            ("hebrew", &[
+                "тишрей", "хешван", "кислев", "тевет", "шват", "адар", "нисан", "ияр", "сиван",
+                "таммуз", "ав", "элул", "адар I", "адар II",
+            ]),
+        ],
+        weekdays: ["воскресенье", "понедельник", "вторник", "среда", "четверг", "пятница", "суббота"],
+        weekday_abbreviations: ["вс", "пн", "вт", "ср", "чт", "пт", "сб"],
+        eras: ["до н. э.", "н. э."],
+        truncates_months: true,
+    },
+    Names {
+        locale: "fr",
+        months: &[
+            ("roman", &[
+                "janvier", "février", "mars", "avril", "mai", "juin", "juillet", "août", "septembre",
+                "octobre", "novembre", "décembre",
+            ]),
+            ("hebrew", &[
+                "Tisseri", "Hesvan", "Kislev", "Tébeth", "Schébat", "Adar", "Nissan", "Iyar", "Sivan",
+                "Tamouz", "Ab", "Elloul", "Adar I", "Adar II",
+            ]),
+        ],
+        weekdays: ["dimanche", "lundi", "mardi", "mercredi", "jeudi", "vendredi", "samedi"],
+        weekday_abbreviations: ["dim.", "lun.", "mar.", "mer.", "jeu.", "ven.", "sam."],
+        eras: ["av. J.-C.", "ap. J.-C."],
+        truncates_months: true,
+    },
+    Names {
+        locale: "de",
+        months: &[
+            ("roman", &[
+                "Januar", "Februar", "März", "April", "Mai", "Juni", "Juli", "August", "September",
+                "Oktober", "November", "Dezember",
+            ]),
+            ("hebrew", &[
+                "Tischri", "Cheschwan", "Kislew", "Tevet", "Schevat", "Adar", "Nisan", "Ijjar", "Siwan",
+                "Tammus", "Aw", "Elul", "Adar I", "Adar II",
+            ]),
+        ],
+        weekdays: ["Sonntag", "Montag", "Dienstag", "Mittwoch", "Donnerstag", "Freitag", "Samstag"],
+        weekday_abbreviations: ["So.", "Mo.", "Di.", "Mi.", "Do.", "Fr.", "Sa."],
+        eras: ["v. Chr.", "n. Chr."],
+        truncates_months: true,
+    },
+    Names {
+        locale: "es",
+        months: &[
+            ("roman", &[
+                "enero", "febrero", "marzo", "abril", "mayo", "junio", "julio", "agosto", "septiembre",
+                "octubre", "noviembre", "diciembre",
+            ]),
+            ("hebrew", &[
+                "Tishri", "Heshvan", "Kislev", "Tevet", "Shevat", "Adar", "Nisán", "Iyar", "Siván",
+                "Tamuz", "Av", "Elul", "Adar I", "Adar II",
+            ]),
+        ],
+        weekdays: ["domingo", "lunes", "martes", "miércoles", "jueves", "viernes", "sábado"],
+        weekday_abbreviations: ["dom", "lun", "mar", "mié", "jue", "vie", "sáb"],
+        eras: ["a. C.", "d. C."],
+        truncates_months: true,
+    },
+    Names {
+        locale: "am",
+        months: &[
+            ("roman", &[
+                "ጃንዩወሪ", "ፌብሩወሪ", "ማርች", "ኤፕሪል", "ሜይ", "ጁን", "ጁላይ", "ኦገስት", "ሴፕቴምበር", "ኦክቶበር",
+                "ኖቬምበር", "ዲሴምበር",
+            ]),
+            // the names of the Amharic Bible, where it has them
+            ("hebrew", &[
+                "ቲሽሪ", "ሔሽቫን", "ካሴሉ", "ቴቤት", "ሳባጥ", "አዳር", "ኒሳን", "ኢያር", "ሲዋን", "ታሙዝ", "አቭ",
+                "ኤሉል", "አዳር ፩", "አዳር ፪",
+            ]),
+        ],
+        weekdays: ["እሑድ", "ሰኞ", "ማክሰኞ", "ረቡዕ", "ሐሙስ", "ዓርብ", "ቅዳሜ"],
+        weekday_abbreviations: ["እሑድ", "ሰኞ", "ማክሰ", "ረቡዕ", "ሐሙስ", "ዓርብ", "ቅዳሜ"],
+        eras: ["ዓ/ዓ", "ዓ/ም"],
+        truncates_months: false,
+    },
+];
+
+// "fr-CA" gives ["fr-ca", "fr", "en"]. Underscores are read as hyphens
+pub fn fallback_chain(locale: &str) -> Vec<String> {
+    let mut tag = locale.trim().to_lowercase().replace('_', "-");
+    let mut chain = Vec::new();
+    while !tag.is_empty() {
+        chain.push(tag.clone());
+        match tag.rfind('-') {
+            Some(i) => tag.truncate(i),
+            None => tag.clear(),
+        }
+    }
+    if !chain.iter().any(|t| t == "en") {
+        chain.push(String::from("en"));
+    }
+    chain
+}
+
+// the first names along the chain that `find` accepts. English always has
+// weekdays and eras
+fn lookup<T>(locale: &str, find: impl Fn(&'static Names) -> Option<T>) -> Option<T> {
+    fallback_chain(locale)
+        .iter()
+        .filter_map(|tag| NAMES.iter().find(|names| names.locale == tag))
+        .find_map(find)
+}
+
+// Julian and Gregorian months share their names
+fn month_table(calendar: &str) -> String {
+    match calendar.to_lowercase().as_str() {
+        "julian" | "gregorian" => String::from("roman"),
+        calendar => String::from(calendar),
+    }
+}
+
+/*
+The month names of a calendar, in the order of GenericDate::month_name.
+Empty for calendars without names here, such as ones registered from
+other crates
+ */
+pub fn month_names(calendar: &str, locale: &str) -> Vec<&'static str> {
+    let table = month_table(calendar);
+    lookup(locale, |names| names.months.iter().find(|(t, _)| *t == table).map(|(_, months)| months.to_vec()))
+        .unwrap_or_default()
+}
+
+// the name of the month with the given GenericDate::month_name
+pub fn month_name(calendar: &str, month_name: u8, locale: &str) -> Option<&'static str> {
+    month_names(calendar, locale).get((month_name as usize).wrapping_sub(1)).copied()
+}
+
+// the name in another locale of a month given by its English name
+pub fn translate_month(calendar: &str, name: &str, locale: &str) -> Option<&'static str> {
+    let position = month_names(calendar, "en").iter().position(|&n| n == name)?;
+    month_name(calendar, position as u8 + 1, locale)
+}
+
+pub fn weekday_name(weekday: Weekday, locale: &str) -> &'static str {
+    lookup(locale, |names| Some(names.weekdays[weekday.number() as usize])).unwrap_or_else(|| weekday.name())
+}
+
+pub fn weekday_abbreviation(weekday: Weekday, locale: &str) -> &'static str {
+    lookup(locale, |names| Some(names.weekday_abbreviations[weekday.number() as usize]))
+        .unwrap_or_else(|| &weekday.name()[..3])
+}
+
+pub fn era_name(era: Era, locale: &str) -> &'static str {
+    let index = match era {
+        Era::BC => 0,
+        Era::AD => 1,
+    };
+    lookup(locale, |names| Some(names.eras[index])).unwrap_or_default()
+}
+
+// the eras of a calendar, first era first. Empty for calendars without eras
+pub fn era_names(calendar: &str, locale: &str) -> Vec<&'static str> {
+    match calendar.to_lowercase().as_str() {
+        "julian" | "gregorian" | "babylonian" => vec![era_name(Era::BC, locale), era_name(Era::AD, locale)],
+        _ => Vec::new(),
+    }
+}
+
+// whether month names in the locale can be abbreviated to three letters
+pub fn truncates_months(locale: &str) -> bool {
+    lookup(locale, |names| Some(names.truncates_months)).unwrap_or(true)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn every_locale_names_the_months() {
+        for names in NAMES.iter() {
+            for (table, count) in [("roman", 12), ("hebrew", 14)].iter() {
+                let months = names.months.iter().find(|(t, _)| t == table).map(|(_, months)| months.len());
+                assert_eq!(months, Some(*count), "{} {}", names.locale, table);
+            }
+        }
+    }
+
+    #[test]
+    fn hebrew_months() {
+        assert_eq!(translate_month("hebrew", "Nisan", "fa-IR"), Some("نیسان"));
+        assert_eq!(translate_month("hebrew", "Adar II", "fa"), Some("آذار دوم"));
+        assert_eq!(translate_month("hebrew", "Nisan", "am"), Some("ኒሳን"));
+        assert_eq!(month_name("hebrew", 13, "am-ET"), Some("አዳር ፩"));
+        // Tibetan months are only named in English
+        assert_eq!(month_name("tibetan", 1, "fa"), Some("Month 1"));
+    }
+}
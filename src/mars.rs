@@ -0,0 +1,386 @@
+use crate::invalid_year_err::CalendarError;
+use crate::cal::{supported, Calendar};
+use crate::julian_day::JulianDayNumber;
+use crate::arithmetic::{month_days_by_julian_day, CalendarArithmetic};
+use crate::format::DateFormat;
+use crate::astronomy;
+use crate::GenericDate;
+use std::convert::TryInto;
+use std::ops::RangeInclusive;
+
+/*
+Mars Sol Date, Coordinated Mars Time and the Darian calendar.
+
+Mars Sol Date (MSD) counts sols (mean solar days on Mars, about 1.0275
+earth days) from 29 December 1873, using the formula of Allison and McEwen.
+Coordinated Mars Time (MTC) is the mean solar time at the Mars prime
+meridian, the fractional part of the MSD
+ */
+
+// julian date (TT) at which MSD 0 began, and the length of a sol in days
+const MSD_EPOCH: f64 = 2405522.0028779;
+const SOL_LENGTH: f64 = 1.0274912517;
+
+// sols between the start of Darian year 0 (the northern spring equinox of
+// 1609) and MSD 0
+const DARIAN_EPOCH_OFFSET: i128 = 94129;
+
+const MAX_DARIAN_YEAR: u32 = 9999;
+
+// sols in a 500 year cycle of the Darian calendar
+const DARIAN_CYCLE_SOLS: i128 = 500 * 668 + 296;
+
+pub const DARIAN_MONTH_NAMES: [&str; 24] = [
+    "Sagittarius",
+    "Dhanus",
+    "Capricornus",
+    "Makara",
+    "Aquarius",
+    "Kumbha",
+    "Pisces",
+    "Mina",
+    "Aries",
+    "Mesha",
+    "Taurus",
+    "Rishabha",
+    "Gemini",
+    "Mithuna",
+    "Cancer",
+    "Karka",
+    "Leo",
+    "Simha",
+    "Virgo",
+    "Kanya",
+    "Libra",
+    "Tula",
+    "Scorpius",
+    "Vrishika",
+];
+
+/*
+Mars Sol Date of an instant. day is the earth date and day_fraction is the
+time since midnight UT as a fraction of a day (0.5 is noon)
+ */
+pub fn mars_sol_date(day: JulianDayNumber, day_fraction: f64) -> f64 {
+    let ut = astronomy::day_start(day.to_legacy()) + day_fraction;
+    let tt = ut + astronomy::delta_t(ut);
+    (tt - MSD_EPOCH) / SOL_LENGTH
+}
+
+/*
+Mean solar time at the Mars prime meridian, in the same 24 hour format
+used on earth (each hour is a 24th of a sol)
+ */
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub struct MarsTime {
+    pub hours: u8,
+    pub minutes: u8,
+    pub seconds: u8,
+}
+
+pub fn coordinated_mars_time(day: JulianDayNumber, day_fraction: f64) -> MarsTime {
+    let msd = mars_sol_date(day, day_fraction);
+    let seconds = ((msd - msd.floor()) * 86400.0).floor() as u32;
+    MarsTime {
+        hours: (seconds / 3600) as u8,
+        minutes: (seconds / 60 % 60) as u8,
+        seconds: (seconds % 60) as u8,
+    }
+}
+
+impl std::fmt::Display for MarsTime {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(f, "{:02}:{:02}:{:02} MTC", self.hours, self.minutes, self.seconds)
+    }
+}
+
+/*
+Date on the Darian calendar for Mars.
+
+year: years since the telescopic epoch (1609). Each year has 668 sols, or
+669 when the year is odd or divisible by 10, except years divisible by 100
+but not 500
+
+month: 1 (Sagittarius) to 24 (Vrishika). Months have 28 sols, except that
+the last month of each quarter has 27. The last month of a 669 sol year
+has 28
+
+sol: sol of the month, starting at 1
+
+Sols do not line up with earth days, so a julian day is converted using
+the sol in progress at noon UT at the Mars prime meridian. Converting a
+Darian date gives the first day whose noon falls within that sol
+ */
+#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct DarianDate {
+    pub year: u32,
+    pub month: u8,
+    pub sol: u8,
+}
+
+pub fn is_darian_leap_year(year: u32) -> bool {
+    (year % 2 == 1) | ((year % 10 == 0) & ((year % 100 != 0) | (year % 500 == 0)))
+}
+
+fn darian_year_length(year: u32) -> i128 {
+    match is_darian_leap_year(year) {
+        true => 669,
+        false => 668,
+    }
+}
+
+pub fn darian_month_length(year: u32, month: u8) -> u8 {
+    match month {
+        24 if is_darian_leap_year(year) => 28,
+        6 | 12 | 18 | 24 => 27,
+        _ => 28,
+    }
+}
+
+// sols between the start of year 0 and the start of `year`
+fn darian_year_start(year: u32) -> i128 {
+    let cycles = (year / 500) as i128;
+    let mut sols = cycles * DARIAN_CYCLE_SOLS;
+    for y in (year - year % 500)..year {
+        sols += darian_year_length(y);
+    }
+    sols
+}
+
+impl DarianDate {
+    // sols since the start of Darian year 0
+    fn sol_count(&self) -> Result<i128, CalendarError> {
+        if !(1..=24).contains(&self.month) {
+            return Err(CalendarError::UnknownMonth(self.month.to_string()));
+        }
+        let max = darian_month_length(self.year, self.month);
+        if (self.sol < 1) | (self.sol > max) {
+            return Err(CalendarError::DayOutOfRange { day: self.sol as u32, max: max as u32 });
+        }
+        if self.year > MAX_DARIAN_YEAR {
+            return Err(CalendarError::Overflow);
+        }
+
+        let month_start: i128 = (1..self.month).map(|m| darian_month_length(self.year, m) as i128).sum();
+        Ok(darian_year_start(self.year) + month_start + self.sol as i128 - 1)
+    }
+
+    fn from_sol_count(count: i128) -> Result<DarianDate, CalendarError> {
+        if count < 0 {
+            return Err(CalendarError::Overflow);
+        }
+
+        let mut year = (count / DARIAN_CYCLE_SOLS * 500) as u32;
+        let mut remaining = count % DARIAN_CYCLE_SOLS;
+        while remaining >= darian_year_length(year) {
+            remaining -= darian_year_length(year);
+            year += 1;
+        }
+        if year > MAX_DARIAN_YEAR {
+            return Err(CalendarError::Overflow);
+        }
+
+        let mut month = 1;
+        while remaining >= darian_month_length(year, month) as i128 {
+            remaining -= darian_month_length(year, month) as i128;
+            month += 1;
+        }
+
+        Ok(DarianDate {
+            year,
+            month,
+            sol: (remaining + 1) as u8,
+        })
+    }
+
+    pub fn month_name(&self) -> &'static str {
+        DARIAN_MONTH_NAMES.get((self.month as usize).wrapping_sub(1)).unwrap_or(&"")
+    }
+}
+
+impl Calendar for DarianDate {
+    fn to_julian_day_number(&self) -> Result<JulianDayNumber, CalendarError> {
+        let msd = (self.sol_count()? - DARIAN_EPOCH_OFFSET) as f64;
+        let tt = msd * SOL_LENGTH + MSD_EPOCH;
+        let ut = tt - astronomy::delta_t(tt);
+        // the first noon UT at or after the start of the sol
+        supported::<Self>(JulianDayNumber::new(ut.ceil() as i64))
+    }
+
+    fn from_julian_day_number(day: JulianDayNumber) -> Result<Self, CalendarError> where Self: Sized {
+        let msd = mars_sol_date(supported::<Self>(day)?, 0.5).floor() as i128;
+        DarianDate::from_sol_count(msd + DARIAN_EPOCH_OFFSET)
+    }
+
+    // the start of year 0 to the end of year 9999
+    fn supported_range() -> RangeInclusive<JulianDayNumber> where Self: Sized {
+        JulianDayNumber::new(2308806)..=JulianDayNumber::new(9178516)
+    }
+
+    // month is either a month name ("Sagittarius") or number ("1")
+    fn new(day: u32, month: String, year: i32, _era: String) -> Result<Self, CalendarError> where Self: Sized {
+        // months have at most 28 sols
+        let sol: u8 = match day.try_into() {
+            Ok(n) => n,
+            Err(_) => return Err(CalendarError::DayOutOfRange { day, max: 28 }),
+        };
+        let year: u32 = match year.try_into() {
+            Ok(n) => n,
+            Err(_) => return Err(CalendarError::InvalidInput),
+        };
+        let month = match DARIAN_MONTH_NAMES.iter().position(|&n| n == month) {
+            Some(i) => i as u8 + 1,
+            None => match month.parse() {
+                Ok(n) => n,
+                Err(_) => return Err(CalendarError::UnknownMonth(month)),
+            },
+        };
+        Ok(DarianDate { year, month, sol })
+    }
+}
+
+impl CalendarArithmetic for DarianDate {
+    fn fields(&self) -> (i64, u8, u8) {
+        (self.year as i64, self.month, self.sol)
+    }
+
+    fn from_fields(year: i64, month: u8, day: u8) -> Result<Self, CalendarError> {
+        match year.try_into() {
+            Ok(year) => Ok(DarianDate { year, month, sol: day }),
+            Err(_) => Err(CalendarError::Overflow),
+        }
+    }
+
+    // a sol is longer than a day, so the same sol is sometimes given for two
+    // days in a row
+    fn month_days(date: &Self, julian_day: JulianDayNumber) -> Result<(JulianDayNumber, Vec<Self>), CalendarError> {
+        month_days_by_julian_day(julian_day, |d: &DarianDate| (d.year, d.month) == (date.year, date.month))
+    }
+
+    fn is_leap_year(&self) -> bool {
+        is_darian_leap_year(self.year)
+    }
+
+    fn month_count(_year: i64) -> Result<u8, CalendarError> {
+        Ok(24)
+    }
+
+    const MONTH_CYCLE: Option<(i64, i64)> = Some((1, 24));
+
+    fn month_length(year: i64, month: u8) -> Result<u8, CalendarError> {
+        match year.try_into() {
+            Ok(year) => Ok(darian_month_length(year, month)),
+            Err(_) => Err(CalendarError::Overflow),
+        }
+    }
+
+    // the following count sols rather than earth days
+
+    fn days_in_month(&self) -> Result<u8, CalendarError> {
+        Ok(darian_month_length(self.year, self.month))
+    }
+
+    fn days_in_year(&self) -> Result<u16, CalendarError> {
+        Ok(darian_year_length(self.year) as u16)
+    }
+
+    fn day_of_year(&self) -> Result<u16, CalendarError> {
+        Ok((self.sol_count()? - darian_year_start(self.year) + 1) as u16)
+    }
+
+    // the week starts again with Sol Solis at the start of every month, so
+    // each month has four weeks, the last of them sometimes a sol short
+    fn week_of_year(&self) -> Result<u8, CalendarError> {
+        self.sol_count()?;
+        Ok((self.month - 1) * 4 + (self.sol - 1) / 7 + 1)
+    }
+}
+
+impl std::fmt::Display for DarianDate {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(f, "year: {}, month: {}, sol: {}", self.year, self.month_name(), self.sol)
+    }
+}
+
+impl DateFormat for DarianDate {
+    const CALENDAR: &'static str = "Darian";
+
+    fn month_name(&self) -> String {
+        String::from(DarianDate::month_name(self))
+    }
+}
+
+impl From<DarianDate> for GenericDate {
+    fn from(date: DarianDate) -> GenericDate {
+        GenericDate {
+            is_valid: true,
+            is_not_overflow: true,
+            era: false,
+            year: date.year,
+            month_name: date.month,
+            day: date.sol,
+            is_leap_day: false,
+            weekday: 0,
+            error: String::new(),
+            month_text: String::new(),
+            weekday_text: String::new(),
+            era_text: String::new(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // the worked example of Allison and McEwen: 6 January 2000 at 00:00 UT
+    // is MSD 44795.99976, 23:59:39 MTC. They take TT - UTC (64.184 seconds)
+    // where delta_t gives TT - UT1 (63.8), which is a third of a second
+    // earlier and so a second earlier on the clock
+    #[test]
+    fn allison_mcewen_reference() {
+        let day = JulianDayNumber::new(2451550);
+        let msd = mars_sol_date(day, 0.0);
+        assert!((msd - 44795.99976).abs() < 0.00001, "{}", msd);
+        assert_eq!(coordinated_mars_time(day, 0.0).to_string(), "23:59:38 MTC");
+        assert_eq!(coordinated_mars_time(day, 0.5).to_string(), "11:40:23 MTC");
+    }
+
+    #[test]
+    fn darian_leap_years() {
+        assert!(is_darian_leap_year(9));
+        assert!(is_darian_leap_year(10));
+        assert!(!is_darian_leap_year(12));
+        assert!(!is_darian_leap_year(100));
+        assert!(is_darian_leap_year(110));
+        assert!(!is_darian_leap_year(200));
+        assert!(is_darian_leap_year(500));
+        assert!(is_darian_leap_year(1000));
+        assert_eq!(darian_month_length(10, 24), 28);
+        assert_eq!(darian_month_length(100, 24), 27);
+        assert_eq!(darian_year_start(500), DARIAN_CYCLE_SOLS);
+        assert_eq!(darian_year_start(501) - darian_year_start(500), 669);
+    }
+
+    // every sol from the last month of year 214 into year 215 converts to a
+    // day and back, one or two days after the sol before
+    #[test]
+    fn sols_across_a_year_boundary() {
+        let first = DarianDate { year: 214, month: 24, sol: 1 };
+        let start = first.sol_count().unwrap();
+        let mut previous: Option<JulianDayNumber> = None;
+        for count in start..start + 60 {
+            let date = DarianDate::from_sol_count(count).unwrap();
+            let day = date.to_julian_day_number().unwrap();
+            assert_eq!(DarianDate::from_julian_day_number(day), Ok(date));
+            if let Some(previous) = previous {
+                assert!(matches!(day.value() - previous.value(), 1 | 2), "{}", date);
+            }
+            previous = Some(day);
+        }
+        let new_year = DarianDate { year: 215, month: 1, sol: 1 };
+        assert_eq!(DarianDate::from_sol_count(start + 27), Ok(new_year));
+        assert_eq!(new_year.day_of_year(), Ok(1));
+    }
+}
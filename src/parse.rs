@@ -0,0 +1,494 @@
+use std::convert::TryFrom;
+use std::ops::Range;
+use std::str::FromStr;
+
+use crate::any_date::AnyDate;
+use crate::babylonian::{BabylonianDate, BABYLONIAN_MONTH_NAMES};
+use crate::gematria::{from_hebrew_numeral, from_hebrew_year, is_point, GERESH, GERSHAYIM};
+use crate::hebrew::{HebrewDate, HebrewMonth};
+use crate::invalid_year_err::CalendarError;
+use crate::julian_gregorian::{GregorianCalendar, JulianCalendar, RomanMonth};
+use crate::mars::{DarianDate, DARIAN_MONTH_NAMES};
+use crate::roman_numerals::from_roman_numeral;
+use crate::sidereal_solar::{BengaliDate, NepaliDate, TamilDate, BENGALI_MONTH_NAMES, NEPALI_MONTH_NAMES, TAMIL_MONTH_NAMES};
+use crate::tibetan::TibetanDate;
+
+/*
+Reading dates written out as text, such as "15 Nisan 5785",
+"3rd of Adar II, 5784", "September 11, 1752 O.S." or "Month 3 5, 2150".
+
+The text needs a day, a month and a year. The month is written out and
+picks the calendar; the day and year are numbers in either order around it,
+with the day first when both could be either. Ordinals ("3rd") are always
+the day. Commas, "of" and "the" are skipped.
+
+Era markers after the year choose between calendars with the same month
+names and set the era:
+AD, CE, BC, BCE: Gregorian (or Julian or Babylonian if the month says so)
+O.S.: Julian, N.S.: Gregorian
+AM: Hebrew, VS: Nepali, BS: Bengali, Saka: Tamil
+Dates with Roman month names and no O.S. are read as Gregorian, unless
+read as a JulianCalendar with from_str.
+
+Hebrew dates can also be written in Hebrew script, as in "ט״ו בשבט תשפ״ה",
+with the day and year in Hebrew numerals (see the gematria module). Years
+without their thousands are in the sixth millennium, and may be marked
+לפ״ק.
+
+Julian and Gregorian years can be Roman numerals, as in "11 September
+MDCCLII O.S." (see the roman_numerals module). A Roman numeral reads the
+date on those two calendars only.
+
+Islamic month names and the AH marker are recognized only to report that
+the calendar is not supported
+ */
+
+// where in the text parsing failed, as a byte range, and why
+#[derive(Clone, PartialEq, Debug)]
+pub struct ParseError {
+    pub span: Range<usize>,
+    pub error: CalendarError,
+}
+
+impl std::fmt::Display for ParseError {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(f, "{} (at {}..{})", self.error, self.span.start, self.span.end)
+    }
+}
+
+impl std::error::Error for ParseError {}
+
+// a date read by parse_any, or every date the text could be when it could
+// be read on more than one calendar
+#[derive(Clone, PartialEq, Debug)]
+pub enum Parsed {
+    Date(AnyDate),
+    Ambiguous(Vec<AnyDate>),
+}
+
+const ISLAMIC_MONTH_NAMES: [&str; 12] = [
+    "muharram", "safar", "rabi al-awwal", "rabi al-thani", "jumada al-awwal", "jumada al-thani",
+    "rajab", "shaban", "ramadan", "shawwal", "dhu al-qadah", "dhu al-hijjah",
+];
+
+// words that can be skipped
+const FILLER: [&str; 2] = ["of", "the"];
+
+#[derive(Clone, Debug)]
+enum TokenKind {
+    // the day or year, and whether it was written as an ordinal
+    Number(u32, bool),
+    // a Hebrew numeral, and its value as a year with any thousands added
+    HebrewNumber(u32, u32),
+    RomanNumber(u32),
+    Word,
+}
+
+#[derive(Clone, Debug)]
+struct Token<'a> {
+    text: &'a str,
+    span: Range<usize>,
+    kind: TokenKind,
+}
+
+fn tokenize(input: &str) -> Vec<Token<'_>> {
+    let mut tokens = Vec::new();
+    let mut start = None;
+    for (i, c) in input.char_indices().chain(std::iter::once((input.len(), ' '))) {
+        // a gershayim is often typed as ", which only joins Hebrew words
+        let hebrew = start.is_some_and(|s| input[s..i].chars().any(is_hebrew_letter));
+        let in_word = c.is_alphanumeric()
+            | matches!(c, '.' | '\'' | '-' | GERESH | GERSHAYIM)
+            | is_point(c)
+            | (hebrew & (c == '"'));
+        match (start, in_word) {
+            (None, true) => start = Some(i),
+            (Some(s), false) => {
+                // a trailing full stop ends the sentence, except in markers like "O.S."
+                let mut text = &input[s..i];
+                if text.ends_with('.') && !text[..text.len() - 1].contains('.') {
+                    text = &text[..text.len() - 1];
+                }
+                if !text.is_empty() {
+                    tokens.push(Token { text, span: s..s + text.len(), kind: token_kind(text) });
+                }
+                start = None;
+            }
+            _ => {}
+        }
+    }
+    tokens
+}
+
+fn is_hebrew_letter(c: char) -> bool {
+    ('\u{05D0}'..='\u{05EA}').contains(&c)
+}
+
+fn token_kind(text: &str) -> TokenKind {
+    // Hebrew numerals are punctuated, which tells them from words
+    if text.chars().any(is_hebrew_letter) && text.contains(|c| matches!(c, GERESH | GERSHAYIM | '"' | '\'')) && era_marker(text).is_none() {
+        if let (Ok(n), Ok(year)) = (from_hebrew_numeral(text), from_hebrew_year(text)) {
+            return TokenKind::HebrewNumber(n, year);
+        }
+    }
+    let digits = text.find(|c: char| !c.is_ascii_digit()).unwrap_or(text.len());
+    let suffix = text[digits..].to_lowercase();
+    match text[..digits].parse() {
+        Ok(n) if suffix.is_empty() => TokenKind::Number(n, false),
+        Ok(n) if ["st", "nd", "rd", "th"].contains(&suffix.as_str()) => TokenKind::Number(n, true),
+        _ => match from_roman_numeral(text) {
+            Ok(n) if era_marker(text).is_none() => TokenKind::RomanNumber(n),
+            _ => TokenKind::Word,
+        },
+    }
+}
+
+fn normalize(text: &str) -> String {
+    text.chars().filter(|&c| !matches!(c, '.' | GERESH | GERSHAYIM | '"')).collect::<String>().to_lowercase()
+}
+
+struct Marker {
+    calendars: &'static [&'static str],
+    era: &'static str,
+}
+
+fn era_marker(word: &str) -> Option<Marker> {
+    const ERA_CALENDARS: &[&str] = &["Gregorian", "Julian", "Babylonian"];
+    match normalize(word).as_str() {
+        "ad" | "ce" => Some(Marker { calendars: ERA_CALENDARS, era: "AD" }),
+        "bc" | "bce" => Some(Marker { calendars: ERA_CALENDARS, era: "BC" }),
+        "os" => Some(Marker { calendars: &["Julian"], era: "AD" }),
+        "ns" => Some(Marker { calendars: &["Gregorian"], era: "AD" }),
+        // לפ״ק: "by the small count", without the thousands
+        "am" | "לפק" => Some(Marker { calendars: &["Hebrew"], era: "" }),
+        "vs" => Some(Marker { calendars: &["Nepali"], era: "" }),
+        "bs" => Some(Marker { calendars: &["Bengali"], era: "" }),
+        "saka" => Some(Marker { calendars: &["Tamil"], era: "" }),
+        "ah" => Some(Marker { calendars: &["Islamic"], era: "" }),
+        _ => None,
+    }
+}
+
+// the calendars with a month of this name, and the name as their `new` takes it
+fn month_candidates(phrase: &str) -> Vec<(&'static str, String)> {
+    let lower = phrase.to_lowercase();
+    let mut candidates = Vec::new();
+    let named = |names: &[&str]| names.iter().find(|n| n.to_lowercase() == lower).map(|n| String::from(*n));
+
+    if ISLAMIC_MONTH_NAMES.iter().any(|n| n.replace(' ', "") == lower.replace(' ', "")) {
+        candidates.push(("Islamic", String::from(phrase)));
+    }
+    if HebrewMonth::try_from(phrase).is_ok() {
+        candidates.push(("Hebrew", String::from(phrase)));
+    }
+    if lower.parse::<u8>().is_err() && RomanMonth::try_from(phrase).is_ok() {
+        candidates.push(("Gregorian", String::from(phrase)));
+        candidates.push(("Julian", String::from(phrase)));
+    }
+    let babylonian: Vec<&str> = BABYLONIAN_MONTH_NAMES.iter().copied().chain(["Ululu II", "Addaru II"].iter().copied()).collect();
+    for (calendar, names) in [
+        ("Babylonian", babylonian.as_slice()),
+        ("Nepali", &NEPALI_MONTH_NAMES[..]),
+        ("Bengali", &BENGALI_MONTH_NAMES[..]),
+        ("Tamil", &TAMIL_MONTH_NAMES[..]),
+        ("Darian", &DARIAN_MONTH_NAMES[..]),
+    ]
+    .iter()
+    {
+        if let Some(name) = named(names) {
+            candidates.push((*calendar, name));
+        }
+    }
+
+    // "Month 3" or "Leap Month 3"
+    let (leap, rest) = match lower.strip_prefix("leap ") {
+        Some(rest) => (true, rest),
+        None => (false, lower.as_str()),
+    };
+    if let Some(Ok(number)) = rest.strip_prefix("month ").map(|n| n.parse::<u8>()) {
+        if (1..=12).contains(&number) {
+            let prefix = if leap { "Leap " } else { "" };
+            candidates.push(("Tibetan", format!("{}Month {}", prefix, number)));
+        }
+    }
+    candidates
+}
+
+struct Fields {
+    // (calendar, month name) pairs
+    months: Vec<(&'static str, String)>,
+    month_span: Range<usize>,
+    day: u32,
+    day_span: Range<usize>,
+    year: i32,
+    year_span: Range<usize>,
+    marker: Option<(Marker, Range<usize>)>,
+    // the first Roman numeral
+    roman: Option<Range<usize>>,
+}
+
+fn error(span: Range<usize>, error: CalendarError) -> ParseError {
+    ParseError { span, error }
+}
+
+fn read_fields(input: &str) -> Result<Fields, ParseError> {
+    let tokens: Vec<Token> = tokenize(input)
+        .into_iter()
+        .filter(|t| !FILLER.contains(&t.text.to_lowercase().as_str()))
+        .collect();
+    let whole = 0..input.len();
+
+    // the month is the longest run of up to three tokens that names one
+    let mut month = None;
+    'search: for start in 0..tokens.len() {
+        for length in (1..=3).rev().filter(|l| start + l <= tokens.len()) {
+            let run = &tokens[start..start + length];
+            let phrase = run.iter().map(|t| t.text).collect::<Vec<_>>().join(" ");
+            let candidates = month_candidates(&phrase);
+            if !candidates.is_empty() {
+                month = Some((start..start + length, candidates));
+                break 'search;
+            }
+        }
+    }
+    let (month_tokens, months) = match month {
+        Some(month) => month,
+        None => {
+            let word = tokens.iter().find(|t| matches!(t.kind, TokenKind::Word) && era_marker(t.text).is_none());
+            return Err(match word {
+                Some(t) => error(t.span.clone(), CalendarError::UnknownMonth(String::from(t.text))),
+                None => error(whole, CalendarError::InvalidInput),
+            });
+        }
+    };
+    let month_span = tokens[month_tokens.start].span.start..tokens[month_tokens.end - 1].span.end;
+
+    let mut numbers = Vec::new();
+    let mut marker = None;
+    let mut roman = None;
+    for (_, token) in tokens.iter().enumerate().filter(|(i, _)| !month_tokens.contains(i)) {
+        match (&token.kind, era_marker(token.text)) {
+            (TokenKind::Number(n, ordinal), _) => numbers.push((*n, *ordinal, token.span.clone(), *n)),
+            (TokenKind::HebrewNumber(n, year), _) => numbers.push((*n, false, token.span.clone(), *year)),
+            (TokenKind::RomanNumber(n), _) => {
+                roman.get_or_insert(token.span.clone());
+                numbers.push((*n, false, token.span.clone(), *n));
+            }
+            (TokenKind::Word, Some(m)) if marker.is_none() => marker = Some((m, token.span.clone())),
+            _ => return Err(error(token.span.clone(), CalendarError::InvalidInput)),
+        }
+    }
+    let ((day, _, day_span, _), (_, _, year_span, year)) = match numbers.as_slice() {
+        [first, second] => {
+            // the day is the ordinal, or the number that can be a day
+            let year_first = (!first.1 & second.1) | (!first.1 & !second.1 & (first.0 > 32) & (second.0 <= 32));
+            match year_first {
+                true => (second.clone(), first.clone()),
+                false => (first.clone(), second.clone()),
+            }
+        }
+        [] | [_] => return Err(error(whole, CalendarError::InvalidInput)),
+        [_, _, extra, ..] => return Err(error(extra.2.clone(), CalendarError::InvalidInput)),
+    };
+    let year = match i32::try_from(year) {
+        Ok(year) => year,
+        Err(_) => return Err(error(year_span, CalendarError::Overflow)),
+    };
+
+    Ok(Fields { months, month_span, day, day_span, year, year_span, marker, roman })
+}
+
+// the part of the text a conversion error is about
+fn error_span(fields: &Fields, e: &CalendarError) -> Range<usize> {
+    match e {
+        CalendarError::DayOutOfRange { .. } => fields.day_span.clone(),
+        CalendarError::UnknownMonth(_) | CalendarError::MonthNotInYear { .. } => fields.month_span.clone(),
+        CalendarError::UnknownEra(_) | CalendarError::UnknownCalendar(_) => match &fields.marker {
+            Some((_, span)) => span.clone(),
+            None => fields.month_span.clone(),
+        },
+        _ => fields.year_span.clone(),
+    }
+}
+
+// every calendar the text can be read on, or `only` if given
+fn parse_dates(input: &str, only: Option<&str>) -> Result<Vec<AnyDate>, ParseError> {
+    let fields = read_fields(input)?;
+    let mut months = fields.months.clone();
+    if let Some((marker, span)) = &fields.marker {
+        months.retain(|(calendar, _)| marker.calendars.contains(calendar));
+        if months.is_empty() {
+            return Err(error(span.clone(), CalendarError::UnknownEra(String::from(&input[span.clone()]))));
+        }
+    }
+    if let Some(span) = &fields.roman {
+        months.retain(|(calendar, _)| ["Gregorian", "Julian"].contains(calendar));
+        if months.is_empty() {
+            return Err(error(span.clone(), CalendarError::InvalidInput));
+        }
+    }
+    match only {
+        Some(only) => {
+            months.retain(|(calendar, _)| calendar.eq_ignore_ascii_case(only));
+            if months.is_empty() {
+                return Err(error(fields.month_span.clone(), CalendarError::UnknownMonth(String::from(&input[fields.month_span.clone()]))));
+            }
+        }
+        // Old Style dates are marked
+        None if months.iter().any(|(calendar, _)| *calendar == "Gregorian") => {
+            months.retain(|(calendar, _)| *calendar != "Julian");
+        }
+        None => {}
+    }
+
+    let era = match &fields.marker {
+        Some((marker, _)) => marker.era,
+        None => "AD",
+    };
+    let mut dates = Vec::new();
+    let mut first_error = None;
+    for (calendar, month) in months {
+        let date = AnyDate::new(calendar, fields.day, month, fields.year, String::from(era))
+            .and_then(|date| date.to_julian_day_number().map(|_| date));
+        match date {
+            Ok(date) => dates.push(date),
+            Err(e) => {
+                first_error.get_or_insert(error(error_span(&fields, &e), e));
+            }
+        }
+    }
+    match (dates.is_empty(), first_error) {
+        (true, Some(e)) => Err(e),
+        _ => Ok(dates),
+    }
+}
+
+// reads a date on whichever calendar its month name and era marker point to
+pub fn parse_any(input: &str) -> Result<Parsed, ParseError> {
+    let mut dates = parse_dates(input, None)?;
+    match dates.len() {
+        1 => Ok(Parsed::Date(dates.remove(0))),
+        _ => Ok(Parsed::Ambiguous(dates)),
+    }
+}
+
+macro_rules! from_str_for {
+    ($($variant:ident($date:ty)),*) => {
+        $(
+            impl FromStr for $date {
+                type Err = ParseError;
+
+                fn from_str(input: &str) -> Result<Self, Self::Err> {
+                    match parse_dates(input, Some(stringify!($variant)))?.pop() {
+                        Some(AnyDate::$variant(date)) => Ok(date),
+                        _ => Err(error(0..input.len(), CalendarError::InvalidInput)),
+                    }
+                }
+            }
+        )*
+    };
+}
+
+from_str_for!(
+    Hebrew(HebrewDate),
+    Julian(JulianCalendar),
+    Gregorian(GregorianCalendar),
+    Tibetan(TibetanDate),
+    Babylonian(BabylonianDate),
+    Nepali(NepaliDate),
+    Bengali(BengaliDate),
+    Tamil(TamilDate),
+    Darian(DarianDate)
+);
+
+// the one date the text can be, or an error at the whole text when it is
+// ambiguous
+impl FromStr for AnyDate {
+    type Err = ParseError;
+
+    fn from_str(input: &str) -> Result<Self, Self::Err> {
+        match parse_any(input)? {
+            Parsed::Date(date) => Ok(date),
+            Parsed::Ambiguous(_) => Err(error(0..input.len(), CalendarError::InvalidInput)),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cal::Calendar;
+
+    fn date(calendar: &str, day: u32, month: &str, year: i32, era: &str) -> AnyDate {
+        AnyDate::new(calendar, day, String::from(month), year, String::from(era)).unwrap()
+    }
+
+    fn failure(input: &str) -> (&str, CalendarError) {
+        let e = parse_any(input).unwrap_err();
+        (&input[e.span], e.error)
+    }
+
+    #[test]
+    fn dates() {
+        let cases = [
+            ("15 Nisan 5785", date("hebrew", 15, "Nisan", 5785, "")),
+            ("3rd of Adar II, 5784", date("hebrew", 3, "Adar II", 5784, "")),
+            ("5785 Nisan 15", date("hebrew", 15, "Nisan", 5785, "")),
+            ("15 Nisan 5785 AM", date("hebrew", 15, "Nisan", 5785, "")),
+            ("11 Sept 1752 O.S.", date("julian", 11, "September", 1752, "AD")),
+            ("September 11, 1752 O.S.", date("julian", 11, "September", 1752, "AD")),
+            ("September 11, 1752", date("gregorian", 11, "September", 1752, "AD")),
+            ("11 September MDCCLII O.S.", date("julian", 11, "September", 1752, "AD")),
+            ("1 Addaru II 300 BC", date("babylonian", 1, "Addaru II", 300, "BC")),
+            ("Month 3 5, 2150", date("tibetan", 5, "Month 3", 2150, "")),
+            ("1 Kartik 2080 VS", date("nepali", 1, "Kartik", 2080, "")),
+            ("1 Magh 1430 BS", date("bengali", 1, "Magh", 1430, "")),
+            ("1 Sagittarius 200", date("darian", 1, "Sagittarius", 200, "")),
+        ];
+        for (input, expected) in cases.iter() {
+            assert_eq!(parse_any(input), Ok(Parsed::Date(*expected)), "{}", input);
+        }
+    }
+
+    #[test]
+    fn hebrew_script() {
+        let expected = Parsed::Date(date("hebrew", 15, "Shevat", 5785, ""));
+        assert_eq!(parse_any("ט״ו בשבט תשפ״ה"), Ok(expected.clone()));
+        // a gershayim typed as a quotation mark
+        assert_eq!(parse_any("ט\"ו בשבט תשפ\"ה"), Ok(expected.clone()));
+        assert_eq!(parse_any("ט״ו בשבט תשפ״ה לפ״ק"), Ok(expected));
+    }
+
+    #[test]
+    fn ambiguous() {
+        assert_eq!(
+            parse_any("1 Magh 1430"),
+            Ok(Parsed::Ambiguous(vec![date("nepali", 1, "Magh", 1430, ""), date("bengali", 1, "Magh", 1430, "")]))
+        );
+        assert!("1 Magh 1430".parse::<AnyDate>().is_err());
+        assert_eq!("1 Magh 1430".parse::<BengaliDate>(), BengaliDate::new(1, String::from("Magh"), 1430, String::new()).map_err(|e| error(0..0, e)));
+    }
+
+    #[test]
+    fn error_spans() {
+        assert_eq!(failure("32 Nisan 5785"), ("32", CalendarError::DayOutOfRange { day: 32, max: 30 }));
+        assert_eq!(failure("30 Adar 5785"), ("30", CalendarError::DayOutOfRange { day: 30, max: 29 }));
+        assert_eq!(failure("15 Nisanx 5785"), ("Nisanx", CalendarError::UnknownMonth(String::from("Nisanx"))));
+        assert_eq!(
+            failure("1 Adar I 5785"),
+            ("Adar I", CalendarError::MonthNotInYear { month: String::from("Adar I"), year: 5785 })
+        );
+        assert_eq!(failure("15 Nisan 5785 BC"), ("BC", CalendarError::UnknownEra(String::from("BC"))));
+        assert_eq!(failure("15 Nisan 5785 extra 3"), ("extra", CalendarError::InvalidInput));
+        assert_eq!(failure("5 Nisan"), ("5 Nisan", CalendarError::InvalidInput));
+        assert_eq!(failure("1 Muharram 1446 AH"), ("AH", CalendarError::UnknownCalendar(String::from("Islamic"))));
+    }
+
+    #[test]
+    fn from_str() {
+        assert_eq!("11 September 1752".parse::<JulianCalendar>(), Ok(JulianCalendar::new(11, String::from("September"), 1752, String::from("AD")).unwrap()));
+        assert_eq!("15 Nisan 5785".parse::<AnyDate>(), Ok(date("hebrew", 15, "Nisan", 5785, "")));
+        let e = "1 Nisannu 5785".parse::<HebrewDate>().unwrap_err();
+        assert_eq!((e.span, e.error), (2..9, CalendarError::UnknownMonth(String::from("Nisannu"))));
+    }
+}
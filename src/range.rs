@@ -0,0 +1,111 @@
+use crate::arithmetic::CalendarArithmetic;
+use crate::invalid_year_err::CalendarError;
+use crate::julian_day::JulianDayNumber;
+
+/*
+Iterator over days on one calendar.
+
+days_between gives every day from the start date up to, but not including,
+the end date, so it yields arithmetic::days_between(start, end) dates. The
+month_iter and year_iter methods of CalendarArithmetic give every day of a
+date's month or year. step_by_days keeps only every few days, starting with
+the first.
+
+The iterator does not convert each day from its julian day. Whenever it
+enters a month it converts that day once and builds the rest of the month
+with CalendarArithmetic::month_days, so iterating over a Hebrew year runs
+the molad calculation about once a month instead of once a day.
+
+Iteration stops early if a day cannot be converted, which only happens for
+dates outside the range the calendar supports
+ */
+#[derive(Clone, Debug)]
+pub struct DateRange<T> {
+    next: JulianDayNumber,
+    end: JulianDayNumber,
+    step: i64,
+    // days of the month starting on month_start
+    month_start: JulianDayNumber,
+    month: Vec<T>,
+}
+
+impl<T: CalendarArithmetic + Clone> DateRange<T> {
+    pub fn days_between(start: &T, end: &T) -> Result<DateRange<T>, CalendarError> {
+        Ok(DateRange::from_julian_days(start.to_julian_day_number()?, end.to_julian_day_number()?))
+    }
+
+    // the days from `start` up to, but not including, `end`
+    pub fn from_julian_days(start: JulianDayNumber, end: JulianDayNumber) -> DateRange<T> {
+        DateRange {
+            next: start,
+            end,
+            step: 1,
+            month_start: start,
+            month: Vec::new(),
+        }
+    }
+
+    // a month already built by month_days, which starts on `start`
+    pub(crate) fn from_month(start: JulianDayNumber, month: Vec<T>) -> Result<DateRange<T>, CalendarError> {
+        Ok(DateRange {
+            next: start,
+            end: start.offset(month.len() as i64)?,
+            step: 1,
+            month_start: start,
+            month,
+        })
+    }
+
+    // every `days` days from the next date. days must be at least 1
+    pub fn step_by_days(mut self, days: u32) -> Result<DateRange<T>, CalendarError> {
+        if days == 0 {
+            return Err(CalendarError::InvalidInput);
+        }
+        self.step = days as i64;
+        Ok(self)
+    }
+
+    fn load_month(&mut self) -> Result<(), CalendarError> {
+        let date = T::from_julian_day_number(self.next)?;
+        let (start, month) = T::month_days(&date, self.next)?;
+        self.month_start = start;
+        self.month = month;
+        Ok(())
+    }
+}
+
+impl<T: CalendarArithmetic + Clone> Iterator for DateRange<T> {
+    type Item = T;
+
+    fn next(&mut self) -> Option<T> {
+        if self.next >= self.end {
+            return None;
+        }
+
+        let index = self.next.days_since(self.month_start);
+        if ((index < 0) | (index >= self.month.len() as i128)) && self.load_month().is_err() {
+            self.next = self.end;
+            return None;
+        }
+
+        match self.month.get(self.next.days_since(self.month_start) as usize) {
+            Some(date) => {
+                // past the end when the step does not fit
+                self.next = self.next.checked_add(self.step).unwrap_or(self.end);
+                Some(date.clone())
+            }
+            None => {
+                self.next = self.end;
+                None
+            }
+        }
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let remaining = match self.next < self.end {
+            true => ((self.end.days_since(self.next) + self.step as i128 - 1) / self.step as i128) as usize,
+            false => 0,
+        };
+        (0, Some(remaining))
+    }
+}
@@ -0,0 +1,296 @@
+use std::cell::RefCell;
+use std::marker::PhantomData;
+
+use crate::arithmetic::CalendarArithmetic;
+use crate::babylonian::BabylonianDate;
+use crate::cal::Weekday;
+use crate::hebrew::HebrewDate;
+use crate::invalid_year_err::CalendarError;
+use crate::julian_day::JulianDayNumber;
+use crate::julian_gregorian::{GregorianCalendar, JulianCalendar};
+use crate::locale;
+use crate::mars::DarianDate;
+use crate::range::DateRange;
+use crate::sidereal_solar::{BengaliDate, NepaliDate, TamilDate};
+use crate::tibetan::TibetanDate;
+use crate::GenericDate;
+
+/*
+A calendar chosen at run time by its id, such as "Hebrew". js_api converts
+through these so that adding a calendar means registering it instead of
+editing every match on the calendar names.
+
+month_names: the names the calendar accepts for months, in the order of
+GenericDate::month_name, so the name of a converted date is
+month_names()[month_name - 1]
+
+era_names: the eras the calendar accepts, first era first. Empty for
+calendars without eras
+
+localized_month_names, localized_era_names: the same names in a locale,
+for showing to the user. By default the names above
+ */
+pub trait DynCalendar {
+    fn id(&self) -> &str;
+
+    fn display_name(&self) -> &str;
+
+    fn month_names(&self) -> Vec<String>;
+
+    fn era_names(&self) -> Vec<String>;
+
+    fn localized_month_names(&self, _locale: &str) -> Vec<String> {
+        self.month_names()
+    }
+
+    fn localized_era_names(&self, _locale: &str) -> Vec<String> {
+        self.era_names()
+    }
+
+    // the day of a date given by its fields, as taken by Calendar::new
+    fn julian_day_number(&self, day: u32, month: &str, year: i32, era: &str) -> Result<JulianDayNumber, CalendarError>;
+
+    // the date on this calendar of the day
+    fn date(&self, day: JulianDayNumber) -> Result<GenericDate, CalendarError>;
+
+    // every `step`th day of the `days` days starting on `start`
+    fn range(&self, start: JulianDayNumber, days: u32, step: u32) -> Result<Vec<GenericDate>, CalendarError> {
+        check_range(days, step)?;
+        let mut dates = Vec::new();
+        for offset in (0..days as i64).step_by(step as usize) {
+            dates.push(self.date(start.offset(offset)?)?);
+        }
+        Ok(dates)
+    }
+}
+
+// most dates a range can return, about 270 years of single days
+pub const MAX_RANGE_DATES: u32 = 100_000;
+
+// CalendarError::InvalidInput for a zero step or more than MAX_RANGE_DATES dates
+fn check_range(days: u32, step: u32) -> Result<(), CalendarError> {
+    match step {
+        0 => Err(CalendarError::InvalidInput),
+        _ if days as u64 > MAX_RANGE_DATES as u64 * step as u64 => Err(CalendarError::InvalidInput),
+        _ => Ok(()),
+    }
+}
+
+// a DynCalendar for any type that implements Calendar
+pub struct CalendarEntry<T> {
+    id: String,
+    display_name: String,
+    month_names: Vec<String>,
+    era_names: Vec<String>,
+    calendar: PhantomData<T>,
+}
+
+impl<T> CalendarEntry<T> {
+    pub fn new(id: &str, display_name: &str, month_names: &[&str], era_names: &[&str]) -> CalendarEntry<T> {
+        CalendarEntry {
+            id: String::from(id),
+            display_name: String::from(display_name),
+            month_names: month_names.iter().map(|&n| String::from(n)).collect(),
+            era_names: era_names.iter().map(|&n| String::from(n)).collect(),
+            calendar: PhantomData,
+        }
+    }
+}
+
+impl<T> DynCalendar for CalendarEntry<T>
+where
+    T: CalendarArithmetic + Clone + Into<GenericDate>,
+{
+    fn id(&self) -> &str {
+        &self.id
+    }
+
+    fn display_name(&self) -> &str {
+        &self.display_name
+    }
+
+    fn month_names(&self) -> Vec<String> {
+        self.month_names.clone()
+    }
+
+    fn era_names(&self) -> Vec<String> {
+        self.era_names.clone()
+    }
+
+    // from the locale tables when they have the calendar's names
+    fn localized_month_names(&self, locale: &str) -> Vec<String> {
+        localized(locale::month_names(&self.id, locale), &self.month_names)
+    }
+
+    fn localized_era_names(&self, locale: &str) -> Vec<String> {
+        localized(locale::era_names(&self.id, locale), &self.era_names)
+    }
+
+    fn julian_day_number(&self, day: u32, month: &str, year: i32, era: &str) -> Result<JulianDayNumber, CalendarError> {
+        T::new(day, String::from(month), year, String::from(era))?.to_julian_day_number()
+    }
+
+    fn date(&self, day: JulianDayNumber) -> Result<GenericDate, CalendarError> {
+        Ok(T::from_julian_day_number(day)?.into())
+    }
+
+    // converts about once a month instead of once a day
+    fn range(&self, start: JulianDayNumber, days: u32, step: u32) -> Result<Vec<GenericDate>, CalendarError> {
+        check_range(days, step)?;
+        T::from_julian_day_number(start)?;
+        let end = start.offset(days as i64)?;
+        let range = DateRange::<T>::from_julian_days(start, end).step_by_days(step)?;
+        Ok(range.map(|date| date.into()).collect())
+    }
+}
+
+// the names from the locale tables, unless they are not for this calendar
+fn localized(names: Vec<&str>, english: &[String]) -> Vec<String> {
+    match names.len() == english.len() {
+        true => names.into_iter().map(String::from).collect(),
+        false => english.to_vec(),
+    }
+}
+
+/*
+The calendars js_api can convert between, looked up by id ignoring case.
+with_builtin has every calendar in the crate; other crates can add their
+own with register, or register_calendar for the registry js_api uses
+ */
+#[derive(Default)]
+pub struct CalendarRegistry {
+    calendars: Vec<Box<dyn DynCalendar>>,
+}
+
+impl CalendarRegistry {
+    pub fn new() -> CalendarRegistry {
+        CalendarRegistry { calendars: Vec::new() }
+    }
+
+    pub fn with_builtin() -> CalendarRegistry {
+        // the English names from the locale tables
+        fn entry<T>(id: &str, display_name: &str) -> Box<CalendarEntry<T>> {
+            Box::new(CalendarEntry::new(id, display_name, &locale::month_names(id, "en"), &locale::era_names(id, "en")))
+        }
+
+        let mut registry = CalendarRegistry::new();
+        registry.register(entry::<HebrewDate>("Hebrew", "Hebrew"));
+        registry.register(entry::<JulianCalendar>("Julian", "Julian"));
+        registry.register(entry::<GregorianCalendar>("Gregorian", "Gregorian"));
+        registry.register(entry::<TibetanDate>("Tibetan", "Tibetan (Phugpa)"));
+        registry.register(entry::<BabylonianDate>("Babylonian", "Babylonian"));
+        registry.register(entry::<NepaliDate>("Nepali", "Nepali (Vikram Samvat)"));
+        registry.register(entry::<BengaliDate>("Bengali", "Bengali"));
+        registry.register(entry::<TamilDate>("Tamil", "Tamil"));
+        registry.register(entry::<DarianDate>("Darian", "Darian (Mars)"));
+        registry
+    }
+
+    // replaces a calendar with the same id
+    pub fn register(&mut self, calendar: Box<dyn DynCalendar>) {
+        match self.calendars.iter().position(|c| c.id().eq_ignore_ascii_case(calendar.id())) {
+            Some(i) => self.calendars[i] = calendar,
+            None => self.calendars.push(calendar),
+        }
+    }
+
+    pub fn get(&self, id: &str) -> Result<&dyn DynCalendar, CalendarError> {
+        match self.calendars.iter().find(|c| c.id().eq_ignore_ascii_case(id)) {
+            Some(calendar) => Ok(calendar.as_ref()),
+            None => Err(CalendarError::UnknownCalendar(String::from(id))),
+        }
+    }
+
+    pub fn ids(&self) -> Vec<&str> {
+        self.calendars.iter().map(|c| c.id()).collect()
+    }
+
+    pub fn to_julian_day_number(&self, from: &str, day: u32, month: &str, year: i32, era: &str) -> Result<JulianDayNumber, CalendarError> {
+        self.get(from)?.julian_day_number(day, month, year, era)
+    }
+
+    // the converted date, with its weekday and English names
+    pub fn date(&self, to: &str, day: JulianDayNumber) -> Result<GenericDate, CalendarError> {
+        self.date_in(to, day, "en")
+    }
+
+    // the converted date, with its weekday and names in the locale
+    pub fn date_in(&self, to: &str, day: JulianDayNumber, locale: &str) -> Result<GenericDate, CalendarError> {
+        let calendar = self.get(to)?;
+        let mut date = calendar.date(day)?;
+        set_names(calendar, &mut date, day, locale);
+        Ok(date)
+    }
+
+    pub fn convert(&self, from: &str, to: &str, day: u32, month: &str, year: i32, era: &str) -> Result<GenericDate, CalendarError> {
+        self.convert_in(from, to, day, month, year, era, "en")
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    pub fn convert_in(&self, from: &str, to: &str, day: u32, month: &str, year: i32, era: &str, locale: &str) -> Result<GenericDate, CalendarError> {
+        self.date_in(to, self.to_julian_day_number(from, day, month, year, era)?, locale)
+    }
+
+    // the dates of DynCalendar::range, with their weekdays and English names
+    pub fn range(&self, to: &str, start: JulianDayNumber, days: u32, step: u32) -> Result<Vec<GenericDate>, CalendarError> {
+        self.range_in(to, start, days, step, "en")
+    }
+
+    pub fn range_in(&self, to: &str, start: JulianDayNumber, days: u32, step: u32, locale: &str) -> Result<Vec<GenericDate>, CalendarError> {
+        let calendar = self.get(to)?;
+        let mut dates = calendar.range(start, days, step)?;
+        for (i, date) in dates.iter_mut().enumerate() {
+            set_names(calendar, date, start.offset(i as i64 * step as i64)?, locale);
+        }
+        Ok(dates)
+    }
+}
+
+// fills in the weekday and the names of a converted date
+fn set_names(calendar: &dyn DynCalendar, date: &mut GenericDate, day: JulianDayNumber, locale: &str) {
+    let weekday = Weekday::from_julian_day_number(day);
+    date.weekday = weekday.number();
+    date.weekday_text = String::from(locale::weekday_name(weekday, locale));
+    let months = calendar.localized_month_names(locale);
+    date.month_text = months.get((date.month_name as usize).wrapping_sub(1)).cloned().unwrap_or_default();
+    let eras = calendar.localized_era_names(locale);
+    date.era_text = eras.get(date.era as usize).cloned().unwrap_or_default();
+}
+
+thread_local! {
+    static REGISTRY: RefCell<CalendarRegistry> = RefCell::new(CalendarRegistry::with_builtin());
+}
+
+// adds a calendar to the registry js_api uses
+pub fn register_calendar(calendar: Box<dyn DynCalendar>) {
+    REGISTRY.with(|registry| registry.borrow_mut().register(calendar));
+}
+
+pub fn with_registry<R>(f: impl FnOnce(&CalendarRegistry) -> R) -> R {
+    REGISTRY.with(|registry| f(&registry.borrow()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn range_sizes() {
+        let registry = CalendarRegistry::with_builtin();
+        let start = JulianDayNumber::new(2460000);
+        assert_eq!(registry.range("gregorian", start, 10, 3).map(|dates| dates.len()), Ok(4));
+        assert_eq!(registry.range("hebrew", start, MAX_RANGE_DATES, 1).map(|dates| dates.len()), Ok(MAX_RANGE_DATES as usize));
+        assert_eq!(registry.range("gregorian", start, 0, 1).map(|dates| dates.len()), Ok(0));
+    }
+
+    #[test]
+    fn range_too_large() {
+        let registry = CalendarRegistry::with_builtin();
+        let start = JulianDayNumber::new(2460000);
+        assert_eq!(registry.range("gregorian", start, u32::MAX, 1).map(|_| ()), Err(CalendarError::InvalidInput));
+        assert_eq!(registry.range("gregorian", start, MAX_RANGE_DATES + 1, 1).map(|_| ()), Err(CalendarError::InvalidInput));
+        assert_eq!(registry.range("gregorian", start, 10, 0).map(|_| ()), Err(CalendarError::InvalidInput));
+        // a large step keeps the number of dates down
+        assert_eq!(registry.range("gregorian", start, 2 * MAX_RANGE_DATES, 2).map(|dates| dates.len()), Ok(MAX_RANGE_DATES as usize));
+    }
+}
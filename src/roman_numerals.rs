@@ -0,0 +1,159 @@
+use std::convert::TryFrom;
+
+use crate::invalid_year_err::CalendarError;
+
+/*
+Roman numerals, as used for years on inscriptions and title pages
+("MDCCLII" is 1752).
+
+Numerals are written in the usual subtractive form (IV, XC, CM) from 1 to
+3999. Reading is more tolerant, since older texts vary:
+- letters in either case, and the Unicode numerals (Ⅻ)
+- additive forms such as IIII, VIIII and DCCCC
+- j for i, as in the final j of viij
+- the apostrophus forms CIↃ for M and IↃ for D
+- full stops and spaces between the letters, as in M.DCC.LII
+ */
+
+const NUMERALS: [(u32, &str); 13] = [
+    (1000, "M"),
+    (900, "CM"),
+    (500, "D"),
+    (400, "CD"),
+    (100, "C"),
+    (90, "XC"),
+    (50, "L"),
+    (40, "XL"),
+    (10, "X"),
+    (9, "IX"),
+    (5, "V"),
+    (4, "IV"),
+    (1, "I"),
+];
+
+pub const MAX_ROMAN_NUMERAL: u32 = 3999;
+
+// 0 and numbers above 3999 give CalendarError::Overflow
+pub fn to_roman_numeral(n: u32) -> Result<String, CalendarError> {
+    if n == 0 || n > MAX_ROMAN_NUMERAL {
+        return Err(CalendarError::Overflow);
+    }
+    let mut output = String::new();
+    let mut rest = n;
+    for (value, letters) in NUMERALS.iter() {
+        while rest >= *value {
+            output.push_str(letters);
+            rest -= value;
+        }
+    }
+    Ok(output)
+}
+
+fn value(letter: char) -> Option<u32> {
+    match letter {
+        'I' | 'J' => Some(1),
+        'V' => Some(5),
+        'X' => Some(10),
+        'L' => Some(50),
+        'C' => Some(100),
+        'D' => Some(500),
+        'M' => Some(1000),
+        _ => None,
+    }
+}
+
+// the letters of a numeral in upper case, with Unicode numerals and the
+// apostrophus forms written out
+fn normalize(text: &str) -> String {
+    let mut letters = String::new();
+    for c in text.chars().filter(|c| !c.is_whitespace() && *c != '.') {
+        match c {
+            // Ⅰ to Ⅿ and ⅰ to ⅿ
+            '\u{2160}'..='\u{217F}' => {
+                let offset = (c as u32 - 0x2160) % 16;
+                letters.push_str(["I", "II", "III", "IV", "V", "VI", "VII", "VIII", "IX", "X", "XI", "XII", "L", "C", "D", "M"][offset as usize]);
+            }
+            'Ↄ' | 'ↄ' | 'Ɔ' | 'ɔ' => letters.push('Ↄ'),
+            c => letters.extend(c.to_uppercase()),
+        }
+    }
+    letters.replace("CIↃ", "M").replace("IↃ", "D")
+}
+
+/*
+The value of a Roman numeral. Each letter adds its value, except that a
+letter before a larger one is subtracted (IX), without checking that the
+numeral is written the usual way.
+
+Text that is not a numeral gives CalendarError::InvalidInput
+ */
+pub fn from_roman_numeral(text: &str) -> Result<u32, CalendarError> {
+    let values = normalize(text)
+        .chars()
+        .map(value)
+        .collect::<Option<Vec<u32>>>()
+        .ok_or(CalendarError::InvalidInput)?;
+    if values.is_empty() {
+        return Err(CalendarError::InvalidInput);
+    }
+    let mut total: i64 = 0;
+    for (i, &v) in values.iter().enumerate() {
+        match values.get(i + 1) {
+            Some(&next) if next > v => total -= v as i64,
+            _ => total += v as i64,
+        }
+    }
+    match total {
+        total if total <= 0 => Err(CalendarError::InvalidInput),
+        total => u32::try_from(total).map_err(|_| CalendarError::Overflow),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn writing() {
+        assert_eq!(to_roman_numeral(1), Ok(String::from("I")));
+        assert_eq!(to_roman_numeral(4), Ok(String::from("IV")));
+        assert_eq!(to_roman_numeral(1752), Ok(String::from("MDCCLII")));
+        assert_eq!(to_roman_numeral(3999), Ok(String::from("MMMCMXCIX")));
+        assert_eq!(to_roman_numeral(0), Err(CalendarError::Overflow));
+        assert_eq!(to_roman_numeral(4000), Err(CalendarError::Overflow));
+    }
+
+    #[test]
+    fn older_forms() {
+        assert_eq!(from_roman_numeral("IIII"), Ok(4));
+        assert_eq!(from_roman_numeral("DCCCC"), Ok(900));
+        assert_eq!(from_roman_numeral("viij"), Ok(8));
+        assert_eq!(from_roman_numeral("M.DCC.LII"), Ok(1752));
+        assert_eq!(from_roman_numeral(" mdcc lii "), Ok(1752));
+        assert_eq!(from_roman_numeral("CIↃ"), Ok(1000));
+        assert_eq!(from_roman_numeral("IↃ"), Ok(500));
+        assert_eq!(from_roman_numeral("CIↃIↃCCLII"), Ok(1752));
+    }
+
+    #[test]
+    fn unicode_numerals() {
+        assert_eq!(from_roman_numeral("ⅯⅮⅭⅭⅬⅡ"), Ok(1752));
+        assert_eq!(from_roman_numeral("ⅿⅾⅽⅽⅼⅱ"), Ok(1752));
+        assert_eq!(from_roman_numeral("Ⅻ"), Ok(12));
+        assert_eq!(from_roman_numeral("ⅯⅯⅩⅩⅣ"), Ok(2024));
+    }
+
+    #[test]
+    fn not_numerals() {
+        for text in ["", " . ", "ABC", "12", "MCMXIIV2", "Ⅿ-Ⅽ"].iter() {
+            assert_eq!(from_roman_numeral(text), Err(CalendarError::InvalidInput), "{}", text);
+        }
+    }
+
+    #[test]
+    fn round_trip() {
+        for n in 1..=MAX_ROMAN_NUMERAL {
+            assert_eq!(from_roman_numeral(&to_roman_numeral(n).unwrap()), Ok(n));
+        }
+    }
+}
@@ -0,0 +1,785 @@
+use crate::astronomy;
+pub use crate::astronomy::Location;
+use crate::cal::{supported, Calendar};
+use crate::julian_day::JulianDayNumber;
+use crate::arithmetic::CalendarArithmetic;
+use crate::format::DateFormat;
+use crate::invalid_year_err::CalendarError;
+use crate::julian_gregorian::gregorian_julian_day;
+use crate::GenericDate;
+use std::convert::TryInto;
+use std::ops::RangeInclusive;
+
+/*
+Solar calendars of South Asia whose months follow the sun through the twelve
+signs of the sidereal zodiac. Month 1 begins when the sun enters Aries
+(Mesha) in mid April, and each later month when it enters the next sign (the
+sankranti).
+
+The moment of a sankranti rarely falls exactly at the start of a day, so each
+calendar has a rule for which day counts as the first day of the month. All
+the calendars here share the same engine and only differ in that rule, the
+place where it is applied and how years are counted.
+ */
+
+/*
+Moment of the day compared with the sankranti. A month starts on the first
+day whose critical time comes after the sun enters the sign
+ */
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum CriticalTime {
+    // midnight at the end of the day, so the month starts on the day the
+    // sankranti falls on
+    Midnight,
+    Sunrise,
+    Sunset,
+}
+
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum MonthStartRule {
+    // month starts are found from the sankranti, seen from `location`
+    Sankranti {
+        critical_time: CriticalTime,
+        location: Location,
+    },
+    // the month lengths Nepal publishes, for the years in NEPAL_MONTH_LENGTHS;
+    // other years start months on the day of the sankranti as seen from
+    // Kathmandu
+    Nepal,
+    // the 2019 revision of the Bengali calendar in Bangladesh: Boishakh 1 is
+    // always 14 April, the first six months have 31 days, Falgun has 29 (30
+    // in Gregorian leap years) and the rest have 30
+    Bangladesh,
+}
+
+/*
+A sidereal solar calendar. Year `y` begins in the Gregorian year
+`y - year_offset`
+ */
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub struct SiderealSolarCalendar {
+    pub rule: MonthStartRule,
+    pub year_offset: i128,
+}
+
+// the astronomy is only trusted from 2000 BC to AD 2500 (Gregorian)
+const FIRST_GREGORIAN_YEAR: i128 = -1999;
+const LAST_GREGORIAN_YEAR: i128 = 2500;
+
+const BANGLADESH_MONTH_LENGTHS: [i128; 12] = [31, 31, 31, 31, 31, 31, 30, 30, 30, 30, 29, 30];
+
+const KATHMANDU: Location = Location {
+    latitude: 27.7172,
+    longitude: 85.3240,
+};
+
+// first Bikram Sambat year of NEPAL_MONTH_LENGTHS, and the Gregorian date of
+// its Baisakh 1
+const NEPAL_TABLE_FIRST_YEAR: i128 = 1970;
+const NEPAL_TABLE_START: (i128, i128, i128) = (1913, 4, 13);
+
+/*
+Month lengths of each Bikram Sambat year from 1970 to 2081, as published in
+Nepal. They are set by the almanac makers rather than from the exact moment
+of each sankranti, so months often start a day earlier or later than the
+astronomical rule gives (Shrawan 2080 has 32 days where the rule gives 31).
+1970 and 2082 start on the same day by both, so the table and the rule join
+up without a gap. Years from 2082, through 2100 and beyond, use the rule
+until rows for them are added from the published almanacs; the long tables
+circulated for those years could not be checked against them.
+ */
+const NEPAL_MONTH_LENGTHS: [[u8; 12]; 112] = [
+    [31, 31, 32, 31, 31, 31, 30, 29, 30, 29, 30, 30], // 1970
+    [31, 31, 32, 31, 32, 30, 30, 29, 30, 29, 30, 30], // 1971
+    [31, 32, 31, 32, 31, 30, 30, 30, 29, 29, 30, 31], // 1972
+    [30, 32, 31, 32, 31, 30, 30, 30, 29, 30, 29, 31], // 1973
+    [31, 31, 32, 31, 31, 31, 30, 29, 30, 29, 30, 30], // 1974
+    [31, 31, 32, 32, 30, 31, 30, 29, 30, 29, 30, 30], // 1975
+    [31, 32, 31, 32, 31, 30, 30, 30, 29, 29, 30, 31], // 1976
+    [30, 32, 31, 32, 31, 31, 29, 30, 29, 30, 29, 31], // 1977
+    [31, 31, 32, 31, 31, 31, 30, 29, 30, 29, 30, 30], // 1978
+    [31, 31, 32, 32, 31, 30, 30, 29, 30, 29, 30, 30], // 1979
+    [31, 32, 31, 32, 31, 30, 30, 30, 29, 29, 30, 31], // 1980
+    [31, 31, 31, 32, 31, 31, 29, 30, 30, 29, 30, 30], // 1981
+    [31, 31, 32, 31, 31, 31, 30, 29, 30, 29, 30, 30], // 1982
+    [31, 31, 32, 32, 31, 30, 30, 29, 30, 29, 30, 30], // 1983
+    [31, 32, 31, 32, 31, 30, 30, 30, 29, 29, 30, 31], // 1984
+    [31, 31, 31, 32, 31, 31, 29, 30, 30, 29, 30, 30], // 1985
+    [31, 31, 32, 31, 31, 31, 30, 29, 30, 29, 30, 30], // 1986
+    [31, 32, 31, 32, 31, 30, 30, 29, 30, 29, 30, 30], // 1987
+    [31, 32, 31, 32, 31, 30, 30, 30, 29, 29, 30, 31], // 1988
+    [31, 31, 31, 32, 31, 31, 30, 29, 30, 29, 30, 30], // 1989
+    [31, 31, 32, 31, 31, 31, 30, 29, 30, 29, 30, 30], // 1990
+    [31, 32, 31, 32, 31, 30, 30, 29, 30, 29, 30, 30], // 1991
+    [31, 32, 31, 32, 31, 30, 30, 30, 29, 30, 29, 31], // 1992
+    [31, 31, 31, 32, 31, 31, 30, 29, 30, 29, 30, 30], // 1993
+    [31, 31, 32, 31, 31, 31, 30, 29, 30, 29, 30, 30], // 1994
+    [31, 32, 31, 32, 31, 30, 30, 30, 29, 29, 30, 30], // 1995
+    [31, 32, 31, 32, 31, 30, 30, 30, 29, 30, 29, 31], // 1996
+    [31, 31, 32, 31, 31, 31, 30, 29, 30, 29, 30, 30], // 1997
+    [31, 31, 32, 31, 31, 31, 30, 29, 30, 29, 30, 30], // 1998
+    [31, 32, 31, 32, 31, 30, 30, 30, 29, 29, 30, 31], // 1999
+    [30, 32, 31, 32, 31, 30, 30, 30, 29, 30, 29, 31], // 2000
+    [31, 31, 32, 31, 31, 31, 30, 29, 30, 29, 30, 30], // 2001
+    [31, 31, 32, 32, 31, 30, 30, 29, 30, 29, 30, 30], // 2002
+    [31, 32, 31, 32, 31, 30, 30, 30, 29, 29, 30, 31], // 2003
+    [30, 32, 31, 32, 31, 30, 30, 30, 29, 30, 29, 31], // 2004
+    [31, 31, 32, 31, 31, 31, 30, 29, 30, 29, 30, 30], // 2005
+    [31, 31, 32, 32, 31, 30, 30, 29, 30, 29, 30, 30], // 2006
+    [31, 32, 31, 32, 31, 30, 30, 30, 29, 29, 30, 31], // 2007
+    [31, 31, 31, 32, 31, 31, 29, 30, 30, 29, 29, 31], // 2008
+    [31, 31, 32, 31, 31, 31, 30, 29, 30, 29, 30, 30], // 2009
+    [31, 31, 32, 32, 31, 30, 30, 29, 30, 29, 30, 30], // 2010
+    [31, 32, 31, 32, 31, 30, 30, 30, 29, 29, 30, 31], // 2011
+    [31, 31, 31, 32, 31, 31, 29, 30, 30, 29, 30, 30], // 2012
+    [31, 31, 32, 31, 31, 31, 30, 29, 30, 29, 30, 30], // 2013
+    [31, 31, 32, 32, 31, 30, 30, 29, 30, 29, 30, 30], // 2014
+    [31, 32, 31, 32, 31, 30, 30, 30, 29, 29, 30, 31], // 2015
+    [31, 31, 31, 32, 31, 31, 29, 30, 30, 29, 30, 30], // 2016
+    [31, 31, 32, 31, 31, 31, 30, 29, 30, 29, 30, 30], // 2017
+    [31, 32, 31, 32, 31, 30, 30, 29, 30, 29, 30, 30], // 2018
+    [31, 32, 31, 32, 31, 30, 30, 30, 29, 30, 29, 31], // 2019
+    [31, 31, 31, 32, 31, 31, 30, 29, 30, 29, 30, 30], // 2020
+    [31, 31, 32, 31, 31, 31, 30, 29, 30, 29, 30, 30], // 2021
+    [31, 32, 31, 32, 31, 30, 30, 30, 29, 29, 30, 30], // 2022
+    [31, 32, 31, 32, 31, 30, 30, 30, 29, 30, 29, 31], // 2023
+    [31, 31, 31, 32, 31, 31, 30, 29, 30, 29, 30, 30], // 2024
+    [31, 31, 32, 31, 31, 31, 30, 29, 30, 29, 30, 30], // 2025
+    [31, 32, 31, 32, 31, 30, 30, 30, 29, 29, 30, 31], // 2026
+    [30, 32, 31, 32, 31, 30, 30, 30, 29, 30, 29, 31], // 2027
+    [31, 31, 32, 31, 31, 31, 30, 29, 30, 29, 30, 30], // 2028
+    [31, 31, 32, 31, 32, 30, 30, 29, 30, 29, 30, 30], // 2029
+    [31, 32, 31, 32, 31, 30, 30, 30, 29, 29, 30, 31], // 2030
+    [30, 32, 31, 32, 31, 30, 30, 30, 29, 30, 29, 31], // 2031
+    [31, 31, 32, 31, 31, 31, 30, 29, 30, 29, 30, 30], // 2032
+    [31, 31, 32, 32, 31, 30, 30, 29, 30, 29, 30, 30], // 2033
+    [31, 32, 31, 32, 31, 30, 30, 30, 29, 29, 30, 31], // 2034
+    [30, 32, 31, 32, 31, 31, 29, 30, 30, 29, 29, 31], // 2035
+    [31, 31, 32, 31, 31, 31, 30, 29, 30, 29, 30, 30], // 2036
+    [31, 31, 32, 32, 31, 30, 30, 29, 30, 29, 30, 30], // 2037
+    [31, 32, 31, 32, 31, 30, 30, 30, 29, 29, 30, 31], // 2038
+    [31, 31, 31, 32, 31, 31, 29, 30, 30, 29, 30, 30], // 2039
+    [31, 31, 32, 31, 31, 31, 30, 29, 30, 29, 30, 30], // 2040
+    [31, 31, 32, 32, 31, 30, 30, 29, 30, 29, 30, 30], // 2041
+    [31, 32, 31, 32, 31, 30, 30, 30, 29, 29, 30, 31], // 2042
+    [31, 31, 31, 32, 31, 31, 29, 30, 30, 29, 30, 30], // 2043
+    [31, 31, 32, 31, 31, 31, 30, 29, 30, 29, 30, 30], // 2044
+    [31, 32, 31, 32, 31, 30, 30, 29, 30, 29, 30, 30], // 2045
+    [31, 32, 31, 32, 31, 30, 30, 30, 29, 29, 30, 31], // 2046
+    [31, 31, 31, 32, 31, 31, 30, 29, 30, 29, 30, 30], // 2047
+    [31, 31, 32, 31, 31, 31, 30, 29, 30, 29, 30, 30], // 2048
+    [31, 32, 31, 32, 31, 30, 30, 30, 29, 29, 30, 30], // 2049
+    [31, 32, 31, 32, 31, 30, 30, 30, 29, 30, 29, 31], // 2050
+    [31, 31, 31, 32, 31, 31, 30, 29, 30, 29, 30, 30], // 2051
+    [31, 31, 32, 31, 31, 31, 30, 29, 30, 29, 30, 30], // 2052
+    [31, 32, 31, 32, 31, 30, 30, 30, 29, 29, 30, 30], // 2053
+    [31, 32, 31, 32, 31, 30, 30, 30, 29, 30, 29, 31], // 2054
+    [31, 31, 32, 31, 31, 31, 30, 29, 30, 29, 30, 30], // 2055
+    [31, 31, 32, 31, 32, 30, 30, 29, 30, 29, 30, 30], // 2056
+    [31, 32, 31, 32, 31, 30, 30, 30, 29, 29, 30, 31], // 2057
+    [30, 32, 31, 32, 31, 30, 30, 30, 29, 30, 29, 31], // 2058
+    [31, 31, 32, 31, 31, 31, 30, 29, 30, 29, 30, 30], // 2059
+    [31, 31, 32, 32, 31, 30, 30, 29, 30, 29, 30, 30], // 2060
+    [31, 32, 31, 32, 31, 30, 30, 30, 29, 29, 30, 31], // 2061
+    [30, 32, 31, 32, 31, 31, 29, 30, 29, 30, 29, 31], // 2062
+    [31, 31, 32, 31, 31, 31, 30, 29, 30, 29, 30, 30], // 2063
+    [31, 31, 32, 32, 31, 30, 30, 29, 30, 29, 30, 30], // 2064
+    [31, 32, 31, 32, 31, 30, 30, 30, 29, 29, 30, 31], // 2065
+    [31, 31, 31, 32, 31, 31, 29, 30, 30, 29, 29, 31], // 2066
+    [31, 31, 32, 31, 31, 31, 30, 29, 30, 29, 30, 30], // 2067
+    [31, 31, 32, 32, 31, 30, 30, 29, 30, 29, 30, 30], // 2068
+    [31, 32, 31, 32, 31, 30, 30, 30, 29, 29, 30, 31], // 2069
+    [31, 31, 31, 32, 31, 31, 29, 30, 30, 29, 30, 30], // 2070
+    [31, 31, 32, 31, 31, 31, 30, 29, 30, 29, 30, 30], // 2071
+    [31, 32, 31, 32, 31, 30, 30, 29, 30, 29, 30, 30], // 2072
+    [31, 32, 31, 32, 31, 30, 30, 30, 29, 29, 30, 31], // 2073
+    [31, 31, 31, 32, 31, 31, 30, 29, 30, 29, 30, 30], // 2074
+    [31, 31, 32, 31, 31, 31, 30, 29, 30, 29, 30, 30], // 2075
+    [31, 32, 31, 32, 31, 30, 30, 30, 29, 29, 30, 30], // 2076
+    [31, 32, 31, 32, 31, 30, 30, 30, 29, 30, 29, 31], // 2077
+    [31, 31, 31, 32, 31, 31, 30, 29, 30, 29, 30, 30], // 2078
+    [31, 31, 32, 31, 31, 31, 30, 29, 30, 29, 30, 30], // 2079
+    [31, 32, 31, 32, 31, 30, 30, 30, 29, 29, 30, 30], // 2080
+    [31, 31, 32, 32, 31, 30, 30, 30, 29, 30, 30, 30], // 2081
+];
+
+impl SiderealSolarCalendar {
+    // julian day of the first day of `month` (1 to 12) in the year that
+    // begins in `gregorian_year`
+    fn month_start(&self, gregorian_year: i128, month: u8) -> i128 {
+        match self.rule {
+            MonthStartRule::Bangladesh => {
+                let mut start = gregorian_julian_day(gregorian_year, 4, 14);
+                for m in 1..month as usize {
+                    start += BANGLADESH_MONTH_LENGTHS[m - 1];
+                    // Falgun gains a day when the February it contains is the leap one
+                    if (m == 11) & is_gregorian_leap_year(gregorian_year + 1) {
+                        start += 1;
+                    }
+                }
+                start
+            }
+            MonthStartRule::Nepal => {
+                let year = gregorian_year + self.year_offset;
+                match NEPAL_MONTH_LENGTHS.get((year - NEPAL_TABLE_FIRST_YEAR) as usize) {
+                    Some(lengths) if year >= NEPAL_TABLE_FIRST_YEAR => {
+                        let (y, m, d) = NEPAL_TABLE_START;
+                        let years: i128 = NEPAL_MONTH_LENGTHS[..(year - NEPAL_TABLE_FIRST_YEAR) as usize]
+                            .iter()
+                            .flatten()
+                            .map(|&n| n as i128)
+                            .sum();
+                        let months: i128 = lengths[..month as usize - 1].iter().map(|&n| n as i128).sum();
+                        gregorian_julian_day(y, m, d) + years + months
+                    }
+                    _ => sankranti_month_start(CriticalTime::Midnight, KATHMANDU, gregorian_year, month),
+                }
+            }
+            MonthStartRule::Sankranti { critical_time, location } => {
+                sankranti_month_start(critical_time, location, gregorian_year, month)
+            }
+        }
+    }
+
+    fn check_year(&self, year: i128) -> Result<i128, CalendarError> {
+        let gregorian_year = year - self.year_offset;
+        if !(FIRST_GREGORIAN_YEAR..=LAST_GREGORIAN_YEAR).contains(&gregorian_year) {
+            return Err(CalendarError::Overflow);
+        }
+        Ok(gregorian_year)
+    }
+
+    pub fn days_in_month(&self, year: i128, month: u8) -> Result<u8, CalendarError> {
+        if !(1..=12).contains(&month) {
+            return Err(CalendarError::UnknownMonth(month.to_string()));
+        }
+        let gregorian_year = self.check_year(year)?;
+        let start = self.month_start(gregorian_year, month);
+        let end = match month {
+            12 => self.month_start(gregorian_year + 1, 1),
+            _ => self.month_start(gregorian_year, month + 1),
+        };
+        Ok((end - start) as u8)
+    }
+
+    pub fn to_julian_day_number(&self, year: i128, month: u8, day: u8) -> Result<JulianDayNumber, CalendarError> {
+        let max = self.days_in_month(year, month)?;
+        if (day < 1) | (day > max) {
+            return Err(CalendarError::DayOutOfRange { day: day as u32, max: max as u32 });
+        }
+        let gregorian_year = self.check_year(year)?;
+        JulianDayNumber::from_legacy(self.month_start(gregorian_year, month) + day as i128 - 1)
+    }
+
+    // (year, month, day) of `day`
+    pub fn from_julian_day_number(&self, day: JulianDayNumber) -> Result<(i128, u8, u8), CalendarError> {
+        let julian_day = day.to_legacy();
+        if (julian_day < gregorian_julian_day(FIRST_GREGORIAN_YEAR, 1, 1))
+            | (julian_day >= gregorian_julian_day(LAST_GREGORIAN_YEAR + 2, 1, 1))
+        {
+            return Err(CalendarError::Overflow);
+        }
+
+        // the year starts in the spring, so it began either in this
+        // Gregorian year or the one before
+        let mut gregorian_year = FIRST_GREGORIAN_YEAR + (julian_day - gregorian_julian_day(FIRST_GREGORIAN_YEAR, 1, 1)) * 400 / 146097;
+        while gregorian_julian_day(gregorian_year, 1, 1) > julian_day {
+            gregorian_year -= 1;
+        }
+        while gregorian_julian_day(gregorian_year + 1, 1, 1) <= julian_day {
+            gregorian_year += 1;
+        }
+        if self.month_start(gregorian_year, 1) > julian_day {
+            gregorian_year -= 1;
+        }
+        self.check_year(gregorian_year + self.year_offset)?;
+
+        let mut month = 12;
+        let mut start = self.month_start(gregorian_year, month);
+        while start > julian_day {
+            month -= 1;
+            start = self.month_start(gregorian_year, month);
+        }
+
+        Ok((gregorian_year + self.year_offset, month, (julian_day - start + 1) as u8))
+    }
+}
+
+// julian day of the first day of `month` in the year that begins in
+// `gregorian_year`, for the month starts of MonthStartRule::Sankranti
+fn sankranti_month_start(critical_time: CriticalTime, location: Location, gregorian_year: i128, month: u8) -> i128 {
+    // the sun enters Aries some time after 1 January (when exactly
+    // drifts with the precession of the equinoxes) and the later
+    // signs follow in order
+    let aries = astronomy::sidereal_solar_longitude_after(
+        0.0,
+        astronomy::day_start(gregorian_julian_day(gregorian_year, 1, 1)),
+    );
+    let sankranti = match month {
+        1 => aries,
+        _ => astronomy::sidereal_solar_longitude_after((month as f64 - 1.0) * 30.0, aries),
+    };
+    let day = astronomy::day_of(sankranti + location.longitude / 360.0);
+
+    let critical = match critical_time {
+        CriticalTime::Midnight => Some(astronomy::local_midnight(day + 1, location)),
+        CriticalTime::Sunrise => astronomy::sunrise(day, location),
+        CriticalTime::Sunset => astronomy::sunset(day, location),
+    };
+    match critical {
+        Some(t) if sankranti < t => day,
+        _ => day + 1,
+    }
+}
+
+fn is_gregorian_leap_year(year: i128) -> bool {
+    ((year % 4 == 0) & (year % 100 != 0)) | (year % 400 == 0)
+}
+
+pub const NEPALI: SiderealSolarCalendar = SiderealSolarCalendar {
+    rule: MonthStartRule::Nepal,
+    year_offset: 57,
+};
+
+pub const BENGALI: SiderealSolarCalendar = SiderealSolarCalendar {
+    rule: MonthStartRule::Bangladesh,
+    year_offset: -593,
+};
+
+pub const TAMIL: SiderealSolarCalendar = SiderealSolarCalendar {
+    rule: MonthStartRule::Sankranti {
+        critical_time: CriticalTime::Sunset,
+        location: Location {
+            latitude: 13.0827,
+            longitude: 80.2707,
+        },
+    },
+    year_offset: -78,
+};
+
+pub const NEPALI_MONTH_NAMES: [&str; 12] = [
+    "Baisakh", "Jestha", "Asar", "Shrawan", "Bhadra", "Asoj", "Kartik", "Mangsir", "Poush", "Magh",
+    "Falgun", "Chaitra",
+];
+
+pub const BENGALI_MONTH_NAMES: [&str; 12] = [
+    "Boishakh", "Jyoishtho", "Asharh", "Shrabon", "Bhadro", "Ashwin", "Kartik", "Ogrohayon",
+    "Poush", "Magh", "Falgun", "Choitro",
+];
+
+pub const TAMIL_MONTH_NAMES: [&str; 12] = [
+    "Chithirai", "Vaikasi", "Aani", "Aadi", "Aavani", "Purattasi", "Aippasi", "Karthigai",
+    "Margazhi", "Thai", "Maasi", "Panguni",
+];
+
+// shared by the `new` constructors: day and year as numbers, month by name or number
+fn parse_fields(day: u32, month: &str, year: i32, names: &[&str; 12]) -> Result<(u32, u8, u8), CalendarError> {
+    // months have at most 32 days
+    let day: u8 = match day.try_into() {
+        Ok(n) => n,
+        Err(_) => return Err(CalendarError::DayOutOfRange { day, max: 32 }),
+    };
+    let year: u32 = match year.try_into() {
+        Ok(n) => n,
+        Err(_) => return Err(CalendarError::InvalidInput),
+    };
+    let month = match names.iter().position(|&n| n == month) {
+        Some(i) => i as u8 + 1,
+        None => match month.parse() {
+            Ok(n) => n,
+            Err(_) => return Err(CalendarError::UnknownMonth(String::from(month))),
+        },
+    };
+    Ok((year, month, day))
+}
+
+fn checked_year(year: i128) -> Result<u32, CalendarError> {
+    match year.try_into() {
+        Ok(n) if n > 0 => Ok(n),
+        _ => Err(CalendarError::Overflow),
+    }
+}
+
+/*
+Bikram Sambat, the official calendar of Nepal. From 1970 to 2081 the months
+have the lengths published in Nepal. Outside those years months start on the
+day of the sankranti as seen from Kathmandu, which can differ from the
+almanac by a day around some month boundaries.
+ */
+#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct NepaliDate {
+    pub year: u32,
+    pub month: u8,
+    pub day: u8,
+}
+
+/*
+Bengali calendar (Bangabda) as revised in Bangladesh in 2019. Years start
+on 14 April and are numbered 593 less than the Gregorian year they start in.
+ */
+#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct BengaliDate {
+    pub year: u32,
+    pub month: u8,
+    pub day: u8,
+}
+
+/*
+Tamil solar calendar. Months start on the day of the sankranti if it happens
+before sunset in Chennai, otherwise the day after. Years are counted in the
+Shalivahana Saka era.
+ */
+#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct TamilDate {
+    pub year: u32,
+    pub month: u8,
+    pub day: u8,
+}
+
+impl Calendar for NepaliDate {
+    fn to_julian_day_number(&self) -> Result<JulianDayNumber, CalendarError> {
+        supported::<Self>(NEPALI.to_julian_day_number(self.year as i128, self.month, self.day)?)
+    }
+
+    fn from_julian_day_number(day: JulianDayNumber) -> Result<Self, CalendarError> where Self: Sized {
+        let (year, month, day) = NEPALI.from_julian_day_number(supported::<Self>(day)?)?;
+        Ok(NepaliDate { year: checked_year(year)?, month, day })
+    }
+
+    // the first day of year 1 to the last day of 2557
+    fn supported_range() -> RangeInclusive<JulianDayNumber> where Self: Sized {
+        JulianDayNumber::new(1700681)..=JulianDayNumber::new(2634641)
+    }
+
+    fn new(day: u32, month: String, year: i32, _era: String) -> Result<Self, CalendarError> where Self: Sized {
+        let (year, month, day) = parse_fields(day, &month, year, &NEPALI_MONTH_NAMES)?;
+        Ok(NepaliDate { year, month, day })
+    }
+}
+
+impl Calendar for BengaliDate {
+    fn to_julian_day_number(&self) -> Result<JulianDayNumber, CalendarError> {
+        supported::<Self>(BENGALI.to_julian_day_number(self.year as i128, self.month, self.day)?)
+    }
+
+    fn from_julian_day_number(day: JulianDayNumber) -> Result<Self, CalendarError> where Self: Sized {
+        let (year, month, day) = BENGALI.from_julian_day_number(supported::<Self>(day)?)?;
+        Ok(BengaliDate { year: checked_year(year)?, month, day })
+    }
+
+    // the first day of year 1 to the last day of 1907
+    fn supported_range() -> RangeInclusive<JulianDayNumber> where Self: Sized {
+        JulianDayNumber::new(1938118)..=JulianDayNumber::new(2634634)
+    }
+
+    fn new(day: u32, month: String, year: i32, _era: String) -> Result<Self, CalendarError> where Self: Sized {
+        let (year, month, day) = parse_fields(day, &month, year, &BENGALI_MONTH_NAMES)?;
+        Ok(BengaliDate { year, month, day })
+    }
+}
+
+impl Calendar for TamilDate {
+    fn to_julian_day_number(&self) -> Result<JulianDayNumber, CalendarError> {
+        supported::<Self>(TAMIL.to_julian_day_number(self.year as i128, self.month, self.day)?)
+    }
+
+    fn from_julian_day_number(day: JulianDayNumber) -> Result<Self, CalendarError> where Self: Sized {
+        let (year, month, day) = TAMIL.from_julian_day_number(supported::<Self>(day)?)?;
+        Ok(TamilDate { year: checked_year(year)?, month, day })
+    }
+
+    // the first day of year 1 to the last day of 2422
+    fn supported_range() -> RangeInclusive<JulianDayNumber> where Self: Sized {
+        JulianDayNumber::new(1749991)..=JulianDayNumber::new(2634641)
+    }
+
+    fn new(day: u32, month: String, year: i32, _era: String) -> Result<Self, CalendarError> where Self: Sized {
+        let (year, month, day) = parse_fields(day, &month, year, &TAMIL_MONTH_NAMES)?;
+        Ok(TamilDate { year, month, day })
+    }
+}
+
+impl CalendarArithmetic for NepaliDate {
+    fn fields(&self) -> (i64, u8, u8) {
+        (self.year as i64, self.month, self.day)
+    }
+
+    fn from_fields(year: i64, month: u8, day: u8) -> Result<Self, CalendarError> {
+        Ok(NepaliDate { year: checked_year(year as i128)?, month, day })
+    }
+
+    // a year of 366 days
+    fn is_leap_year(&self) -> bool {
+        matches!(self.days_in_year(), Ok(366))
+    }
+
+    fn month_count(_year: i64) -> Result<u8, CalendarError> {
+        Ok(12)
+    }
+
+    const MONTH_CYCLE: Option<(i64, i64)> = Some((1, 12));
+
+    fn month_length(year: i64, month: u8) -> Result<u8, CalendarError> {
+        NEPALI.days_in_month(year as i128, month)
+    }
+}
+
+impl CalendarArithmetic for BengaliDate {
+    fn fields(&self) -> (i64, u8, u8) {
+        (self.year as i64, self.month, self.day)
+    }
+
+    fn from_fields(year: i64, month: u8, day: u8) -> Result<Self, CalendarError> {
+        Ok(BengaliDate { year: checked_year(year as i128)?, month, day })
+    }
+
+    // a year of 366 days
+    fn is_leap_year(&self) -> bool {
+        matches!(self.days_in_year(), Ok(366))
+    }
+
+    fn month_count(_year: i64) -> Result<u8, CalendarError> {
+        Ok(12)
+    }
+
+    const MONTH_CYCLE: Option<(i64, i64)> = Some((1, 12));
+
+    fn month_length(year: i64, month: u8) -> Result<u8, CalendarError> {
+        BENGALI.days_in_month(year as i128, month)
+    }
+}
+
+impl CalendarArithmetic for TamilDate {
+    fn fields(&self) -> (i64, u8, u8) {
+        (self.year as i64, self.month, self.day)
+    }
+
+    fn from_fields(year: i64, month: u8, day: u8) -> Result<Self, CalendarError> {
+        Ok(TamilDate { year: checked_year(year as i128)?, month, day })
+    }
+
+    // a year of 366 days
+    fn is_leap_year(&self) -> bool {
+        matches!(self.days_in_year(), Ok(366))
+    }
+
+    fn month_count(_year: i64) -> Result<u8, CalendarError> {
+        Ok(12)
+    }
+
+    const MONTH_CYCLE: Option<(i64, i64)> = Some((1, 12));
+
+    fn month_length(year: i64, month: u8) -> Result<u8, CalendarError> {
+        TAMIL.days_in_month(year as i128, month)
+    }
+}
+
+fn month_name(names: &[&'static str; 12], month: u8) -> &'static str {
+    names.get((month as usize).wrapping_sub(1)).unwrap_or(&"")
+}
+
+impl NepaliDate {
+    pub fn month_name(&self) -> &'static str {
+        month_name(&NEPALI_MONTH_NAMES, self.month)
+    }
+}
+
+impl std::fmt::Display for NepaliDate {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(f, "year: {}, month: {}, day: {}", self.year, self.month_name(), self.day)
+    }
+}
+
+impl BengaliDate {
+    pub fn month_name(&self) -> &'static str {
+        month_name(&BENGALI_MONTH_NAMES, self.month)
+    }
+}
+
+impl std::fmt::Display for BengaliDate {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(f, "year: {}, month: {}, day: {}", self.year, self.month_name(), self.day)
+    }
+}
+
+impl TamilDate {
+    pub fn month_name(&self) -> &'static str {
+        month_name(&TAMIL_MONTH_NAMES, self.month)
+    }
+}
+
+impl std::fmt::Display for TamilDate {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(f, "year: {}, month: {}, day: {}", self.year, self.month_name(), self.day)
+    }
+}
+
+fn generic_date(year: u32, month: u8, day: u8) -> GenericDate {
+    GenericDate {
+        is_valid: true,
+        is_not_overflow: true,
+        era: false,
+        year,
+        month_name: month,
+        day,
+        is_leap_day: false,
+        weekday: 0,
+        error: String::new(),
+        month_text: String::new(),
+        weekday_text: String::new(),
+        era_text: String::new(),
+    }
+}
+
+impl DateFormat for NepaliDate {
+    const CALENDAR: &'static str = "Nepali";
+
+    fn month_name(&self) -> String {
+        String::from(NepaliDate::month_name(self))
+    }
+}
+
+impl From<NepaliDate> for GenericDate {
+    fn from(date: NepaliDate) -> GenericDate {
+        generic_date(date.year, date.month, date.day)
+    }
+}
+
+impl DateFormat for BengaliDate {
+    const CALENDAR: &'static str = "Bengali";
+
+    fn month_name(&self) -> String {
+        String::from(BengaliDate::month_name(self))
+    }
+}
+
+impl From<BengaliDate> for GenericDate {
+    fn from(date: BengaliDate) -> GenericDate {
+        generic_date(date.year, date.month, date.day)
+    }
+}
+
+impl DateFormat for TamilDate {
+    const CALENDAR: &'static str = "Tamil";
+
+    fn month_name(&self) -> String {
+        String::from(TamilDate::month_name(self))
+    }
+}
+
+impl From<TamilDate> for GenericDate {
+    fn from(date: TamilDate) -> GenericDate {
+        generic_date(date.year, date.month, date.day)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::julian_gregorian::GregorianCalendar;
+
+    fn gregorian(day: u32, month: &str, year: i32) -> JulianDayNumber {
+        GregorianCalendar::new(day, month.into(), year, "AD".into())
+            .unwrap()
+            .to_julian_day_number()
+            .unwrap()
+    }
+
+    fn nepali(day: u8, month: u8, year: u32) -> NepaliDate {
+        NepaliDate { year, month, day }
+    }
+
+    #[test]
+    fn nepali_new_years() {
+        assert_eq!(nepali(1, 1, 2081).to_julian_day_number(), Ok(gregorian(13, "April", 2024)));
+        assert_eq!(nepali(1, 1, 2080).to_julian_day_number(), Ok(gregorian(14, "April", 2023)));
+        assert_eq!(nepali(1, 1, 2000).to_julian_day_number(), Ok(gregorian(14, "April", 1943)));
+        assert_eq!(NepaliDate::from_julian_day_number(gregorian(13, "April", 2024)), Ok(nepali(1, 1, 2081)));
+    }
+
+    #[test]
+    fn nepali_month_ends() {
+        // Shrawan 2080 has 32 days in the published table
+        assert_eq!(NEPALI.days_in_month(2080, 4), Ok(32));
+        assert_eq!(nepali(32, 4, 2080).to_julian_day_number(), Ok(gregorian(17, "August", 2023)));
+        assert_eq!(nepali(1, 5, 2080).to_julian_day_number(), Ok(gregorian(18, "August", 2023)));
+        assert!(nepali(33, 4, 2080).to_julian_day_number().is_err());
+
+        assert_eq!(NepaliDate::from_julian_day_number(gregorian(12, "April", 2024)), Ok(nepali(30, 12, 2080)));
+        assert_eq!(nepali(1, 4, 2081).to_julian_day_number(), Ok(gregorian(16, "July", 2024)));
+        assert_eq!(nepali(17, 6, 2081).to_julian_day_number(), Ok(gregorian(3, "October", 2024)));
+        assert_eq!(nepali(1, 10, 2081).to_julian_day_number(), Ok(gregorian(14, "January", 2025)));
+    }
+
+    #[test]
+    fn nepali_table_joins_the_astronomical_rule() {
+        // the last day before the table and the first day after it
+        assert_eq!(NepaliDate::from_julian_day_number(gregorian(12, "April", 1913)), Ok(nepali(30, 12, 1969)));
+        assert_eq!(nepali(1, 1, 1970).to_julian_day_number(), Ok(gregorian(13, "April", 1913)));
+        assert_eq!(nepali(30, 12, 2081).to_julian_day_number(), Ok(gregorian(13, "April", 2025)));
+        assert_eq!(nepali(1, 1, 2082).to_julian_day_number(), Ok(gregorian(14, "April", 2025)));
+
+        let first = gregorian(1, "January", 1912);
+        let last = gregorian(1, "January", 2027);
+        let mut previous = NepaliDate::from_julian_day_number(first).unwrap();
+        for n in 1..last.value() - first.value() {
+            let day = first.offset(n).unwrap();
+            let date = NepaliDate::from_julian_day_number(day).unwrap();
+            assert_eq!(date.to_julian_day_number(), Ok(day));
+            assert!(date > previous);
+            previous = date;
+        }
+    }
+
+    #[test]
+    fn nepali_table_years() {
+        for year in NEPAL_TABLE_FIRST_YEAR..NEPAL_TABLE_FIRST_YEAR + NEPAL_MONTH_LENGTHS.len() as i128 {
+            let length: u32 = (1..=12).map(|m| NEPALI.days_in_month(year, m).unwrap() as u32).sum();
+            assert!(matches!(length, 365 | 366), "year {}", year);
+        }
+    }
+
+    #[test]
+    fn nepali_years_after_the_table() {
+        // Vijaya Dashami, 16 Asoj 2082
+        assert_eq!(nepali(16, 6, 2082).to_julian_day_number(), Ok(gregorian(2, "October", 2025)));
+        assert_eq!(NepaliDate::from_julian_day_number(gregorian(13, "April", 2025)), Ok(nepali(30, 12, 2081)));
+
+        assert_eq!(nepali(1, 1, 2100).to_julian_day_number(), Ok(gregorian(14, "April", 2043)));
+        assert_eq!(NEPALI.days_in_month(2100, 12), Ok(31));
+        assert_eq!(NepaliDate::from_julian_day_number(gregorian(13, "April", 2044)), Ok(nepali(31, 12, 2100)));
+        assert_eq!(NepaliDate::from_julian_day_number(gregorian(14, "April", 2044)), Ok(nepali(1, 1, 2101)));
+    }
+
+    #[test]
+    fn bengali_fixed_days() {
+        let bengali = |day, month, year| BengaliDate { year, month, day };
+        // Pohela Boishakh
+        assert_eq!(bengali(1, 1, 1432).to_julian_day_number(), Ok(gregorian(14, "April", 2025)));
+        assert_eq!(BengaliDate::from_julian_day_number(gregorian(14, "April", 2024)), Ok(bengali(1, 1, 1431)));
+        // Victory Day, Language Movement Day and Independence Day fall on
+        // the same Bengali dates every year, whether or not February is long
+        assert_eq!(BengaliDate::from_julian_day_number(gregorian(16, "December", 2025)), Ok(bengali(1, 9, 1432)));
+        assert_eq!(BengaliDate::from_julian_day_number(gregorian(21, "February", 2025)), Ok(bengali(8, 11, 1431)));
+        assert_eq!(BengaliDate::from_julian_day_number(gregorian(26, "March", 2024)), Ok(bengali(12, 12, 1430)));
+        assert_eq!(BengaliDate::from_julian_day_number(gregorian(26, "March", 2025)), Ok(bengali(12, 12, 1431)));
+        assert_eq!(BENGALI.days_in_month(1430, 11), Ok(30));
+        assert_eq!(BENGALI.days_in_month(1431, 11), Ok(29));
+    }
+
+    #[test]
+    fn tamil_month_starts() {
+        let tamil = |day, month, year| TamilDate { year, month, day };
+        // Puthandu: the sankranti was after sunset on 13 April 2024 and
+        // before dawn on 14 April 2025
+        assert_eq!(tamil(1, 1, 1946).to_julian_day_number(), Ok(gregorian(14, "April", 2024)));
+        assert_eq!(tamil(1, 1, 1947).to_julian_day_number(), Ok(gregorian(14, "April", 2025)));
+        assert_eq!(TamilDate::from_julian_day_number(gregorian(13, "April", 2025)), Ok(tamil(30, 12, 1946)));
+        // Thai Pongal
+        assert_eq!(TamilDate::from_julian_day_number(gregorian(14, "January", 2025)), Ok(tamil(1, 10, 1946)));
+        assert_eq!(TamilDate::from_julian_day_number(gregorian(14, "January", 2026)), Ok(tamil(1, 10, 1947)));
+    }
+}
@@ -0,0 +1,542 @@
+use crate::invalid_year_err::CalendarError;
+use crate::cal::{supported, Calendar};
+use crate::julian_day::JulianDayNumber;
+use crate::arithmetic::{month_days_by_julian_day, CalendarArithmetic};
+use crate::format::DateFormat;
+use crate::GenericDate;
+use std::cmp::Ordering;
+use std::convert::TryInto;
+use std::ops::RangeInclusive;
+
+/*
+Date on the Tibetan (Phugpa) lunisolar calendar.
+
+month: starts at month 1. is_leap_month is true for the first of two months
+with the same number (the leap month comes before the regular one)
+
+day: lunar day (tithi) number. Some numbers are skipped and others are
+repeated. is_leap_day is true for the second of two days with the same number
+
+year: Tibetan year as counted by the Phugpa calendar, which is 127 more than
+the Gregorian year in which it mostly falls
+ */
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct TibetanDate {
+    pub year: u32,
+    pub month: u8,
+    pub is_leap_month: bool,
+    pub day: u8,
+    pub is_leap_day: bool,
+}
+
+// ordered by time: a leap month comes before the regular month with the same
+// number, and a leap day after the regular day
+impl Ord for TibetanDate {
+    fn cmp(&self, other: &Self) -> Ordering {
+        let key = |d: &Self| (d.year, d.month, !d.is_leap_month, d.day, d.is_leap_day);
+        key(self).cmp(&key(other))
+    }
+}
+
+impl PartialOrd for TibetanDate {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Calendar for TibetanDate {
+    fn to_julian_day_number(&self) -> Result<JulianDayNumber, CalendarError> {
+        supported::<Self>(JulianDayNumber::from_legacy(from_tibetan(self)?)?)
+    }
+
+    fn from_julian_day_number(day: JulianDayNumber) -> Result<Self, CalendarError> where Self: Sized {
+        to_tibetan(supported::<Self>(day)?.to_legacy())
+    }
+
+    // from the first day of year 1 to u32::MAX * 365 days later
+    fn supported_range() -> RangeInclusive<JulianDayNumber> where Self: Sized {
+        JulianDayNumber::new(1675015)..=JulianDayNumber::new(1567664737690)
+    }
+
+    // month is either a month number ("3", "Month 3") or a leap month ("Leap 3",
+    // "Leap Month 3"). Dates created this way always refer to the first of
+    // two repeated days; set is_leap_day on the result to pick the second one
+    fn new(day: u32, month: String, year: i32, _era: String) -> Result<Self, CalendarError> where Self: Sized {
+        let day: u8 = match day.try_into() {
+            Ok(n) => n,
+            Err(_) => return Err(CalendarError::DayOutOfRange { day, max: 30 }),
+        };
+
+        let year: u32 = match year.try_into() {
+            Ok(n) => n,
+            Err(_) => return Err(CalendarError::InvalidInput),
+        };
+
+        let mut month_str = month.trim();
+        let is_leap_month = month_str.starts_with("Leap");
+        if is_leap_month {
+            month_str = month_str.trim_start_matches("Leap").trim_start();
+        }
+        month_str = month_str.trim_start_matches("Month").trim_start();
+
+        let month: u8 = match month_str.parse() {
+            Ok(n) => n,
+            Err(_) => return Err(CalendarError::UnknownMonth(month)),
+        };
+
+        Ok(TibetanDate {
+            year,
+            month,
+            is_leap_month,
+            day,
+            is_leap_day: false,
+        })
+    }
+}
+
+impl std::fmt::Display for TibetanDate {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        let month_prefix = match self.is_leap_month {
+            true => "leap ",
+            false => "",
+        };
+        let day_prefix = match self.is_leap_day {
+            true => "leap ",
+            false => "",
+        };
+        write!(
+            f,
+            "year: {}, month: {}{}, day: {}{}",
+            self.year, month_prefix, self.month, day_prefix, self.day
+        )
+    }
+}
+
+// exact fractions are needed because the day boundaries are found by taking
+// the floor of sums which can land very close to whole numbers
+#[derive(Clone, Copy)]
+struct Fraction {
+    num: i128,
+    den: i128,
+}
+
+impl Fraction {
+    fn new(num: i128, den: i128) -> Fraction {
+        let mut a = num.abs();
+        let mut b = den.abs();
+        while b != 0 {
+            let t = a % b;
+            a = b;
+            b = t;
+        }
+        let gcd = if a == 0 { 1 } else { a };
+        let sign = if den < 0 { -1 } else { 1 };
+        Fraction {
+            num: sign * num / gcd,
+            den: sign * den / gcd,
+        }
+    }
+
+    fn int(n: i128) -> Fraction {
+        Fraction { num: n, den: 1 }
+    }
+
+    fn add(self, other: Fraction) -> Fraction {
+        Fraction::new(self.num * other.den + other.num * self.den, self.den * other.den)
+    }
+
+    fn sub(self, other: Fraction) -> Fraction {
+        self.add(other.neg())
+    }
+
+    fn mul(self, other: Fraction) -> Fraction {
+        Fraction::new(self.num * other.num, self.den * other.den)
+    }
+
+    fn neg(self) -> Fraction {
+        Fraction { num: -self.num, den: self.den }
+    }
+
+    fn floor(self) -> i128 {
+        self.num.div_euclid(self.den)
+    }
+
+    fn ceil(self) -> i128 {
+        -self.neg().floor()
+    }
+
+    // fractional part, always in [0, 1)
+    fn fract(self) -> Fraction {
+        Fraction::new(self.num.rem_euclid(self.den), self.den)
+    }
+
+    fn is_integer(self) -> bool {
+        self.den == 1
+    }
+
+    fn gt(self, other: Fraction) -> bool {
+        self.num * other.den > other.num * self.den
+    }
+}
+
+// julian day of 7 December 128 BC (Gregorian), the epoch of the Phugpa year count
+const TIBETAN_EPOCH: i128 = 1675014;
+const SUN_EQUATION_SIXTIETHS: [i128; 4] = [0, 6, 10, 11];
+const MOON_EQUATION_SIXTIETHS: [i128; 8] = [0, 5, 10, 15, 19, 22, 24, 25];
+
+fn sun_equation(alpha: Fraction) -> Fraction {
+    if alpha.gt(Fraction::int(6)) {
+        return sun_equation(alpha.sub(Fraction::int(6))).neg();
+    }
+    if alpha.gt(Fraction::int(3)) {
+        return sun_equation(Fraction::int(6).sub(alpha));
+    }
+    if alpha.is_integer() {
+        return Fraction::new(SUN_EQUATION_SIXTIETHS[alpha.num as usize], 60);
+    }
+    // linear interpolation between table entries
+    let upper = sun_equation(Fraction::int(alpha.ceil()));
+    let lower = sun_equation(Fraction::int(alpha.floor()));
+    alpha.fract().mul(upper).add(alpha.neg().fract().mul(lower))
+}
+
+fn moon_equation(alpha: Fraction) -> Fraction {
+    if alpha.gt(Fraction::int(14)) {
+        return moon_equation(alpha.sub(Fraction::int(14))).neg();
+    }
+    if alpha.gt(Fraction::int(7)) {
+        return moon_equation(Fraction::int(14).sub(alpha));
+    }
+    if alpha.is_integer() {
+        return Fraction::new(MOON_EQUATION_SIXTIETHS[alpha.num as usize], 60);
+    }
+    let upper = moon_equation(Fraction::int(alpha.ceil()));
+    let lower = moon_equation(Fraction::int(alpha.floor()));
+    alpha.fract().mul(upper).add(alpha.neg().fract().mul(lower))
+}
+
+// julian day on which the given (not necessarily valid) date falls. Skipped
+// days give the same julian day as the day after them
+fn tibetan_julian_day(year: i128, month: i128, is_leap_month: bool, day: i128, is_leap_day: bool) -> i128 {
+    let mut months = (804 * (year - 1) + 67 * month + 64).div_euclid(65);
+    if is_leap_month {
+        months -= 1;
+    }
+    let days = 30 * months + day;
+
+    let mut mean = Fraction::new(days * 11135 + 1071 * 7, 11312).sub(Fraction::int(30));
+    if !is_leap_day {
+        mean = mean.sub(Fraction::int(1));
+    }
+
+    let solar_anomaly = Fraction::new(days * 13 + 2117, 4824).fract();
+    let lunar_anomaly = Fraction::new(days * 3781 + 2837 * 7, 105840).fract();
+    let sun = sun_equation(Fraction::int(12).mul(solar_anomaly)).neg();
+    let moon = moon_equation(Fraction::int(28).mul(lunar_anomaly));
+
+    Fraction::int(TIBETAN_EPOCH).add(mean).add(sun).add(moon).floor()
+}
+
+pub(crate) fn to_tibetan(julian_day: i128) -> Result<TibetanDate, CalendarError> {
+    if (julian_day < TIBETAN_EPOCH) | (julian_day > TIBETAN_EPOCH + (u32::MAX as i128) * 365) {
+        return Err(CalendarError::Overflow);
+    }
+
+    // mean length of a year is 365 + 4975/18382 days
+    let years = Fraction::new((julian_day - TIBETAN_EPOCH) * 18382, 365 * 18382 + 4975).ceil();
+
+    // the estimate is either the right year or the one after it
+    let mut year0 = years;
+    while julian_day >= tibetan_julian_day(year0, 1, false, 1, false) {
+        year0 += 1;
+    }
+    year0 -= 1;
+
+    let mut month0 = 1;
+    while julian_day >= tibetan_julian_day(year0, month0, false, 1, false) {
+        month0 += 1;
+    }
+    month0 -= 1;
+
+    let estimate = julian_day - tibetan_julian_day(year0, month0, false, 1, false);
+    let mut day0 = estimate - 2;
+    while julian_day >= tibetan_julian_day(year0, month0, false, day0, false) {
+        day0 += 1;
+    }
+    day0 -= 1;
+
+    // day0 is counted from the start of the regular month, so values past 30
+    // mean we are in the leap month that shares the next month's number and
+    // values below 1 mean we are still in the previous month
+    let is_leap_month = day0 > 30;
+    let day = (day0 - 1).rem_euclid(30) + 1;
+    let month;
+    if day > day0 {
+        month = (month0 - 2).rem_euclid(12) + 1;
+    } else if is_leap_month {
+        month = month0.rem_euclid(12) + 1;
+    } else {
+        month = (month0 - 1).rem_euclid(12) + 1;
+    }
+    let year;
+    if (day > day0) & (month0 == 1) {
+        year = year0 - 1;
+    } else if is_leap_month & (month0 == 12) {
+        year = year0 + 1;
+    } else {
+        year = year0;
+    }
+
+    let is_leap_day = julian_day == tibetan_julian_day(year, month, is_leap_month, day, true);
+
+    if year < 1 {
+        return Err(CalendarError::Overflow);
+    }
+    let year: u32 = match year.try_into() {
+        Ok(n) => n,
+        Err(_) => return Err(CalendarError::Overflow),
+    };
+
+    Ok(TibetanDate {
+        year,
+        month: month as u8,
+        is_leap_month,
+        day: day as u8,
+        is_leap_day,
+    })
+}
+
+fn from_tibetan(date: &TibetanDate) -> Result<i128, CalendarError> {
+    if date.year < 1 {
+        return Err(CalendarError::InvalidInput);
+    }
+    if (date.month < 1) | (date.month > 12) {
+        return Err(CalendarError::UnknownMonth(date.month.to_string()));
+    }
+    if (date.day < 1) | (date.day > 30) {
+        return Err(CalendarError::DayOutOfRange { day: date.day as u32, max: 30 });
+    }
+    if date.is_leap_month && !tibetan_year_months(date.year as i64)?.contains(&(date.month, true)) {
+        return Err(CalendarError::MonthNotInYear {
+            month: format!("leap {}", date.month),
+            year: date.year as i64,
+        });
+    }
+
+    let julian_day = tibetan_julian_day(
+        date.year as i128,
+        date.month as i128,
+        date.is_leap_month,
+        date.day as i128,
+        date.is_leap_day,
+    );
+
+    // leap days and skipped days are only valid if converting back gives the
+    // same date
+    let round_trip = to_tibetan(julian_day)?;
+    if (round_trip.year != date.year)
+        | (round_trip.month != date.month)
+        | (round_trip.is_leap_month != date.is_leap_month)
+        | (round_trip.day != date.day)
+        | (round_trip.is_leap_day != date.is_leap_day)
+    {
+        return Err(CalendarError::InvalidInput);
+    }
+
+    Ok(julian_day)
+}
+
+// months of the year in order as (month, is_leap_month). A leap month comes
+// before the regular month with the same number
+fn tibetan_year_months(year: i64) -> Result<Vec<(u8, bool)>, CalendarError> {
+    if year < 1 {
+        return Err(CalendarError::Overflow);
+    }
+    let mut months = Vec::new();
+    for month in 1..=12 {
+        // the middle of a leap month converts back to a leap month only if
+        // the year has one
+        let middle = to_tibetan(tibetan_julian_day(year as i128, month as i128, true, 15, false))?;
+        if middle.is_leap_month & (middle.month == month) & (middle.year as i64 == year) {
+            months.push((month, true));
+        }
+        months.push((month, false));
+    }
+    Ok(months)
+}
+
+/*
+Days are counted by their number, so a day of the month can be skipped. The
+overflow policy decides what happens when the result lands on one, in the
+same way as for a day past the end of the month. Repeated days give the
+first of the two
+ */
+impl CalendarArithmetic for TibetanDate {
+    fn fields(&self) -> (i64, u8, u8) {
+        let year = self.year as i64;
+        let position = match tibetan_year_months(year) {
+            Ok(months) => months.iter().position(|&m| m == (self.month, self.is_leap_month)).unwrap_or(0) + 1,
+            Err(_) => self.month as usize,
+        };
+        (year, position as u8, self.day)
+    }
+
+    fn from_fields(year: i64, month: u8, day: u8) -> Result<Self, CalendarError> {
+        let (month, is_leap_month) = match tibetan_year_months(year)?.get((month as usize).wrapping_sub(1)) {
+            Some(&m) => m,
+            None => return Err(CalendarError::UnknownMonth(month.to_string())),
+        };
+        Ok(TibetanDate {
+            year: year as u32,
+            month,
+            is_leap_month,
+            day,
+            is_leap_day: false,
+        })
+    }
+
+    // day numbers can be skipped or repeated
+    fn month_days(date: &Self, julian_day: JulianDayNumber) -> Result<(JulianDayNumber, Vec<Self>), CalendarError> {
+        month_days_by_julian_day(julian_day, |d: &TibetanDate| {
+            (d.year, d.month, d.is_leap_month) == (date.year, date.month, date.is_leap_month)
+        })
+    }
+
+    fn is_leap_year(&self) -> bool {
+        matches!(tibetan_year_months(self.year as i64), Ok(months) if months.len() == 13)
+    }
+
+    fn month_count(year: i64) -> Result<u8, CalendarError> {
+        Ok(tibetan_year_months(year)?.len() as u8)
+    }
+
+    // 65 years of 12 solar months are 804 lunar months
+    const MONTH_CYCLE: Option<(i64, i64)> = Some((65, 804));
+
+    // a leap month becomes the regular month with its number in a year without it
+    fn month_in_year(from_year: i64, month: u8, to_year: i64) -> Result<u8, CalendarError> {
+        let (month, is_leap_month) = match tibetan_year_months(from_year)?.get((month as usize).wrapping_sub(1)) {
+            Some(&m) => m,
+            None => return Err(CalendarError::UnknownMonth(month.to_string())),
+        };
+        let to_months = tibetan_year_months(to_year)?;
+        let position = match to_months.iter().position(|&m| m == (month, is_leap_month)) {
+            Some(i) => Some(i),
+            None => to_months.iter().position(|&m| m == (month, false)),
+        };
+        match position {
+            Some(i) => Ok(i as u8 + 1),
+            None => Err(CalendarError::InvalidInput),
+        }
+    }
+
+    fn month_length(_year: i64, _month: u8) -> Result<u8, CalendarError> {
+        Ok(30)
+    }
+}
+
+impl DateFormat for TibetanDate {
+    const CALENDAR: &'static str = "Tibetan";
+
+    // "Month 3" or "Leap Month 3", as taken by new
+    fn month_name(&self) -> String {
+        match self.is_leap_month {
+            true => format!("Leap Month {}", self.month),
+            false => format!("Month {}", self.month),
+        }
+    }
+}
+
+impl From<TibetanDate> for GenericDate {
+    fn from(date: TibetanDate) -> GenericDate {
+        // names: month 1 (1) ... month 12 (12), leap month 1 (13) ... leap month 12 (24)
+        let month_name = match date.is_leap_month {
+            true => date.month + 12,
+            false => date.month,
+        };
+
+        GenericDate {
+            is_valid: true,
+            is_not_overflow: true,
+            era: false,
+            year: date.year,
+            month_name,
+            day: date.day,
+            is_leap_day: date.is_leap_day,
+            weekday: 0,
+            error: String::new(),
+            month_text: String::new(),
+            weekday_text: String::new(),
+            era_text: String::new(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::julian_gregorian::GregorianCalendar;
+
+    fn gregorian(day: u32, month: &str, year: i32) -> JulianDayNumber {
+        GregorianCalendar::new(day, month.into(), year, "AD".into())
+            .unwrap()
+            .to_julian_day_number()
+            .unwrap()
+    }
+
+    fn tibetan(year: u32, month: u8, is_leap_month: bool, day: u8, is_leap_day: bool) -> TibetanDate {
+        TibetanDate { year, month, is_leap_month, day, is_leap_day }
+    }
+
+    #[test]
+    fn losar() {
+        let new_years = [
+            (2149, (3, "March", 2022)),
+            (2150, (21, "February", 2023)),
+            (2151, (10, "February", 2024)),
+            (2152, (28, "February", 2025)),
+            (2153, (18, "February", 2026)),
+        ];
+        for &(year, (day, month, g_year)) in new_years.iter() {
+            let losar = tibetan(year, 1, false, 1, false);
+            assert_eq!(losar.to_julian_day_number(), Ok(gregorian(day, month, g_year)), "{}", year);
+            assert_eq!(TibetanDate::from_julian_day_number(gregorian(day, month, g_year)), Ok(losar));
+        }
+        // Saga Dawa Duchen, the 15th of the 4th month
+        assert_eq!(tibetan(2151, 4, false, 15, false).to_julian_day_number(), Ok(gregorian(23, "May", 2024)));
+    }
+
+    #[test]
+    fn leap_month() {
+        // 2151 has 384 days, with two 6th months, the leap month first
+        assert_eq!(tibetan_year_months(2151).unwrap().len(), 13);
+        assert_eq!(tibetan_year_months(2152).unwrap().len(), 12);
+        assert_eq!(tibetan(2151, 6, true, 1, false).to_julian_day_number(), Ok(gregorian(6, "July", 2024)));
+        assert_eq!(tibetan(2151, 6, false, 1, false).to_julian_day_number(), Ok(gregorian(5, "August", 2024)));
+        assert!(tibetan(2151, 6, true, 1, false) < tibetan(2151, 6, false, 1, false));
+        assert!(tibetan(2152, 6, true, 1, false).to_julian_day_number().is_err());
+    }
+
+    #[test]
+    fn skipped_and_repeated_days() {
+        // the 4th of the 2nd month of 2151 is skipped
+        assert_eq!(TibetanDate::from_julian_day_number(gregorian(13, "March", 2024)), Ok(tibetan(2151, 2, false, 3, false)));
+        assert_eq!(TibetanDate::from_julian_day_number(gregorian(14, "March", 2024)), Ok(tibetan(2151, 2, false, 5, false)));
+        assert!(tibetan(2151, 2, false, 4, false).to_julian_day_number().is_err());
+
+        // and the 14th repeated
+        assert_eq!(tibetan(2151, 2, false, 14, false).to_julian_day_number(), Ok(gregorian(23, "March", 2024)));
+        assert_eq!(tibetan(2151, 2, false, 14, true).to_julian_day_number(), Ok(gregorian(24, "March", 2024)));
+        assert_eq!(TibetanDate::from_julian_day_number(gregorian(24, "March", 2024)), Ok(tibetan(2151, 2, false, 14, true)));
+        assert!(tibetan(2151, 2, false, 13, true).to_julian_day_number().is_err());
+    }
+
+    #[test]
+    fn every_day_converts_back() {
+        for day in gregorian(1, "January", 2000).value()..gregorian(1, "January", 2030).value() {
+            let day = JulianDayNumber::new(day);
+            assert_eq!(TibetanDate::from_julian_day_number(day).unwrap().to_julian_day_number(), Ok(day));
+        }
+    }
+}
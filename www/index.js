@@ -3,57 +3,55 @@ import * as wasm from "calendar";
 const Calendars = {
     Gregorian: "Gregorian",
     Julian: "Julian",
-    Hebrew: "Hebrew"
+    Hebrew: "Hebrew",
+    Tibetan: "Tibetan",
+    Babylonian: "Babylonian",
+    Nepali: "Nepali",
+    Bengali: "Bengali",
+    Tamil: "Tamil",
+    Darian: "Darian"
 }
 
 const date = wasm.js_api(Calendars.Gregorian, Calendars.Julian, 11, "September", 2022, "AD");
-console.log(dateToString(date, Calendars.Julian));
+console.log(dateToString(date));
 
 const selectCal = document.getElementById("selectCal");
 selectCal.addEventListener("change", calSelect);
 
+// the month, weekday and era names are shown in this language
+const selectLocale = document.getElementById("selectLocale");
+selectLocale.addEventListener("change", calSelect);
 
-// Converts a date object to a string
-function dateToString(date, cal) {
-    let output = '';
 
-    if(date.get_is_valid() == false){
-        return 'invalid date';
+// Converts a date object to a string, using the names it was converted with
+function dateToString(date) {
+    let output = '';
 
-    }
-    if (date.get_is_not_overflow() == false) {
-        return 'date too early or late';
+    // the error says what was wrong, e.g. which day range the month has
+    if ((date.get_is_valid() == false) || (date.get_is_not_overflow() == false)) {
+        return date.get_error();
     }
 
-    const RomanMonths = ["January", "February", "March", "April", "May", "June", "July", "August", "September", "October", "November", "December"];
-    const HebrewMonths = ["Tishrei", "Chesvan", "Kislev", "Tevet", "Shevat", "Adar", "Nisan", "Iyar", "Sivan", "Tammuz", "Av", "Elul", "Adar I", "Adar II"];
+    // Add Weekday:
+    output += date.get_weekday_text() + ', ';
 
     // Add Day:
     output += date.get_day();
+    if (date.get_is_leap_day() == true) {
+        // the second of two days with the same number
+        output += ' (leap day)';
+    }
 
     // Add Month:
-    output += ' ';
-    if ((cal == Calendars.Gregorian) || (cal == Calendars.Julian)) {
-        // takes the month name index and converts it to the actual month name
-        output += RomanMonths[date.get_month_name() - 1];
-    }
-    if (cal == Calendars.Hebrew) {
-        // takes the month name index and converts it to the actual month name
-        output += HebrewMonths[date.get_month_name() - 1];
-    }
+    output += ' ' + date.get_month_text();
 
     // Add Year:
     output += ' ';
     output += date.get_year();
 
     // Add Era (if applicable):
-    if ((cal == Calendars.Gregorian) || (cal == Calendars.Julian)) {
-        output += ' '
-        if (date.get_era() == true) {
-            output += "AD"
-        } else {
-            output += "BC"
-        }
+    if (date.get_era_text() != '') {
+        output += ' ' + date.get_era_text();
     }
     return output;
 }
@@ -74,7 +72,7 @@ function parseForm() {
     }
 
     var era;
-    if (fromCal == Calendars.Gregorian || fromCal == Calendars.Julian) {
+    if (fromCal == Calendars.Gregorian || fromCal == Calendars.Julian || fromCal == Calendars.Babylonian) {
         era = document.getElementById("era").value;
         if(era == ""){
             return incompleteFormMsg;
@@ -83,8 +81,25 @@ function parseForm() {
         era = "";
     }
 
-    const date = wasm.js_api(fromCal, toCal, day, month, year, era);
-    const dateString = dateToString(wasm.js_api(fromCal, toCal, day, month, year, era), toCal);
+    const locale = document.getElementById("selectLocale").value;
+    let dateString = dateToString(wasm.js_api_localized(fromCal, toCal, day, month, year, era, locale));
+
+    // show the cyclic counts of the same day next to the converted date
+    const cyclic = wasm.js_cyclic_api(fromCal, day, month, year, era);
+    if (cyclic.get_is_valid() && cyclic.get_is_not_overflow()) {
+        dateString += ' (Xiuhpohualli: ' + cyclic.get_xihuitl();
+        dateString += ', Tonalpohualli: ' + cyclic.get_tonalpohualli();
+        dateString += ', Akan: ' + cyclic.get_akan() + ')';
+    }
+
+    // and the Torah reading of the week when converting to the Hebrew calendar
+    if (toCal == Calendars.Hebrew) {
+        const israel = document.getElementById("inIsrael").checked;
+        const week = wasm.js_parashah(fromCal, day, month, year, era, israel);
+        if (week.get_is_valid() && week.get_is_not_overflow()) {
+            dateString += ', Torah reading: ' + week.get_name();
+        }
+    }
 
     return dateString;
 }
@@ -115,6 +130,8 @@ function calSelect() {
 
 // shows the date conversion form
 function showForm(val) {
+    const locale = document.getElementById("selectLocale").value;
+
     const previousForm = document.getElementById("selectDate");
     if (previousForm != null) {
         previousForm.remove();
@@ -123,7 +140,7 @@ function showForm(val) {
     var form = document.createElement("div");
     form.id = "selectDate";
 
-    if ((val == "Julian") || (val == "Gregorian")) {
+    if ((val == "Julian") || (val == "Gregorian") || (val == "Babylonian")) {
         var eraLabel = document.createElement("label");
         eraLabel.for = "era";
         eraLabel.appendChild(document.createTextNode("Era: "));
@@ -138,17 +155,16 @@ function showForm(val) {
         blankEra.value = "";
         era.appendChild(blankEra);
 
-        var BC = document.createElement("option");
-        BC.name = "BC";
-        BC.value = "BC";
-        BC.appendChild(document.createTextNode("BC"));
-        era.appendChild(BC);
-
-        var AD = document.createElement("option");
-        AD.name = "AD";
-        AD.value = "AD";
-        AD.appendChild(document.createTextNode("AD"));
-        era.appendChild(AD);
+        // the values are the English names, which js_api takes
+        const eraValues = wasm.js_era_names(val, "en");
+        const eraNames = wasm.js_era_names(val, locale);
+        for (let eraIndex in eraValues) {
+            var eraOption = document.createElement("option");
+            eraOption.name = eraValues[eraIndex];
+            eraOption.value = eraValues[eraIndex];
+            eraOption.appendChild(document.createTextNode(eraNames[eraIndex]));
+            era.appendChild(eraOption);
+        }
 
         form.appendChild(era);
     }
@@ -173,25 +189,15 @@ function showForm(val) {
     var month = document.createElement("select");
     month.id = "month";
     month.name = "month";
-    var monthArray = Array();
-    monthArray.push("");
-    if (val == "Hebrew") {        
-        monthArray.push("Tishrei", "Chesvan", "Kislev", "Tevet", "Shvat", "Adar I", "Adar / Adar II", "Nisan", "Iyyar", "Sivan", "Tammuz", "Av", "Elul");
-    } else if (val == "Julian") {
-        monthArray.push("January", "February", "March", "April", "May", "June", "July", "August", "September", "October", "November", "December");
-    } else {
-        monthArray.push("January", "February", "March", "April", "May", "June", "July", "August", "September", "October", "November", "December");
-    }
-
-
-
-    for (let monthIndex in monthArray) {
-        var monthName = monthArray[monthIndex];
+    // the values are the English names, which js_api takes
+    const monthValues = wasm.js_month_names(val, "en");
+    const monthNames = wasm.js_month_names(val, locale);
+    month.options.add(document.createElement("option"));
+    for (let monthIndex in monthValues) {
         var monthOption = document.createElement("option");
-        monthOption.name = monthName;
-        monthOption.value = monthName;
-        monthOption.appendChild(document.createTextNode(monthName));
-        //month.appendChild(monthName);
+        monthOption.name = monthValues[monthIndex];
+        monthOption.value = monthValues[monthIndex];
+        monthOption.appendChild(document.createTextNode(monthNames[monthIndex]));
         month.options.add(monthOption);
     }
     form.append(month);